@@ -1,20 +1,27 @@
 use std::collections::BTreeMap;
 
-use crate::api::{Account, API};
+use crate::api::{Account, SpotMargin, Trade, API};
 use crate::client::Client;
 use crate::errors::BybitError;
 use crate::model::{
-    AccountInfoResponse, BatchSetCollateralCoinResponse, BorrowHistoryRequest,
-    BorrowHistoryResponse, Category, CollateralInfoResponse, FeeRateResponse,
-    RepayLiabilityResponse, SetCollateralCoinResponse, SetMarginModeResponse, SmpResponse,
-    SpotHedgingResponse, TransactionLogRequest, TransactionLogResponse, UTAResponse,
-    WalletResponse,
+    AccountInfo, AccountInfoResponse, AccountType, BatchSetCollateralCoinResponse,
+    BorrowHistoryRequest, BorrowHistoryResponse, BorrowQuotaResponse, Category,
+    CollateralInfoResponse, FeeRate, FeeRateResponse, RepayLiabilityResponse,
+    SetCollateralCoinResponse, SetMarginModeResponse, Side, SmpResponse, SpotHedgingResponse,
+    SpotLeverageResponse, SpotMarginModeResponse, SpotMarginStateResponse, TransactionLogRequest,
+    TransactionLogResponse, UTAResponse, WalletResponse,
 };
 
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use crate::util::{build_json_request, build_request, date_to_milliseconds};
 
+/// Spot margin leverage Bybit accepts, see `AccountManager::set_spot_leverage`.
+pub const SPOT_LEVERAGE: std::ops::RangeInclusive<f64> = 2.0..=10.0;
+
 #[derive(Clone)]
 pub struct AccountManager {
     pub client: Client,
@@ -22,15 +29,16 @@ pub struct AccountManager {
 }
 
 impl AccountManager {
+    /// Retrieves the wallet balance of `account`, optionally narrowed to a set of coins.
     pub async fn get_wallet_balance(
         &self,
-        account: &str,
-        coin: Option<&str>,
+        account: AccountType,
+        coins: Option<Vec<&str>>,
     ) -> Result<WalletResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("accountType".into(), account.into());
-        if let Some(c) = coin {
-            parameters.insert("coin".into(), c.into());
+        parameters.insert("accountType".into(), account.as_str().into());
+        if let Some(c) = coins {
+            parameters.insert("coin".into(), c.join(",").into());
         }
         let request = build_request(&parameters);
         let response: WalletResponse = self
@@ -181,29 +189,40 @@ impl AccountManager {
             .await?;
         Ok(response)
     }
+    /// Retrieves the maker and taker fee rates of the account.
+    ///
+    /// Without `symbol`, every symbol of `category` is returned. `base_coin` only applies to
+    /// options, which are charged per base coin. See `FeeRateCache` to avoid refetching rates
+    /// that rarely change.
     pub async fn get_fee_rate(
         &self,
         category: Category,
-        symbol: Option<String>,
-    ) -> Result<FeeRateResponse, BybitError> {
+        symbol: Option<&str>,
+        base_coin: Option<&str>,
+    ) -> Result<Vec<FeeRate>, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
         parameters.insert("category".into(), category.as_str().into());
         if let Some(s) = symbol {
             parameters.insert("symbol".into(), s.into());
         }
+        if let Some(c) = base_coin {
+            parameters.insert("baseCoin".into(), c.into());
+        }
         let req = build_request(&parameters);
         let response: FeeRateResponse = self
             .client
-            .post_signed(
+            .get_signed(
                 API::Account(Account::FeeRate),
                 self.recv_window.into(),
                 Some(req),
             )
             .await?;
-        Ok(response)
+        Ok(response.result.list)
     }
 
-    pub async fn get_account_info(&self) -> Result<AccountInfoResponse, BybitError> {
+    /// Retrieves the account's margin mode and generation, e.g. to tell a unified trading
+    /// account from a classic one at startup.
+    pub async fn get_account_info(&self) -> Result<AccountInfo, BybitError> {
         let response: AccountInfoResponse = self
             .client
             .get_signed(
@@ -212,7 +231,7 @@ impl AccountManager {
                 None,
             )
             .await?;
-        Ok(response)
+        Ok(response.result)
     }
 
     pub async fn get_transaction_log<'a>(
@@ -315,4 +334,146 @@ impl AccountManager {
             .await?;
         Ok(response)
     }
+
+    /// Retrieves how much of a spot `symbol` can be bought or sold on `side`, with and
+    /// without spot margin borrowing. Bybit only answers this for `Category::Spot`.
+    pub async fn get_borrow_quota(
+        &self,
+        category: Category,
+        symbol: &str,
+        side: Side,
+    ) -> Result<BorrowQuotaResponse, BybitError> {
+        if !matches!(category, Category::Spot) {
+            return Err(BybitError::InvalidParams(format!(
+                "Borrow quota is only available for spot, not {}",
+                category.as_str()
+            )));
+        }
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("category".into(), category.as_str().into());
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("side".into(), side.as_str().into());
+        let request = build_request(&parameters);
+        let response: BorrowQuotaResponse = self
+            .client
+            .get_signed(
+                API::Trade(Trade::SpotBorrowCheck),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Turns spot margin trading on or off for the unified account.
+    pub async fn set_spot_margin_mode(
+        &self,
+        enabled: bool,
+    ) -> Result<SpotMarginModeResponse, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        let mode = if enabled { "1" } else { "0" };
+        parameters.insert("spotMarginMode".into(), mode.into());
+        let request = build_json_request(&parameters);
+        let response: SpotMarginModeResponse = self
+            .client
+            .post_signed(
+                API::SpotMargin(SpotMargin::SwitchMode),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Sets the spot margin leverage, which must lie within `SPOT_LEVERAGE`.
+    pub async fn set_spot_leverage(
+        &self,
+        leverage: f64,
+    ) -> Result<SpotLeverageResponse, BybitError> {
+        if !SPOT_LEVERAGE.contains(&leverage) {
+            return Err(BybitError::InvalidParams(format!(
+                "Spot leverage {} is outside {:?}",
+                leverage, SPOT_LEVERAGE
+            )));
+        }
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("leverage".into(), leverage.to_string().into());
+        let request = build_json_request(&parameters);
+        let response: SpotLeverageResponse = self
+            .client
+            .post_signed(
+                API::SpotMargin(SpotMargin::SetLeverage),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Retrieves whether spot margin trading is on and the leverage in use.
+    pub async fn get_spot_margin_state(&self) -> Result<SpotMarginStateResponse, BybitError> {
+        let response: SpotMarginStateResponse = self
+            .client
+            .get_signed(
+                API::SpotMargin(SpotMargin::State),
+                self.recv_window.into(),
+                None,
+            )
+            .await?;
+        Ok(response)
+    }
+}
+
+/// Remembers `AccountManager::get_fee_rate` answers for `ttl`, since fee tiers rarely change
+/// within a day.
+pub struct FeeRateCache {
+    account: AccountManager,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, Vec<FeeRate>)>>,
+}
+
+impl FeeRateCache {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+    pub fn new(account: AccountManager, ttl: Duration) -> Self {
+        Self {
+            account,
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Same as `AccountManager::get_fee_rate`, served from the cache while it is fresh.
+    pub async fn get_fee_rate(
+        &self,
+        category: Category,
+        symbol: Option<&str>,
+        base_coin: Option<&str>,
+    ) -> Result<Vec<FeeRate>, BybitError> {
+        let key = format!(
+            "{}:{}:{}",
+            category.as_str(),
+            symbol.unwrap_or_default(),
+            base_coin.unwrap_or_default()
+        );
+        if let Some((fetched, rates)) = self.entries.lock().unwrap().get(&key) {
+            if fetched.elapsed() < self.ttl {
+                return Ok(rates.clone());
+            }
+        }
+        let rates = self
+            .account
+            .get_fee_rate(category, symbol, base_coin)
+            .await?;
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, (Instant::now(), rates.clone()));
+        Ok(rates)
+    }
+
+    /// Forgets every cached rate, e.g. after a VIP tier change.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
 }
@@ -30,6 +30,7 @@ pub enum Public {
     Spot,
     Linear,
     Inverse,
+    Option,
 }
 
 pub enum Market {
@@ -49,6 +50,7 @@ pub enum Market {
     RiskLimit,
     DeliveryPrice,
     LongShortRatio,
+    Announcements,
 }
 
 pub enum Trade {
@@ -109,6 +111,7 @@ pub enum Asset {
     QueryTransferCoinList,
     QueryTransferSubmemberList,
     QueryAccountCoinBalance,
+    QuerySingleCoinBalance,
     QueryAssetInfo,
     QueryAllowedList,
     QueryRecord,
@@ -164,6 +167,7 @@ impl From<API> for String {
                 Market::RiskLimit => "/v5/market/risk-limit",
                 Market::DeliveryPrice => "/v5/market/delivery-price",
                 Market::LongShortRatio => "/v5/market/account-ratio",
+                Market::Announcements => "/v5/announcements/index",
             },
             API::Trade(route) => match route {
                 Trade::Place => "/v5/order/create",
@@ -214,6 +218,7 @@ impl From<API> for String {
                 Asset::SettlementRecord => "/v5/asset/settlement-record",
                 Asset::QueryAssetInfo => "/v5/asset/transfer/query-asset-info",
                 Asset::QueryAccountCoinBalance => "/v5/asset/transfer/query-account-coins-balance",
+                Asset::QuerySingleCoinBalance => "/v5/asset/transfer/query-account-coin-balance",
                 Asset::QueryTransferCoinList => "/v5/asset/transfer/query-transfer-coin-list",
                 Asset::Intertransfer => "/v5/asset/transfer/inter-transfer",
                 Asset::QueryTransferList => "/v5/asset/transfer/query-inter-transfer-list",
@@ -227,6 +232,7 @@ impl From<API> for String {
                 Asset::CancelWithdraw => "/v5/asset/withdraw/cancel",
                 Asset::QueryInfo => "/v5/asset/coin/query-info",
                 Asset::QueryRecord => "/v5/asset/deposit/query-record",
+                Asset::Deposit => "/v5/asset/deposit/query-address",
                 Asset::QuerySubmemberAddress => "/v5/asset/deposit/query-sub-member-address",
                 _ => {
                     todo!("Asset route not implemented");
@@ -234,7 +240,7 @@ impl From<API> for String {
             },
             API::SpotLeverage(route) => match route {
                 SpotLeverage::Info => "/v5/spot-lever-token/info",
-                SpotLeverage::Marketinfo => "v5/spot-lever-token/reference",
+                SpotLeverage::Marketinfo => "/v5/spot-lever-token/reference",
                 SpotLeverage::Purchase => "/v5/spot-lever-token/purchase",
                 SpotLeverage::Redeem => "/v5/spot-lever-token/redeem",
                 SpotLeverage::OrderRecord => "/v5/spot-lever-token/order-record",
@@ -264,6 +270,7 @@ impl From<WebsocketAPI> for String {
                 Public::Spot => "/public/spot",
                 Public::Linear => "/public/linear",
                 Public::Inverse => "/public/inverse",
+                Public::Option => "/public/option",
             },
             WebsocketAPI::Private => "/private",
             WebsocketAPI::TradeStream => "/trade",
@@ -272,6 +279,7 @@ impl From<WebsocketAPI> for String {
 }
 
 pub trait Bybit {
+    /// Connects to the mainnet hosts (`api.bybit.com` / `stream.bybit.com`).
     fn new(api_key: Option<String>, secret_key: Option<String>) -> Self;
 
     fn new_with_config(
@@ -279,6 +287,23 @@ pub trait Bybit {
         api_key: Option<String>,
         secret_key: Option<String>,
     ) -> Self;
+
+    /// Connects to the testnet hosts (`api-testnet.bybit.com` / `stream-testnet.bybit.com`).
+    fn new_testnet(api_key: Option<String>, secret_key: Option<String>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new_with_config(&Config::testnet(), api_key, secret_key)
+    }
+
+    /// Connects to the demo trading hosts (`api-demo.bybit.com` / `stream-demo.bybit.com`),
+    /// which take the API keys of a mainnet demo account. See `Environment::Demo`.
+    fn new_demo(api_key: Option<String>, secret_key: Option<String>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new_with_config(&Config::demo(), api_key, secret_key)
+    }
 }
 
 impl Bybit for General {
@@ -387,6 +412,7 @@ impl Bybit for Stream {
     ) -> Stream {
         Stream {
             client: Client::new(api_key, secret_key, config.ws_endpoint.to_string()),
+            active: Default::default(),
         }
     }
 }
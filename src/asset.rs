@@ -1,10 +1,19 @@
 #![allow(unused_imports, unreachable_code, unused_variables)]
 use serde_json::{json, Value};
 use crate::api::{API, Asset};
-use crate::client::Client;
+use crate::client::{paginate_all, Client, MAX_PAGES};
+use crate::errors::BybitError;
+use crate::model::{
+    AccountType, CoinBalanceResponse, DeliveryRecord, DeliveryRecordRequest,
+    DeliveryRecordResponse, DepositAddressResponse, InternalTransferRequest,
+    InternalTransferResponse, SettlementRecord, SettlementRecordRequest, SettlementRecordResponse,
+    TransferHistoryRequest, TransferHistoryResponse,
+};
 
-use crate::util::{build_json_request, build_request};
+use crate::util::{build_json_request, build_request, date_to_milliseconds, generate_uuid};
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 #[derive(Clone)]
 pub struct AssetManager {
@@ -13,5 +22,224 @@ pub struct AssetManager {
 }
 
 impl AssetManager {
-  
-  }
\ No newline at end of file
+    /// Moves funds between two account types of the same UID.
+    ///
+    /// A `transferId` UUID is generated when `req` does not carry one. The transfer is
+    /// safe to retry with the same id, so the request goes through the idempotent path.
+    /// Returns the transfer id.
+    pub async fn create_transfer<'a>(
+        &self,
+        req: InternalTransferRequest<'a>,
+    ) -> Result<String, BybitError> {
+        let transfer_id = match req.transfer_id {
+            Some(id) => id.into_owned(),
+            None => generate_uuid(),
+        };
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("transferId".into(), transfer_id.into());
+        parameters.insert("coin".into(), req.coin.into());
+        parameters.insert("amount".into(), req.amount.to_string().into());
+        parameters.insert(
+            "fromAccountType".into(),
+            req.from_account_type.as_str().into(),
+        );
+        parameters.insert("toAccountType".into(), req.to_account_type.as_str().into());
+        let request = build_json_request(&parameters);
+        let response: InternalTransferResponse = self
+            .client
+            .post_signed_idempotent(
+                API::Asset(Asset::Intertransfer),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.result.transfer_id)
+    }
+
+    /// Lists internal transfers, newest first. Set `cursor` to continue from a previous page.
+    pub async fn get_transfer_history<'a>(
+        &self,
+        req: TransferHistoryRequest<'a>,
+    ) -> Result<TransferHistoryResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        if let Some(v) = req.transfer_id {
+            parameters.insert("transferId".into(), v.into());
+        }
+        if let Some(v) = req.coin {
+            parameters.insert("coin".into(), v.into());
+        }
+        if let Some(v) = req.status {
+            parameters.insert("status".into(), v.into());
+        }
+        if let Some(v) = req.start_time {
+            parameters.insert("startTime".into(), date_to_milliseconds(&v).to_string());
+        }
+        if let Some(v) = req.end_time {
+            parameters.insert("endTime".into(), date_to_milliseconds(&v).to_string());
+        }
+        if let Some(v) = req.limit {
+            parameters.insert("limit".into(), v.to_string());
+        }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
+        let request = build_request(&parameters);
+        let response: TransferHistoryResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::QueryTransferList),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Retrieves the master deposit addresses of `coin`, one entry per chain, or only the
+    /// entry for `chain` when given.
+    pub async fn get_deposit_address(
+        &self,
+        coin: &str,
+        chain: Option<&str>,
+    ) -> Result<DepositAddressResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("coin".into(), coin.into());
+        if let Some(v) = chain {
+            parameters.insert("chainType".into(), v.into());
+        }
+        let request = build_request(&parameters);
+        let response: DepositAddressResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::Deposit),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Retrieves the balance of a single coin in `account`.
+    pub async fn get_coin_balance(
+        &self,
+        account: AccountType,
+        coin: &str,
+    ) -> Result<CoinBalanceResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("accountType".into(), account.as_str().into());
+        parameters.insert("coin".into(), coin.into());
+        let request = build_request(&parameters);
+        let response: CoinBalanceResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::QuerySingleCoinBalance),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Lists the deliveries of expired options and dated futures, newest first. Set `cursor`
+    /// to continue from a previous page.
+    pub async fn get_delivery_record<'a>(
+        &self,
+        req: DeliveryRecordRequest<'a>,
+    ) -> Result<DeliveryRecordResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), req.category.as_str().into());
+        if let Some(v) = req.symbol {
+            parameters.insert("symbol".into(), v.into());
+        }
+        if let Some(v) = req.exp_date {
+            parameters.insert("expDate".into(), v.into());
+        }
+        if let Some(v) = req.start_time {
+            parameters.insert("startTime".into(), date_to_milliseconds(&v).to_string());
+        }
+        if let Some(v) = req.end_time {
+            parameters.insert("endTime".into(), date_to_milliseconds(&v).to_string());
+        }
+        if let Some(v) = req.limit {
+            parameters.insert("limit".into(), v.to_string());
+        }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
+        let request = build_request(&parameters);
+        let response: DeliveryRecordResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::DeliveryRecord),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Fetches every page of `get_delivery_record`, starting at `req.cursor`.
+    pub async fn get_all_delivery_records<'a>(
+        &self,
+        req: DeliveryRecordRequest<'a>,
+    ) -> Result<Vec<DeliveryRecord>, BybitError> {
+        paginate_all(MAX_PAGES, |cursor| {
+            let page = DeliveryRecordRequest {
+                cursor: cursor.map(Cow::Owned).or(req.cursor.clone()),
+                ..req.clone()
+            };
+            async move { Ok(self.get_delivery_record(page).await?.result.into_page()) }
+        })
+        .await
+    }
+
+    /// Lists the session settlements of USDC perpetuals, newest first. Set `cursor` to
+    /// continue from a previous page.
+    pub async fn get_settlement_record<'a>(
+        &self,
+        req: SettlementRecordRequest<'a>,
+    ) -> Result<SettlementRecordResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), req.category.as_str().into());
+        if let Some(v) = req.symbol {
+            parameters.insert("symbol".into(), v.into());
+        }
+        if let Some(v) = req.start_time {
+            parameters.insert("startTime".into(), date_to_milliseconds(&v).to_string());
+        }
+        if let Some(v) = req.end_time {
+            parameters.insert("endTime".into(), date_to_milliseconds(&v).to_string());
+        }
+        if let Some(v) = req.limit {
+            parameters.insert("limit".into(), v.to_string());
+        }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
+        let request = build_request(&parameters);
+        let response: SettlementRecordResponse = self
+            .client
+            .get_signed(
+                API::Asset(Asset::SettlementRecord),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Fetches every page of `get_settlement_record`, starting at `req.cursor`.
+    pub async fn get_all_settlement_records<'a>(
+        &self,
+        req: SettlementRecordRequest<'a>,
+    ) -> Result<Vec<SettlementRecord>, BybitError> {
+        paginate_all(MAX_PAGES, |cursor| {
+            let page = SettlementRecordRequest {
+                cursor: cursor.map(Cow::Owned).or(req.cursor.clone()),
+                ..req.clone()
+            };
+            async move { Ok(self.get_settlement_record(page).await?.result.into_page()) }
+        })
+        .await
+    }
+}
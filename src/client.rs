@@ -1,42 +1,319 @@
 use tokio::net::TcpStream;
 
-use crate::api::{WebsocketAPI, API};
+use crate::api::{Market, WebsocketAPI, API};
+use crate::config::{Environment, RateLimitConfig, RetryConfig};
 use crate::errors::{BybitContentError, BybitError};
+use crate::model::ServerTimeResponse;
+use crate::proxy::{Bridge, Proxy};
+use crate::rate_limit::{EndpointGroup, RateLimitStatus, RateLimiter};
 use crate::util::{generate_random_uid, get_timestamp};
 use hex::encode as hex_encode;
 use hmac::{Hmac, Mac};
+use openssl::base64;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
 use reqwest::{
-    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, USER_AGENT},
-    Client as ReqwestClient, Response as ReqwestResponse, StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, REFERER, USER_AGENT},
+    Client as ReqwestClient, ClientBuilder, RequestBuilder, Response as ReqwestResponse,
+    StatusCode,
 };
 
 use futures::sink::SinkExt;
 use serde::de::DeserializeOwned;
 use serde_json::json;
 use sha2::Sha256;
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio_tungstenite::WebSocketStream;
-use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage, MaybeTlsStream};
+use tokio_tungstenite::{
+    client_async_tls_with_config, connect_async_with_config, tungstenite::Message as WsMessage,
+    MaybeTlsStream,
+};
 use url::Url as WsUrl;
 
+pub use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+
+/// API key and the secret used to sign requests with it.
+///
+/// Bybit issues either HMAC keys, which come with a shared secret, or RSA keys, for
+/// which the user generates the key pair and keeps the private key as a PEM.
+#[derive(Clone)]
+pub enum Credentials {
+    Hmac { key: String, secret: String },
+    Rsa { key: String, pem: String },
+}
+
+impl Credentials {
+    pub fn api_key(&self) -> &str {
+        match self {
+            Credentials::Hmac { key, .. } | Credentials::Rsa { key, .. } => key,
+        }
+    }
+
+    /// Signs a preimage: hex encoded HMAC-SHA256 for HMAC keys, base64 encoded
+    /// RSA-SHA256 (PKCS#1 v1.5) for RSA keys.
+    pub fn sign(&self, preimage: &str) -> Result<String, BybitError> {
+        match self {
+            Credentials::Hmac { secret, .. } => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+                mac.update(preimage.as_bytes());
+                Ok(hex_encode(mac.finalize().into_bytes()))
+            }
+            Credentials::Rsa { pem, .. } => {
+                let pkey = PKey::private_key_from_pem(pem.as_bytes())?;
+                let mut signer = Signer::new(MessageDigest::sha256(), &pkey)?;
+                signer.update(preimage.as_bytes())?;
+                Ok(base64::encode_block(&signer.sign_to_vec()?))
+            }
+        }
+    }
+}
+
+/// Signs and sends REST requests, and opens websocket connections.
+///
+/// A client owns one `reqwest` connection pool, shared by its clones, so
+/// back-to-back calls reuse an open keep-alive connection instead of paying for a
+/// new TCP and TLS handshake each time. Every request is bounded by a timeout,
+/// [`Client::DEFAULT_TIMEOUT`] unless changed with [`Client::with_timeout`].
 #[derive(Clone)]
 pub struct Client {
-    api_key: String,
-    secret_key: String,
+    /// The local bridge serving a SOCKS5 proxy to `inner_client`, shared by clones.
+    _bridge: Option<Arc<Bridge>>,
+    credentials: Credentials,
+    /// Extra headers sent with every REST request, see `with_header`.
+    headers: HeaderMap,
     host: String,
     inner_client: ReqwestClient,
+    proxy: Option<Proxy>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Limit headers of the latest REST response, shared by clones.
+    rate_limit_status: Arc<Mutex<Option<RateLimitStatus>>>,
+    retry: Option<RetryConfig>,
+    timeout: Duration,
+    /// Milliseconds to add to the local clock to get Bybit's, set by `sync_time`.
+    time_offset: Arc<AtomicI64>,
+    ws_config: WebSocketConfig,
+    ws_read_timeout: Option<Duration>,
 }
 
 impl Client {
+    /// Time a REST request may take, including reading the response body.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// How long an idle pooled connection is kept open for reuse.
+    const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
     pub fn new(api_key: Option<String>, secret_key: Option<String>, host: String) -> Self {
-        let inner_client = ReqwestClient::builder()
+        let credentials = Credentials::Hmac {
+            key: api_key.unwrap_or_default(),
+            secret: secret_key.unwrap_or_default(),
+        };
+        Self::with_credentials(credentials, host)
+    }
+
+    /// Creates a REST client for demo trading, see `Environment::Demo`. Requests are signed
+    /// exactly as on mainnet, with the keys of the demo account.
+    pub fn demo(api_key: Option<String>, secret_key: Option<String>) -> Self {
+        Self::new(
+            api_key,
+            secret_key,
+            Environment::Demo.rest_api_endpoint().to_string(),
+        )
+    }
+
+    /// Creates a client for either an HMAC or an RSA API key.
+    pub fn with_credentials(credentials: Credentials, host: String) -> Self {
+        let inner_client = Self::http_client()
             .build()
             .expect("Failed to build reqwest client");
 
         Client {
-            api_key: api_key.unwrap_or_default(),
-            secret_key: secret_key.unwrap_or_default(),
+            _bridge: None,
+            credentials,
+            headers: HeaderMap::new(),
             host,
             inner_client,
+            proxy: None,
+            rate_limiter: None,
+            rate_limit_status: Arc::default(),
+            retry: None,
+            timeout: Self::DEFAULT_TIMEOUT,
+            time_offset: Arc::new(AtomicI64::new(0)),
+            ws_config: WebSocketConfig::default(),
+            ws_read_timeout: None,
+        }
+    }
+
+    /// The REST or websocket host this client sends to.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Measures the offset between the local clock and Bybit's server time and applies
+    /// it to the timestamps of all later signed requests, on this client and its clones.
+    ///
+    /// Call this at startup, and periodically on machines whose clock drifts, to avoid
+    /// `TIMESTAMP_ERROR` (10002) rejections. Returns the offset in milliseconds.
+    pub async fn sync_time(&self) -> Result<i64, BybitError> {
+        let sent = get_timestamp() as i64;
+        let response: ServerTimeResponse = self.get(API::Market(Market::Time), None).await?;
+        let received = get_timestamp() as i64;
+        // Assume the server read its clock halfway through the round trip.
+        let server_time = (response.result.time_nano / 1_000_000) as i64;
+        let offset = server_time - (sent + received) / 2;
+        self.time_offset.store(offset, Ordering::Relaxed);
+        Ok(offset)
+    }
+
+    /// The local time in milliseconds, corrected by the offset from `sync_time`.
+    pub fn timestamp(&self) -> u64 {
+        (get_timestamp() as i64 + self.time_offset.load(Ordering::Relaxed)) as u64
+    }
+
+    /// Retries GET requests, and requests sent with `post_signed_idempotent`, on
+    /// transient failures.
+    pub fn with_retry(self, config: RetryConfig) -> Self {
+        Client {
+            retry: Some(config),
+            ..self
+        }
+    }
+
+    /// Routes REST requests and websocket connections through a proxy, given as an
+    /// `http://`, `socks5://` or `socks5h://` URL with optional `user:pass@` auth.
+    pub fn with_proxy(self, url: &str) -> Result<Self, BybitError> {
+        let proxy = Proxy::parse(url)?;
+        let (reqwest_proxy, bridge) = proxy.reqwest_proxy(&self.host)?;
+        let inner_client = Self::http_client().proxy(reqwest_proxy).build()?;
+        Ok(Client {
+            _bridge: bridge,
+            inner_client,
+            proxy: Some(proxy),
+            ..self
+        })
+    }
+
+    /// Sets the timeout for REST requests. A request that takes longer fails with
+    /// `BybitError::Timeout`, which `RetryConfig` treats as transient.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Client { timeout, ..self }
+    }
+
+    /// Sets the limits of websocket connections opened by `wss_connect`.
+    ///
+    /// A message larger than `max_message_size`, or a frame larger than `max_frame_size`,
+    /// fails the read with `BybitError::Tungstenite(Error::Capacity(..))` and ends the
+    /// connection. The defaults (64 MiB and 16 MiB) leave ample room for depth-500 order book
+    /// snapshots and large ticker batches; raise them if such errors show up on reads.
+    pub fn with_ws_config(self, ws_config: WebSocketConfig) -> Self {
+        Client { ws_config, ..self }
+    }
+
+    /// Detects half-open websocket connections. When nothing is received for `timeout`, the
+    /// event loop sends a ping; when the ping is not answered within another `timeout`, the
+    /// connection fails with `BybitError::ConnectionClosed`, which reconnects subscriptions that
+    /// have a `ReconnectConfig`. Off by default.
+    pub fn with_ws_read_timeout(self, timeout: Duration) -> Self {
+        Client {
+            ws_read_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Sends the header `name: value` with every REST request, replacing a header of the same
+    /// name the client would otherwise send, such as `User-Agent`.
+    ///
+    /// Headers are not part of the request signature. The `X-BAPI-*` headers that carry it
+    /// cannot be set here and fail with `BybitError::InvalidParams`, as do malformed names and
+    /// values.
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, BybitError> {
+        let invalid = |reason: &str| BybitError::InvalidParams(format!("Header {name}: {reason}"));
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid("invalid name"))?;
+        if name.as_str().starts_with("x-bapi-") {
+            return Err(invalid("reserved for request signing"));
+        }
+        let value = HeaderValue::from_str(value).map_err(|_| invalid("invalid value"))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Attributes the client's orders to a Bybit broker program account by sending its broker
+    /// id in the `Referer` header.
+    pub fn with_broker_id(self, broker_id: &str) -> Result<Self, BybitError> {
+        self.with_header(REFERER.as_str(), broker_id)
+    }
+
+    pub(crate) fn ws_read_timeout(&self) -> Option<Duration> {
+        self.ws_read_timeout
+    }
+
+    fn http_client() -> ClientBuilder {
+        ReqwestClient::builder()
+            .pool_idle_timeout(Self::POOL_IDLE_TIMEOUT)
+            .tcp_keepalive(Self::POOL_IDLE_TIMEOUT)
+            .tcp_nodelay(true)
+    }
+
+    /// Sends a request with the client's timeout and extra headers applied.
+    async fn send(&self, request: RequestBuilder) -> Result<ReqwestResponse, BybitError> {
+        request
+            .headers(self.headers.clone())
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|err| self.request_error(err))
+    }
+
+    fn request_error(&self, err: reqwest::Error) -> BybitError {
+        if err.is_timeout() {
+            BybitError::Timeout(self.timeout)
+        } else {
+            BybitError::ReqError(err)
+        }
+    }
+
+    async fn retrying<T, F, Fut>(&self, mut send: F) -> Result<T, BybitError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, BybitError>>,
+    {
+        let Some(retry) = &self.retry else {
+            return send().await;
+        };
+        let mut attempt = 0;
+        loop {
+            match send().await {
+                Err(err) if attempt + 1 < retry.max_attempts && retry.should_retry(&err) => {
+                    tokio::time::sleep(retry.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Enables the client-side rate limiter. Clones of the returned client share
+    /// the same budget.
+    pub fn with_rate_limit(self, config: RateLimitConfig) -> Self {
+        Client {
+            rate_limiter: Some(Arc::new(RateLimiter::new(config))),
+            ..self
+        }
+    }
+
+    /// The `X-Bapi-Limit*` headers of the most recent REST response that carried them, on this
+    /// client or any of its clones, whether or not the request succeeded.
+    pub fn last_rate_limit(&self) -> Option<RateLimitStatus> {
+        *self.rate_limit_status.lock().unwrap()
+    }
+
+    async fn throttle(&self, group: EndpointGroup) -> Result<(), BybitError> {
+        match &self.rate_limiter {
+            Some(limiter) => limiter.acquire(group).await,
+            None => Ok(()),
         }
     }
     pub async fn get<T: DeserializeOwned + Send + 'static>(
@@ -44,6 +321,7 @@ impl Client {
         endpoint: API,
         request: Option<String>,
     ) -> Result<T, BybitError> {
+        let group = EndpointGroup::from(&endpoint);
         let url = {
             let mut url = format!("{}/{}", self.host, String::from(endpoint));
             if let Some(request) = request {
@@ -55,8 +333,12 @@ impl Client {
             url
         };
 
-        let response = self.inner_client.get(url).send().await?;
-        self.handler(response).await
+        self.retrying(|| async {
+            self.throttle(group).await?;
+            let response = self.send(self.inner_client.get(url.as_str())).await?;
+            self.handler(response, group).await
+        })
+        .await
     }
     /// Makes a signed HTTP GET request to the specified endpoint.
     pub async fn get_signed<T: DeserializeOwned + Send + 'static>(
@@ -65,6 +347,7 @@ impl Client {
         recv_window: u128,
         request: Option<String>,
     ) -> Result<T, BybitError> {
+        let group = EndpointGroup::from(&endpoint);
         // Construct the full URL
         let mut url: String = format!("{}/{}", self.host, String::from(endpoint));
         let query_string = request.unwrap_or_default();
@@ -72,15 +355,21 @@ impl Client {
             url.push_str(format!("?{}", query_string).as_str());
         }
 
-        // Sign the request, passing the query string for signature
-        let headers = self.build_signed_headers(false, true, recv_window, Some(query_string))?;
+        // Each attempt is signed again so a retry carries a fresh timestamp.
+        self.retrying(|| async {
+            self.throttle(group).await?;
+            // Sign the request, passing the query string for signature
+            let headers =
+                self.build_signed_headers(false, true, recv_window, Some(query_string.clone()))?;
 
-        // Make the signed HTTP GET request
-        let client = &self.inner_client;
-        let response = client.get(url.as_str()).headers(headers).send().await?;
+            // Make the signed HTTP GET request
+            let client = &self.inner_client;
+            let response = self.send(client.get(url.as_str()).headers(headers)).await?;
 
-        // Handle the response
-        self.handler(response).await
+            // Handle the response
+            self.handler(response, group).await
+        })
+        .await
     }
 
     pub async fn post<T: DeserializeOwned + Send + 'static>(
@@ -88,6 +377,8 @@ impl Client {
         endpoint: API,
         request: Option<String>,
     ) -> Result<T, BybitError> {
+        let group = EndpointGroup::from(&endpoint);
+        self.throttle(group).await?;
         let mut url: String = format!("{}/{}", self.host, String::from(endpoint));
         if let Some(request) = request {
             if !request.is_empty() {
@@ -95,8 +386,8 @@ impl Client {
             }
         }
         let client = &self.inner_client;
-        let response = client.post(url.as_str()).send().await?;
-        self.handler(response).await
+        let response = self.send(client.post(url.as_str())).await?;
+        self.handler(response, group).await
     }
 
     /// Makes a signed HTTP POST request to the specified endpoint
@@ -106,8 +397,37 @@ impl Client {
         recv_window: u128,
         raw_request_body: Option<String>,
     ) -> Result<T, BybitError> {
-        // Construct the full URL
+        let group = EndpointGroup::from(&endpoint);
         let url: String = format!("{}{}", self.host, String::from(endpoint));
+        self.send_post_signed(&url, group, recv_window, raw_request_body)
+            .await
+    }
+
+    /// Like `post_signed`, but retried according to the client's `RetryConfig`.
+    ///
+    /// Only use this for requests Bybit can deduplicate, such as an order carrying an
+    /// `orderLinkId`: if an earlier attempt did reach Bybit, the retry is rejected with a
+    /// duplicate `orderLinkId` error instead of placing a second order.
+    pub async fn post_signed_idempotent<T: DeserializeOwned + Send + 'static>(
+        &self,
+        endpoint: API,
+        recv_window: u128,
+        raw_request_body: Option<String>,
+    ) -> Result<T, BybitError> {
+        let group = EndpointGroup::from(&endpoint);
+        let url: String = format!("{}{}", self.host, String::from(endpoint));
+        self.retrying(|| self.send_post_signed(&url, group, recv_window, raw_request_body.clone()))
+            .await
+    }
+
+    async fn send_post_signed<T: DeserializeOwned + Send + 'static>(
+        &self,
+        url: &str,
+        group: EndpointGroup,
+        recv_window: u128,
+        raw_request_body: Option<String>,
+    ) -> Result<T, BybitError> {
+        self.throttle(group).await?;
 
         // Sign the request, passing the raw request body for signature
         let headers =
@@ -115,15 +435,17 @@ impl Client {
 
         // Make the signed HTTP POST request
         let client = &self.inner_client;
-        let response = client
-            .post(url.as_str())
-            .headers(headers)
-            .body(raw_request_body.unwrap_or_default())
-            .send()
+        let response = self
+            .send(
+                client
+                    .post(url)
+                    .headers(headers)
+                    .body(raw_request_body.unwrap_or_default()),
+            )
             .await?;
 
         // Handle the response
-        self.handler(response).await
+        self.handler(response, group).await
     }
 
     fn build_signed_headers<'str>(
@@ -135,9 +457,9 @@ impl Client {
     ) -> Result<HeaderMap, BybitError> {
         let mut custom_headers = HeaderMap::new();
         custom_headers.insert(USER_AGENT, HeaderValue::from_static("bybit-rs"));
-        let timestamp = get_timestamp().to_string();
+        let timestamp = self.timestamp().to_string();
         let window = recv_window.to_string();
-        let signature = self.sign_message(&timestamp, &window, request);
+        let signature = self.sign_message(&timestamp, &window, request)?;
 
         let signature_header = HeaderName::from_static("x-bapi-sign");
         let api_key_header = HeaderName::from_static("x-bapi-api-key");
@@ -151,7 +473,7 @@ impl Client {
             );
             custom_headers.insert(
                 api_key_header,
-                HeaderValue::from_str(&self.api_key.to_owned())?,
+                HeaderValue::from_str(self.credentials.api_key())?,
             );
         }
         custom_headers.insert(
@@ -168,60 +490,83 @@ impl Client {
         Ok(custom_headers).map_err(|e| BybitError::ReqError(e))
     }
 
-    fn sign_message(&self, timestamp: &str, recv_window: &str, request: Option<String>) -> String {
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes()).unwrap();
-        let mut sign_message = format!("{}{}{}", timestamp, self.api_key, recv_window);
-        if let Some(req) = request {
-            sign_message.push_str(&req);
-        }
-
-        mac.update(sign_message.as_bytes());
-        let hex_signature = hex_encode(mac.finalize().into_bytes());
-
-        hex_signature
-    }
-
-    fn _sign_post_message(
+    fn sign_message(
         &self,
         timestamp: &str,
         recv_window: &str,
         request: Option<String>,
-    ) -> String {
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes()).unwrap();
-        mac.update(timestamp.as_bytes());
-        mac.update(self.api_key.as_bytes());
-        mac.update(recv_window.as_bytes());
+    ) -> Result<String, BybitError> {
+        let mut sign_message =
+            format!("{}{}{}", timestamp, self.credentials.api_key(), recv_window);
         if let Some(req) = request {
-            mac.update(req.as_bytes());
+            sign_message.push_str(&req);
         }
-        let hex_signature = hex_encode(mac.finalize().into_bytes());
-
-        hex_signature
+        self.credentials.sign(&sign_message)
     }
 
     async fn handler<T: DeserializeOwned + Send + 'static>(
         &self,
         response: ReqwestResponse,
+        group: EndpointGroup,
     ) -> Result<T, BybitError> {
+        if let Some(status) = RateLimitStatus::from_headers(group, response.headers()) {
+            if let Some(limiter) = &self.rate_limiter {
+                let reset = u64::try_from(status.reset.timestamp_millis()).ok();
+                limiter.observe(group, status.remaining, reset);
+            }
+            *self.rate_limit_status.lock().unwrap() = Some(status);
+        }
         match response.status() {
             StatusCode::OK => {
-                let txt = response.text().await?;
-                println!("{txt}");
-
-                let response: T = serde_json::from_str(&txt)?;
-                Ok(response)
+                let txt = response
+                    .text()
+                    .await
+                    .map_err(|err| self.request_error(err))?;
+                debug!("response: {txt}");
+                parse_response(&txt)
             }
             StatusCode::BAD_REQUEST => {
-                let error: BybitContentError = response.json().await.map_err(BybitError::from)?;
+                let error: BybitContentError = response
+                    .json()
+                    .await
+                    .map_err(|err| self.request_error(err))?;
                 Err(BybitError::BybitError(error).into())
             }
             StatusCode::INTERNAL_SERVER_ERROR => Err(BybitError::InternalServerError),
-            StatusCode::SERVICE_UNAVAILABLE => Err(BybitError::ServiceUnavailable),
+            status @ (StatusCode::FORBIDDEN | StatusCode::SERVICE_UNAVAILABLE) => {
+                let txt = response
+                    .text()
+                    .await
+                    .map_err(|err| self.request_error(err))?;
+                // Bybit also answers 403 when an IP exceeds its rate limit, with no page.
+                match serde_json::from_str::<BybitContentError>(&txt) {
+                    Ok(error) if error.code != 0 => Err(BybitError::BybitError(error)),
+                    _ if is_page(&txt) => Err(maintenance(status, &txt)),
+                    _ => Err(BybitError::StatusCode(status.as_u16())),
+                }
+            }
             StatusCode::UNAUTHORIZED => Err(BybitError::Unauthorized),
             status => Err(BybitError::StatusCode(status.as_u16())),
         }
     }
 
+    /// Builds the `{"op":"auth","args":[api_key, expires, signature]}` frame for private streams.
+    ///
+    /// The signature stays valid for `alive_dur` minutes (at least one), which also absorbs a
+    /// local clock that runs slightly behind Bybit's.
+    pub fn ws_auth_message(&self, alive_dur: Option<u64>) -> Result<String, BybitError> {
+        let expiry_time = alive_dur.unwrap_or(1).max(1) * 1000 * 60;
+        let expires = self.timestamp() + expiry_time;
+
+        let signature = self.credentials.sign(&format!("GET/realtime{expires}"))?;
+        Ok(json!({
+            "req_id": generate_random_uid(5),
+            "op": "auth",
+            "args": [self.credentials.api_key(), expires, signature]
+        })
+        .to_string())
+    }
+
     pub async fn wss_connect(
         &self,
         endpoint: WebsocketAPI,
@@ -231,31 +576,131 @@ impl Client {
     ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, BybitError> {
         let unparsed_url = format!("{}{}", self.host, String::from(endpoint)).to_string();
         let url = WsUrl::parse(unparsed_url.as_str())?;
-        let expiry_time = alive_dur.unwrap_or(0) * 1000 * 60;
-        let expires = get_timestamp() + expiry_time;
-
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes()).unwrap();
-        mac.update(format!("GET/realtime{expires}").as_bytes());
-        let signature = hex_encode(mac.finalize().into_bytes());
-        let uuid = generate_random_uid(5);
 
-        match connect_async(url).await {
+        let connected = match &self.proxy {
+            Some(proxy) => {
+                let host = url.host_str().unwrap_or_default();
+                let host = host.trim_start_matches('[').trim_end_matches(']');
+                let port = url.port_or_known_default().unwrap_or(443);
+                let stream = proxy.connect(host, port).await?;
+                client_async_tls_with_config(url, stream, Some(self.ws_config), None).await
+            }
+            None => connect_async_with_config(url, Some(self.ws_config), false).await,
+        };
+        match connected {
             Ok((mut ws_stream, _)) => {
-                let auth_msg = json!({
-                    "req_id": uuid,
-                    "op": "auth",
-                    "args": [self.api_key, expires, signature]
-                });
                 if private {
                     ws_stream
-                        .send(WsMessage::Text(auth_msg.to_string()))
+                        .send(WsMessage::Text(self.ws_auth_message(alive_dur)?))
                         .await?;
                 }
-                let request = request_body.unwrap_or_else(String::new);
-                ws_stream.send(WsMessage::Text(request)).await?;
+                if let Some(request) = request_body {
+                    ws_stream.send(WsMessage::Text(request)).await?;
+                }
                 Ok(ws_stream)
             }
-            Err(err) => Err(BybitError::Tungstenite(err)),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Deserializes a V5 REST response body.
+///
+/// Bybit reports most failures with HTTP 200 and a non-zero `retCode`, so the envelope is
+/// checked first and such responses become `BybitError::BybitError` with the code and message.
+/// Every REST call goes through this, which makes codes like `TIMESTAMP_ERROR` and
+/// `RATE_LIMITED` matchable. A body that is not JSON at all, such as a maintenance page,
+/// becomes `BybitError::Maintenance`; an empty body or unexpected JSON is a parse error.
+pub fn parse_response<T: DeserializeOwned>(raw: &str) -> Result<T, BybitError> {
+    if is_page(raw) {
+        return Err(maintenance(StatusCode::OK, raw));
+    }
+    if let Ok(error) = serde_json::from_str::<BybitContentError>(raw) {
+        if error.code != 0 {
+            return Err(BybitError::BybitError(error));
+        }
+    }
+    Ok(serde_json::from_str(raw)?)
+}
+
+/// Characters of an unexpected response body kept in `BybitError::Maintenance`.
+const BODY_SNIPPET_LEN: usize = 200;
+
+/// Whether `body` is a page, e.g. the HTML served during maintenance, rather than a possibly
+/// truncated or malformed JSON document.
+fn is_page(body: &str) -> bool {
+    let body = body.trim_start();
+    !body.is_empty()
+        && !body.starts_with(['{', '['])
+        && serde_json::from_str::<serde::de::IgnoredAny>(body).is_err()
+}
+
+fn maintenance(status: StatusCode, body: &str) -> BybitError {
+    BybitError::Maintenance {
+        status: status.as_u16(),
+        body: body.trim().chars().take(BODY_SNIPPET_LEN).collect(),
+    }
+}
+
+/// Default page limit for `paginate_all`, high enough for months of executions at 100 per page.
+pub const MAX_PAGES: usize = 1000;
+
+/// Collects every page of a cursor paginated endpoint.
+///
+/// `fetch` is called with `None` for the first page and then with each `nextPageCursor`, and
+/// returns the page's items together with the cursor of the next page, if any (see the
+/// `into_page` helpers on the paginated responses). Fails with `InvalidParams` rather than
+/// looping forever if more than `max_pages` pages come back.
+pub async fn paginate_all<T, F, Fut>(max_pages: usize, mut fetch: F) -> Result<Vec<T>, BybitError>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>), BybitError>>,
+{
+    let mut items = Vec::new();
+    let mut cursor = None;
+    for _ in 0..max_pages {
+        let (page, next) = fetch(cursor.take()).await?;
+        items.extend(page);
+        match next {
+            Some(next) if !next.is_empty() => cursor = Some(next),
+            _ => return Ok(items),
         }
     }
+    Err(BybitError::InvalidParams(format!(
+        "more than {max_pages} pages, narrow the request"
+    )))
+}
+
+/// Splits `[start, end]` (milliseconds) into consecutive windows of at most `window` and
+/// fetches them with up to `concurrency` requests in flight.
+///
+/// `fetch` receives the inclusive bounds of one window. Results are concatenated in window
+/// order, oldest first, whatever order the requests complete in. Use it for endpoints that cap
+/// the span of one query, such as the 7 day limit on executions, and combine it with
+/// `paginate_all` inside `fetch` when a window can hold more than one page.
+pub async fn fetch_range<T, F, Fut>(
+    start: u64,
+    end: u64,
+    window: u64,
+    concurrency: usize,
+    mut fetch: F,
+) -> Result<Vec<T>, BybitError>
+where
+    F: FnMut(u64, u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, BybitError>>,
+{
+    use futures::stream::{self, StreamExt, TryStreamExt};
+    if window == 0 || start > end {
+        return Err(BybitError::InvalidParams(format!(
+            "invalid range {start}..={end} with window {window}"
+        )));
+    }
+    let windows = (start..=end)
+        .step_by(window as usize)
+        .map(|from| (from, from.saturating_add(window - 1).min(end)));
+    let pages: Vec<Vec<T>> = stream::iter(windows.map(|(from, to)| fetch(from, to)))
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await?;
+    Ok(pages.into_iter().flatten().collect())
 }
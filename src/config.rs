@@ -1,3 +1,44 @@
+use crate::errors::{BybitError, RATE_LIMITED};
+use crate::rate_limit::EndpointGroup;
+use rand::Rng;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The Bybit deployment to connect to. All three accept the same signed requests; an API key
+/// only works on the environment it was created on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Environment {
+    /// Live trading with real funds (`api.bybit.com` / `stream.bybit.com`).
+    #[default]
+    Mainnet,
+    /// A separate exchange with its own accounts, order books and test coins
+    /// (`api-testnet.bybit.com` / `stream-testnet.bybit.com`).
+    Testnet,
+    /// Demo trading on a mainnet account: live market data and matching with virtual
+    /// balances (`api-demo.bybit.com` / `stream-demo.bybit.com`). Only the private stream is
+    /// offered on the demo host, so public topics must be read from `Mainnet`.
+    Demo,
+}
+
+impl Environment {
+    pub const fn rest_api_endpoint(self) -> &'static str {
+        match self {
+            Environment::Mainnet => "https://api.bybit.com",
+            Environment::Testnet => "https://api-testnet.bybit.com",
+            Environment::Demo => "https://api-demo.bybit.com",
+        }
+    }
+
+    pub const fn ws_endpoint(self) -> &'static str {
+        match self {
+            Environment::Mainnet => "wss://stream.bybit.com/v5",
+            Environment::Testnet => "wss://stream-testnet.bybit.com/v5",
+            Environment::Demo => "wss://stream-demo.bybit.com/v5",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub rest_api_endpoint: &'static str,
@@ -6,21 +47,29 @@ pub struct Config {
 }
 
 impl Config {
-    pub const DEFAULT_REST_API_ENDPOINT: &'static str = "https://api.bybit.com";
-    pub const DEFAULT_WS_ENDPOINT: &'static str = "wss://stream.bybit.com/v5";
+    pub const DEFAULT_REST_API_ENDPOINT: &'static str = Environment::Mainnet.rest_api_endpoint();
+    pub const DEFAULT_WS_ENDPOINT: &'static str = Environment::Mainnet.ws_endpoint();
 
+    /// Mainnet, see `Environment::Mainnet`.
     pub const fn default() -> Self {
-        Self {
-            rest_api_endpoint: Self::DEFAULT_REST_API_ENDPOINT,
-            ws_endpoint: Self::DEFAULT_WS_ENDPOINT,
-            recv_window: 5000,
-        }
+        Self::environment(Environment::Mainnet)
     }
 
+    /// Testnet, see `Environment::Testnet`.
     pub const fn testnet() -> Self {
+        Self::environment(Environment::Testnet)
+    }
+
+    /// Demo trading, see `Environment::Demo`.
+    pub const fn demo() -> Self {
+        Self::environment(Environment::Demo)
+    }
+
+    /// The REST and websocket hosts of `environment`.
+    pub const fn environment(environment: Environment) -> Self {
         Self {
-            rest_api_endpoint: "https://api-testnet.bybit.com",
-            ws_endpoint: "wss://stream-testnet.bybit.com/v5",
+            rest_api_endpoint: environment.rest_api_endpoint(),
+            ws_endpoint: environment.ws_endpoint(),
             recv_window: 5000,
         }
     }
@@ -32,3 +81,152 @@ impl Config {
         }
     }
 }
+
+/// Controls how a websocket subscription recovers from a dropped connection.
+///
+/// The delay before retry `n` is `backoff_base * 2^n`, capped at `max_backoff`.
+/// `on_reconnect` is called after every successful reconnect so callers can
+/// reset local state (e.g. order books) before the replayed snapshot arrives.
+#[derive(Clone)]
+pub struct ReconnectConfig {
+    pub max_retries: u32,
+    pub backoff_base: Duration,
+    pub max_backoff: Duration,
+    pub on_reconnect: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl ReconnectConfig {
+    pub fn new(max_retries: u32, backoff_base: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            backoff_base,
+            max_backoff,
+            on_reconnect: None,
+        }
+    }
+
+    pub fn on_reconnect<F>(self, callback: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        Self {
+            on_reconnect: Some(Arc::new(callback)),
+            ..self
+        }
+    }
+
+    /// Delay to wait before the given (zero-based) retry attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff_base
+            .checked_mul(2u32.saturating_pow(attempt))
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(1), Duration::from_secs(30))
+    }
+}
+
+impl fmt::Debug for ReconnectConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectConfig")
+            .field("max_retries", &self.max_retries)
+            .field("backoff_base", &self.backoff_base)
+            .field("max_backoff", &self.max_backoff)
+            .field("on_reconnect", &self.on_reconnect.is_some())
+            .finish()
+    }
+}
+
+/// Request budget for the client-side REST rate limiter, in requests per second for
+/// each endpoint group. Bursts up to one second's worth of requests are allowed.
+///
+/// When `block` is true a request waits for a free slot; otherwise it fails
+/// immediately with `BybitError::RateLimited`.
+#[derive(Clone, Debug)]
+pub struct RateLimitConfig {
+    pub order: u32,
+    pub position: u32,
+    pub account: u32,
+    pub market: u32,
+    pub block: bool,
+}
+
+impl RateLimitConfig {
+    /// Returns the configured rate for a group.
+    pub fn rate(&self, group: EndpointGroup) -> u32 {
+        match group {
+            EndpointGroup::Order => self.order,
+            EndpointGroup::Position => self.position,
+            EndpointGroup::Account => self.account,
+            EndpointGroup::Market => self.market,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    /// Bybit's default per-UID limits for order, position and account endpoints, and
+    /// the per-IP limit of 600 requests per 5 seconds for market data.
+    fn default() -> Self {
+        Self {
+            order: 10,
+            position: 10,
+            account: 10,
+            market: 120,
+            block: true,
+        }
+    }
+}
+
+/// `retCode` Bybit returns for an internal server error.
+pub const SERVER_ERROR: i32 = 10016;
+
+/// Controls how the REST client retries transient failures.
+///
+/// Connection errors, timeouts and 5xx responses are always retried; API errors are
+/// retried when their `retCode` is listed in `retry_on`. The delay before retry `n` is
+/// `base_delay * 2^n`, capped at `max_delay`, with up to half of it taken off at random
+/// so clients that failed together do not retry together.
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub retry_on: Vec<i32>,
+}
+
+impl RetryConfig {
+    pub fn should_retry(&self, err: &BybitError) -> bool {
+        match err {
+            BybitError::ReqError(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            BybitError::Timeout(_) | BybitError::InternalServerError => true,
+            BybitError::StatusCode(code) | BybitError::Maintenance { status: code, .. } => {
+                *code >= 500
+            }
+            BybitError::BybitError(e) => self.retry_on.contains(&e.code),
+            _ => false,
+        }
+    }
+
+    /// Delay to wait before the given (zero-based) retry attempt.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(attempt))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            retry_on: vec![SERVER_ERROR, RATE_LIMITED],
+        }
+    }
+}
@@ -5,21 +5,32 @@ use serde::Deserialize;
 use std::fmt;
 use thiserror::Error;
 /// BybitContentError is a struct that represents the error returned by the Bybit API.
-/// It has two fields: code, which is an i16 representing the error code, and msg, which is a String
-/// representing the error message.
+/// It has two fields: code, which is an i32 representing the error code (`retCode`), and msg,
+/// which is a String representing the error message (`retMsg`).
 #[derive(Debug, Deserialize)]
 pub struct BybitContentError {
-    pub code: i16,
+    #[serde(alias = "retCode")]
+    pub code: i32,
+    #[serde(alias = "retMsg")]
     pub msg: String,
 }
 
+/// `retCode` returned when the request timestamp is outside the `recv_window`.
+pub const TIMESTAMP_ERROR: i32 = 10002;
+
+/// `retCode` returned when the API rate limit has been hit.
+pub const RATE_LIMITED: i32 = 10006;
+
+/// Result alias used for fallible calls in this crate.
+pub type Result<T> = std::result::Result<T, BybitError>;
+
 /// BybitError is an enum that can hold any possible error that can occur during the execution of the program.
 /// It has several variants, each representing a different type of error.
 #[derive(Debug, Error)]
 pub enum BybitError {
-    /// BybitError variant that holds a BybitContentError. This is used when the error returned by the Bybit API
-    /// is of the type BybitContentError.
-    #[error("Bybit error: {0}")]
+    /// BybitError variant that holds a BybitContentError. This is returned whenever Bybit answers with a
+    /// non-zero `retCode`, so callers can match on specific codes such as 10006 (rate limited).
+    #[error("Bybit error {}: {}", .0.code, .0.msg)]
     BybitError(BybitContentError),
 
     /// KlineValueMissingError variant that holds the index of the missing value, and the name of the missing value.
@@ -47,8 +58,9 @@ pub enum BybitError {
     #[error(transparent)]
     Json(#[from] serde_json::Error),
 
+    /// Tungstenite variant holding a websocket error, boxed to keep `BybitError` small.
     #[error(transparent)]
-    Tungstenite(#[from] tokio_tungstenite::tungstenite::Error),
+    Tungstenite(Box<tokio_tungstenite::tungstenite::Error>),
 
     #[error(transparent)]
     TimestampError(#[from] std::time::SystemTimeError),
@@ -56,13 +68,29 @@ pub enum BybitError {
     #[error(transparent)]
     SerdeError(#[from] serde::de::value::Error),
 
+    #[error(transparent)]
+    OpenSsl(#[from] openssl::error::ErrorStack),
+
+    /// Join variant returned when a spawned websocket task panicked.
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+
     // Variants representing common errors.
     #[error("Internal Server Error")]
     InternalServerError,
 
+    /// No longer returned; replaced by `Maintenance`, which carries the status and body.
+    #[deprecated(note = "Bybit outages are reported as `BybitError::Maintenance`")]
     #[error("Service Unavailable")]
     ServiceUnavailable,
 
+    /// Maintenance variant returned when Bybit answers with a page instead of JSON, such as the
+    /// HTML served with HTTP 403 or 503 during scheduled maintenance. Holds the HTTP status and
+    /// the start of the body; bots should pause trading and back off. A 403 or 503 without a
+    /// page is a `StatusCode`.
+    #[error("Bybit unavailable (HTTP {status}): {body}")]
+    Maintenance { status: u16, body: String },
+
     #[error("Unauthorized")]
     Unauthorized,
 
@@ -70,6 +98,54 @@ pub enum BybitError {
     #[error("Status Code")]
     StatusCode(u16),
 
+    /// InvalidParams variant returned before sending a request whose parameters Bybit would reject,
+    /// e.g. a cancel without an order id.
+    #[error("Invalid parameters: {0}")]
+    InvalidParams(String),
+
+    /// ReconnectFailed variant returned once a websocket subscription has used up all of its
+    /// reconnect attempts. `source` is the error that ended the last attempt.
+    #[error("Websocket reconnect failed after {attempts} attempts: {source}")]
+    ReconnectFailed {
+        attempts: u32,
+        source: Box<BybitError>,
+    },
+
+    /// AuthFailed variant returned when Bybit rejects the websocket `auth` frame, e.g. for a wrong
+    /// or expired API key. A `ret_msg` mentioning an expired request usually means the local clock
+    /// is behind Bybit's by more than the signature's validity window.
+    #[error("Websocket authentication failed: {ret_msg}")]
+    AuthFailed { ret_msg: String },
+
+    /// SubscriptionFailed variant returned when Bybit answers a websocket subscribe request with
+    /// `success: false`, e.g. for a misspelled topic. `topics` are the ones sent in the rejected
+    /// request; Bybit rejects a request as a whole.
+    #[error("Websocket subscription to {topics:?} failed: {ret_msg}")]
+    SubscriptionFailed {
+        topics: Vec<String>,
+        ret_msg: String,
+    },
+
+    /// RateLimited variant returned by the client-side rate limiter when it is not set to block.
+    /// `retry_after` is how long until the endpoint group has a free request again.
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited { retry_after: std::time::Duration },
+
+    /// ConnectionClosed variant returned when the server closes a websocket connection, holding the
+    /// close reason if one was given.
+    #[error("Websocket connection closed: {0}")]
+    ConnectionClosed(String),
+
+    /// Timeout variant returned when a REST request, including reading its response, takes longer
+    /// than the client's timeout.
+    #[error("Request timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    /// OrderBookSequenceGap variant returned by `LocalOrderBook::apply` when an update id is skipped.
+    /// The local book is stale and the orderbook topic should be resubscribed.
+    #[error("Order book update id gap: expected {expected}, received {received}")]
+    OrderBookSequenceGap { expected: u64, received: u64 },
+
     /// Base variant that holds a String representing the error.
     /// This variant is used when the error is not of any specific type, and it is just a simple String.
     #[error("Bybit error: {0}")]
@@ -101,8 +177,47 @@ impl From<std::string::String> for BybitError {
     }
 }
 
+impl From<tokio_tungstenite::tungstenite::Error> for BybitError {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        BybitError::Tungstenite(Box::new(err))
+    }
+}
+
 impl BybitError {
     fn new(arg: String) -> Self {
         BybitError::Base(arg)
     }
+
+    /// Returns Bybit's `retCode` if this error came from the API.
+    pub fn ret_code(&self) -> Option<i32> {
+        match self {
+            BybitError::BybitError(e) => Some(e.code),
+            _ => None,
+        }
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.ret_code() == Some(RATE_LIMITED)
+    }
+
+    pub fn is_timestamp_error(&self) -> bool {
+        self.ret_code() == Some(TIMESTAMP_ERROR)
+    }
+
+    pub fn is_maintenance(&self) -> bool {
+        matches!(self, BybitError::Maintenance { .. })
+    }
+
+    /// Whether the error came from the connection rather than from what was sent or received
+    /// over it, so retrying on a new connection can succeed. Websocket subscriptions only
+    /// reconnect after such errors; a rejected auth or a failing handler would fail again.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            BybitError::Tungstenite(_)
+                | BybitError::IoError(_)
+                | BybitError::ConnectionClosed(_)
+                | BybitError::Timeout(_)
+        )
+    }
 }
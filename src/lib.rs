@@ -1,3 +1,6 @@
+#[macro_use]
+mod logging;
+
 pub mod util;
 pub mod errors;
 pub mod config;
@@ -5,9 +8,15 @@ pub mod model;
 pub mod api;
 pub mod general;
 pub mod client;
+pub mod proxy;
 pub mod market;
 pub mod trade;
 pub mod  position;
 pub mod asset;
 pub mod account;
 pub mod ws;
+pub mod stream_manager;
+pub mod orderbook;
+pub mod rate_limit;
+#[cfg(feature = "mock")]
+pub mod mock;
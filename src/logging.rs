@@ -0,0 +1,32 @@
+//! Logging macros used across the crate.
+//!
+//! With the `log` feature they forward to the `log` crate, so applications can route the
+//! library's diagnostics into their own logger. Without it they compile to nothing; the
+//! library never writes to stdout or stderr itself.
+
+macro_rules! log_at {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::$level!(target: "bybit", $($arg)+);
+        #[cfg(not(feature = "log"))]
+        {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+macro_rules! debug {
+    ($($arg:tt)+) => { log_at!(debug, $($arg)+) };
+}
+
+macro_rules! info {
+    ($($arg:tt)+) => { log_at!(info, $($arg)+) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)+) => { log_at!(warn, $($arg)+) };
+}
+
+macro_rules! error {
+    ($($arg:tt)+) => { log_at!(error, $($arg)+) };
+}
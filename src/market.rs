@@ -1,17 +1,21 @@
-use crate::api::{Market, API};
-use crate::client::Client;
+use crate::api::{Market, SpotLeverage, API};
+use crate::client::{paginate_all, Client, MAX_PAGES};
 use crate::errors::BybitError;
 use crate::model::{
-    Category, DeliveryPriceResponse, FundingHistoryRequest, FundingRateResponse, FuturesInstrumentsInfoResponse, FuturesTickersResponse, HistoricalVolatilityRequest,
-    HistoricalVolatilityResponse, IndexPriceKlineResponse, InstrumentRequest, InsuranceResponse, KlineRequest, KlineResponse,
-    LongShortRatioResponse, MarkPriceKlineResponse,
-    OpenInterestRequest, OpeninterestResponse,
-    OptionsInstrument, OrderBookResponse, OrderbookRequest,
-    PremiumIndexPriceKlineResponse, RecentTradesRequest, RecentTradesResponse, RiskLimitRequest, RiskLimitResponse,
-    SpotInstrumentsInfoResponse, SpotTickersResponse,
+    AnnouncementRequest, AnnouncementResponse, Category, DeliveryPriceResponse,
+    FundingHistoryRequest, FundingRate, FundingRateResponse, FuturesInstrumentsInfoResponse,
+    FuturesTickersResponse, HistoricalVolatilityRequest, HistoricalVolatilityResponse,
+    IndexPriceKlineResponse, InstrumentRequest, InstrumentsInfoResponse, InsuranceResponse,
+    KlineRequest, KlineResponse, LeverageTokenInfo, LeverageTokenInfoResponse, LeverageTokenMarket,
+    LeverageTokenMarketResponse, LongShortRatio, LongShortRatioResponse, MarkPriceKlineResponse,
+    OiInterval, OpenInterest, OpenInterestRequest, OpeninterestResponse, OptionsInstrument,
+    OrderBookResponse, OrderbookRequest, PremiumIndexPriceKlineResponse, RecentTradesRequest,
+    RecentTradesResponse, RiskLimitRequest, RiskLimitResponse, ServerTime, ServerTimeResponse,
+    SpotInstrumentsInfoResponse, SpotTickersResponse, TickersResponse, WsTrade,
 };
 use crate::util::{build_request, date_to_milliseconds};
 
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 #[derive(Clone)]
@@ -20,6 +24,21 @@ pub struct MarketData {
     pub recv_window: u64,
 }
 
+/// Most klines Bybit returns for a single request.
+pub const MAX_KLINE_LIMIT: u64 = 1000;
+
+/// Most funding rates Bybit returns for a single request.
+pub const MAX_FUNDING_LIMIT: u64 = 200;
+
+/// Largest order book depth the REST endpoint serves for a category.
+pub fn max_orderbook_depth(category: &Category) -> u64 {
+    match category {
+        Category::Spot => 200,
+        Category::Linear | Category::Inverse => 500,
+        Category::Option => 25,
+    }
+}
+
 /// Market Data endpoints
 
 impl MarketData {
@@ -37,11 +56,12 @@ impl MarketData {
     /// * `interval` - The time interval between klines.
     /// * `start` - The start date for the kline data retrieval in `DDMMYY` format (optional).
     /// * `end` - The end date for the kline data retrieval in `DDMMYY` format (optional).
-    /// * `limit` - The maximum number of klines to return (optional).
+    /// * `limit` - The maximum number of klines to return (optional, capped at 1000).
     ///
     /// # Returns
     ///
-    /// A `Result<Vec<KlineData>, Error>` containing the requested kline data if successful, or an error otherwise.
+    /// A `Result<KlineResponse, Error>` containing the requested kline data if successful, or an error otherwise.
+    /// Bybit returns the newest kline first; the list is reversed into chronological order.
     pub async fn get_klines<'a>(&self, req: KlineRequest<'a>) -> Result<KlineResponse, BybitError> {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         if let Some(cat) = req.category {
@@ -54,7 +74,7 @@ impl MarketData {
                 .or_insert_with(|| Category::Linear.as_str().to_owned());
         }
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("interval".into(), req.interval.into());
+        parameters.insert("interval".into(), req.interval.as_str().into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
             let start_millis = date_to_milliseconds(start_str);
             parameters
@@ -70,13 +90,14 @@ impl MarketData {
         if let Some(l) = req.limit {
             parameters
                 .entry("limit".to_owned())
-                .or_insert_with(|| l.to_string());
+                .or_insert_with(|| l.min(MAX_KLINE_LIMIT).to_string());
         }
         let request = build_request(&parameters);
-        let response: KlineResponse = self
+        let mut response: KlineResponse = self
             .client
             .get(API::Market(Market::Kline), Some(request))
             .await?;
+        response.result.list.reverse();
         Ok(response)
     }
     /// Retrieves historical mark price klines.
@@ -90,7 +111,7 @@ impl MarketData {
     ///
     /// * `category` - An optional category of the contract, if specified.
     /// * `symbol` - The trading pair or contract symbol.
-    /// * `interval` - The interval between klines (e.g., "5m" for five minutes).
+    /// * `interval` - The interval between klines (e.g., `Interval::FiveMinutes`).
     /// * `start` - An optional start time for filtering the data, formatted as "DDMMYY".
     /// * `end` - An optional end time for filtering the data, formatted as "DDMMYY".
     /// * `limit` - An optional limit to the number of kline entries to be returned.
@@ -120,7 +141,7 @@ impl MarketData {
             parameters.insert("category".to_owned(), Category::Linear.as_str().to_string());
         }
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("interval".into(), req.interval.into());
+        parameters.insert("interval".into(), req.interval.as_str().into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
             let start_millis = date_to_milliseconds(start_str);
             parameters
@@ -185,7 +206,7 @@ impl MarketData {
             parameters.insert("category".to_owned(), Category::Linear.as_str().to_string());
         }
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("interval".into(), req.interval.into());
+        parameters.insert("interval".into(), req.interval.as_str().into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
             let start_millis = date_to_milliseconds(start_str);
             parameters
@@ -241,7 +262,7 @@ impl MarketData {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         parameters.insert("category".to_owned(), Category::Linear.as_str().to_string());
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("interval".into(), req.interval.into());
+        parameters.insert("interval".into(), req.interval.as_str().into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
             let start_millis = date_to_milliseconds(start_str);
             parameters
@@ -292,7 +313,7 @@ impl MarketData {
             Category::Linear => "linear",
             Category::Inverse => "inverse",
             _ => {
-                return Err(BybitError::from(
+                return Err(BybitError::InvalidParams(
                     "Category must be either Linear or Inverse".to_string(),
                 ))
             }
@@ -359,6 +380,46 @@ impl MarketData {
         Ok(response)
     }
 
+    /// Fetches instrument metadata for any category.
+    ///
+    /// Unlike the category specific calls this returns only the fields every
+    /// instrument shares, including the tick size and quantity step. Use
+    /// `InstrumentInfo::round_price` and `InstrumentInfo::round_qty` to snap order
+    /// values to them before placing an order.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - An `InstrumentRequest` with the category and optional symbol, status, base coin and limit filters.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<InstrumentsInfoResponse, Error>` containing the matching instruments.
+    pub async fn get_instruments_info<'a>(
+        &self,
+        req: InstrumentRequest<'a>,
+    ) -> Result<InstrumentsInfoResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), req.category.as_str().into());
+        if let Some(symbol) = req.symbol {
+            parameters.insert("symbol".into(), symbol.into());
+        }
+        if req.status.unwrap_or(false) {
+            parameters.insert("status".into(), "Trading".into());
+        }
+        if let Some(base_coin) = req.base_coin {
+            parameters.insert("baseCoin".into(), base_coin.into());
+        }
+        if let Some(l) = req.limit {
+            parameters.insert("limit".into(), l.to_string());
+        }
+        let request = build_request(&parameters);
+        let response: InstrumentsInfoResponse = self
+            .client
+            .get(API::Market(Market::InstrumentsInfo), Some(request))
+            .await?;
+        Ok(response)
+    }
+
     pub async fn get_options_instrument_info<'a>(
         &self,
         _req: InstrumentRequest<'a>,
@@ -374,12 +435,13 @@ impl MarketData {
     /// * `req` - An `OrderbookRequest` containing:
     ///     * `symbol`: The symbol string to query the order book for.
     ///     * `category`: The market category to filter the order book by.
-    ///     * `limit`: An optional usize to restrict the number of entries in the order book.
+    ///     * `limit`: An optional depth, between 1 and `max_orderbook_depth` for the category.
     ///
     /// # Returns
     ///
     /// A `Result<OrderBook, Error>` which is Ok if the order book is successfully retrieved,
-    /// or an Err with a detailed error message otherwise.
+    /// or an Err with a detailed error message otherwise. The result carries `ts` and `u`,
+    /// so it can seed a `LocalOrderBook` via `LocalOrderBook::from_snapshot`.
     pub async fn get_depth<'a>(
         &self,
         req: OrderbookRequest<'a>,
@@ -388,6 +450,15 @@ impl MarketData {
         parameters.insert("category".into(), req.category.as_str().into());
         parameters.insert("symbol".into(), req.symbol.into());
         if let Some(l) = req.limit {
+            let max = max_orderbook_depth(&req.category);
+            if l == 0 || l > max {
+                return Err(BybitError::InvalidParams(format!(
+                    "orderbook depth for {} must be between 1 and {}, got {}",
+                    req.category.as_str(),
+                    max,
+                    l
+                )));
+            }
             parameters.insert("limit".to_string(), l.to_string());
         }
         let request = build_request(&parameters);
@@ -399,6 +470,36 @@ impl MarketData {
         Ok(response)
     }
 
+    /// Asynchronously retrieves tickers for any category.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - The market category; linear and inverse return `Ticker::Futures`,
+    ///   spot returns `Ticker::Spot` and option returns `Ticker::Option`.
+    /// * `symbol` - An optional symbol. Without it every ticker in the category is returned.
+    ///   Option tickers also require a `baseCoin`, which Bybit defaults to BTC.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the tickers, or an error if the retrieval fails.
+    pub async fn get_tickers(
+        &self,
+        category: Category,
+        symbol: Option<&str>,
+    ) -> Result<TickersResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), category.as_str().into());
+        if let Some(symbol) = symbol {
+            parameters.insert("symbol".into(), symbol.into());
+        }
+        let request = build_request(&parameters);
+        let response: TickersResponse = self
+            .client
+            .get(API::Market(Market::Tickers), Some(request))
+            .await?;
+        Ok(response)
+    }
+
     /// Asynchronously retrieves spot tickers based on the provided symbol.
     ///
     /// # Arguments
@@ -462,12 +563,14 @@ impl MarketData {
     /// * `symbol` - The trading pair or contract symbol.
     /// * `start` - An optional parameter indicating the start time for the funding history.
     /// * `end` - An optional parameter indicating the end time for the funding history.
-    /// * `limit` - An optional parameter specifying the maximum number of funding rates to return.
+    /// * `limit` - An optional parameter specifying the maximum number of funding rates to return
+    ///   (capped at 200).
     ///
     /// # Returns
     ///
     /// A `Result<Vec<FundingRate>, Error>` representing the historical funding rates if the request is successful,
-    /// otherwise an error.
+    /// otherwise an error. Bybit returns the newest funding rate first; use
+    /// `get_funding_history_range` for ranges longer than one page.
     ///
     /// # Errors
     ///
@@ -475,9 +578,72 @@ impl MarketData {
     pub async fn get_funding_history<'a>(
         &self,
         req: FundingHistoryRequest<'a>,
+    ) -> Result<FundingRateResponse, BybitError> {
+        let start = req
+            .start_time
+            .as_ref()
+            .map(|s| date_to_milliseconds(s.as_ref()));
+        let end = req
+            .end_time
+            .as_ref()
+            .map(|s| date_to_milliseconds(s.as_ref()));
+        self.funding_page(req.category, &req.symbol, start, end, req.limit)
+            .await
+    }
+
+    /// Retrieves every funding rate between `start` and `end` (both in milliseconds).
+    ///
+    /// Pages backwards from `end` in windows of `MAX_FUNDING_LIMIT` until `start` is
+    /// reached or Bybit has no older entries. The rates are returned newest first, in the
+    /// same order as `get_funding_history`.
+    pub async fn get_funding_history_range(
+        &self,
+        category: Category,
+        symbol: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<FundingRate>, BybitError> {
+        let mut rates: Vec<FundingRate> = Vec::new();
+        let mut window_end = end;
+        while window_end >= start {
+            let page = self
+                .funding_page(
+                    category,
+                    symbol,
+                    Some(start),
+                    Some(window_end),
+                    Some(MAX_FUNDING_LIMIT),
+                )
+                .await?
+                .result
+                .list;
+            let full = page.len() as u64 >= MAX_FUNDING_LIMIT;
+            let oldest = match page.iter().map(|rate| rate.funding_rate_timestamp).min() {
+                Some(ts) => ts,
+                None => break,
+            };
+            rates.extend(
+                page.into_iter()
+                    .filter(|rate| (start..=end).contains(&rate.funding_rate_timestamp)),
+            );
+            if !full || oldest <= start {
+                break;
+            }
+            window_end = oldest - 1;
+        }
+        Ok(rates)
+    }
+
+    async fn funding_page(
+        &self,
+        category: Category,
+        symbol: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+        limit: Option<u64>,
     ) -> Result<FundingRateResponse, BybitError> {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-        let category_value = match req.category {
+        let category_value = match category {
             Category::Linear => "linear",
             Category::Inverse => "inverse",
             _ => {
@@ -487,24 +653,15 @@ impl MarketData {
             }
         };
         parameters.insert("category".into(), category_value.into());
-        parameters.insert("symbol".into(), req.symbol.into());
-        if let Some(start_str) = req.start_time.as_ref().map(|s| s.as_ref()) {
-            let start_millis = date_to_milliseconds(start_str);
-            parameters
-                .entry("startTime".to_owned())
-                .or_insert_with(|| start_millis.to_string());
+        parameters.insert("symbol".into(), symbol.into());
+        if let Some(start_millis) = start {
+            parameters.insert("startTime".into(), start_millis.to_string());
         }
-        if let Some(end_str) = req.end_time.as_ref().map(|s| s.as_ref()) {
-            let end_millis = date_to_milliseconds(end_str);
-            parameters
-                .entry("endTime".to_owned())
-                .or_insert_with(|| end_millis.to_string());
+        if let Some(end_millis) = end {
+            parameters.insert("endTime".into(), end_millis.to_string());
         }
-
-        if let Some(l) = req.limit {
-            parameters
-                .entry("limit".to_owned())
-                .or_insert_with(|| l.to_string());
+        if let Some(l) = limit {
+            parameters.insert("limit".into(), l.min(MAX_FUNDING_LIMIT).to_string());
         }
         let request = build_request(&parameters);
         let response: FundingRateResponse = self
@@ -519,18 +676,24 @@ impl MarketData {
     /// # Parameters
     ///
     /// * `category`: The market category to filter trades.
-    /// * `symbol`: A specific symbol to filter trades (optional).
-    /// * `basecoin`: A specific basecoin to filter trades (optional).
+    /// * `symbol`: A specific symbol to filter trades (required except for options).
+    /// * `basecoin`: A specific basecoin to filter trades (options only; Bybit defaults to BTC).
     /// * `limit`: The maximum number of trades to return (optional).
     ///
     /// # Returns
     ///
     /// Returns `Ok(Vec<Trade>)` containing the recent trades if the operation is successful,
-    /// or an `Err` with an error message if it fails.
+    /// or an `Err` with an error message if it fails. Bybit returns the newest trade first.
     pub async fn get_recent_trades<'a>(
         &self,
         req: RecentTradesRequest<'a>,
     ) -> Result<RecentTradesResponse, BybitError> {
+        if req.symbol.is_none() && !matches!(req.category, Category::Option) {
+            return Err(BybitError::InvalidParams(format!(
+                "recent trades for {} require a symbol",
+                req.category.as_str()
+            )));
+        }
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         parameters.insert("category".into(), req.category.as_str().into());
         if let Some(s) = req.symbol {
@@ -551,6 +714,22 @@ impl MarketData {
         Ok(response)
     }
 
+    /// Retrieves recent trades in the `publicTrade` websocket shape, oldest first, so they
+    /// can backfill the tape before subscribing with `ws_trades`.
+    pub async fn get_public_trades<'a>(
+        &self,
+        req: RecentTradesRequest<'a>,
+    ) -> Result<Vec<WsTrade>, BybitError> {
+        let response = self.get_recent_trades(req).await?;
+        Ok(response
+            .result
+            .list
+            .into_iter()
+            .rev()
+            .map(WsTrade::from)
+            .collect())
+    }
+
     /// Retrieves open interest for a specific market category and symbol over a defined time interval.
     ///
     /// Open interest is the total number of outstanding derivative contracts, such as futures or options,
@@ -586,7 +765,7 @@ impl MarketData {
         };
         parameters.insert("category".into(), category_value.into());
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("intervalTime".into(), req.interval.into());
+        parameters.insert("intervalTime".into(), req.interval.as_str().into());
         if let Some(start_str) = req.start.as_ref().map(|s| s.as_ref()) {
             let start_millis = date_to_milliseconds(start_str);
             parameters
@@ -604,6 +783,9 @@ impl MarketData {
                 .entry("limit".to_owned())
                 .or_insert_with(|| l.to_string());
         }
+        if let Some(cursor) = req.cursor {
+            parameters.insert("cursor".into(), cursor.into());
+        }
         let request = build_request(&parameters);
         let response: OpeninterestResponse = self
             .client
@@ -611,6 +793,25 @@ impl MarketData {
             .await?;
         Ok(response)
     }
+
+    /// Retrieves every open interest entry matching `req`, following `nextPageCursor`
+    /// until the last page. Entries are returned newest first.
+    pub async fn get_all_open_interest<'a>(
+        &self,
+        req: OpenInterestRequest<'a>,
+    ) -> Result<Vec<OpenInterest>, BybitError> {
+        paginate_all(MAX_PAGES, |cursor| {
+            let page = OpenInterestRequest {
+                cursor: cursor.map(Cow::Owned),
+                ..req.clone()
+            };
+            async move {
+                let result = self.get_open_interest(page).await?.result;
+                Ok((result.list, Some(result.next_page_cursor)))
+            }
+        })
+        .await
+    }
     /// Fetches historical volatility data for a specified base coin.
     ///
     /// This function queries historical volatility based on the given base coin and optional
@@ -734,6 +935,11 @@ impl MarketData {
         base_coin: Option<&str>,
         limit: Option<u64>,
     ) -> Result<DeliveryPriceResponse, BybitError> {
+        if matches!(category, Category::Spot) {
+            return Err(BybitError::InvalidParams(
+                "Spot has no delivery price".to_string(),
+            ));
+        }
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         parameters.insert("category".into(), category.as_str().into());
         if let Some(s) = symbol {
@@ -800,4 +1006,86 @@ impl MarketData {
             .await?;
         Ok(response)
     }
+
+    /// Same as `get_longshort_ratio` with a typed `period`, returning the ratios newest first.
+    /// `buy_ratio` and `sell_ratio` are the shares of accounts holding long and short positions.
+    pub async fn get_long_short_ratio(
+        &self,
+        category: Category,
+        symbol: &str,
+        period: OiInterval,
+        limit: Option<u64>,
+    ) -> Result<Vec<LongShortRatio>, BybitError> {
+        let response = self
+            .get_longshort_ratio(category, symbol, period.as_str(), limit)
+            .await?;
+        Ok(response.result.list)
+    }
+
+    /// Retrieves Bybit's server time, in seconds and nanoseconds.
+    pub async fn get_server_time(&self) -> Result<ServerTime, BybitError> {
+        let response: ServerTimeResponse = self.client.get(API::Market(Market::Time), None).await?;
+        Ok(response.result)
+    }
+
+    /// Retrieves one page of Bybit announcements, newest first. Use
+    /// `AnnouncementRequest::maintenance` to find maintenance windows that should pause trading.
+    pub async fn get_announcements<'a>(
+        &self,
+        req: AnnouncementRequest<'a>,
+    ) -> Result<AnnouncementResponse, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("locale".into(), req.locale.into());
+        if let Some(t) = req.announcement_type {
+            parameters.insert("type".into(), t.into());
+        }
+        if let Some(t) = req.tag {
+            parameters.insert("tag".into(), t.into());
+        }
+        if let Some(p) = req.page {
+            parameters.insert("page".into(), p.to_string());
+        }
+        if let Some(l) = req.limit {
+            parameters.insert("limit".into(), l.to_string());
+        }
+        let request = build_request(&parameters);
+        let response: AnnouncementResponse = self
+            .client
+            .get(API::Market(Market::Announcements), Some(request))
+            .await?;
+        Ok(response)
+    }
+
+    /// Retrieves the limits, fees and net value of every leveraged token, or only of `lt_coin`
+    /// (e.g. `BTC3L`).
+    pub async fn get_leverage_token_info(
+        &self,
+        lt_coin: Option<&str>,
+    ) -> Result<Vec<LeverageTokenInfo>, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        if let Some(c) = lt_coin {
+            parameters.insert("ltCoin".into(), c.into());
+        }
+        let request = build_request(&parameters);
+        let response: LeverageTokenInfoResponse = self
+            .client
+            .get(API::SpotLeverage(SpotLeverage::Info), Some(request))
+            .await?;
+        Ok(response.result.list)
+    }
+
+    /// Retrieves the live net asset value, basket and leverage of `lt_coin`.
+    pub async fn get_leverage_token_market(
+        &self,
+        lt_coin: &str,
+    ) -> Result<LeverageTokenMarket, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("ltCoin".into(), lt_coin.into());
+        let request = build_request(&parameters);
+        let response: LeverageTokenMarketResponse = self
+            .client
+            .get(API::SpotLeverage(SpotLeverage::Marketinfo), Some(request))
+            .await?;
+        Ok(response.result)
+    }
 }
@@ -0,0 +1,374 @@
+//! Local stand-ins for Bybit's REST and websocket servers, for testing code built on this crate
+//! without reaching the exchange. Enabled by the `mock` feature.
+//!
+//! Both servers listen on a random localhost port and hand out a `Config` pointing at it, so
+//! any client built with `Bybit::new_with_config` talks to them through the crate's real
+//! request signing, response parsing and event loop.
+//!
+//! ```ignore
+//! let mock = MockClient::start([r#"{"retCode":0,"retMsg":"OK","result":{"orderId":"1","orderLinkId":""},"retExtInfo":{},"time":1}"#]).await?;
+//! let trader: Trader = mock.client(Some("key".into()), Some("secret".into()));
+//! trader.place_futures_limit_order(Category::Linear, "BTCUSDT", Side::Buy, 0.01, 30000.0, PositionIdx::OneWay).await?;
+//! let sent = &mock.requests()[0];
+//! assert_eq!(sent.json()?["price"], "30000");
+//! assert!(sent.is_signed_by(&Credentials::Hmac { key: "key".into(), secret: "secret".into() }));
+//! ```
+
+use crate::api::Bybit;
+use crate::client::Credentials;
+use crate::config::Config;
+use crate::errors::BybitError;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+
+/// A canned REST reply. Strings convert into a `200 OK` reply with that body.
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: String,
+    /// Sent in addition to `Content-Type` and `Content-Length`, e.g. Bybit's rate limit headers.
+    pub headers: Vec<(String, String)>,
+    /// How long to wait before replying, e.g. to exceed the client's timeout.
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            headers: Vec::new(),
+            delay: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_delay(self, delay: Duration) -> Self {
+        Self {
+            delay: Some(delay),
+            ..self
+        }
+    }
+}
+
+impl From<&str> for MockResponse {
+    fn from(body: &str) -> Self {
+        Self::new(200, body)
+    }
+}
+
+impl From<String> for MockResponse {
+    fn from(body: String) -> Self {
+        Self::new(200, body)
+    }
+}
+
+/// A REST request received by `MockClient`, exactly as the crate sent it.
+#[derive(Clone, Debug, Default)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The endpoint path, e.g. `/v5/order/create`.
+    pub path: String,
+    /// The query string without the leading `?`.
+    pub query: String,
+    /// Header names are lowercase.
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// The value of the query parameter `name`, as sent (still URL encoded).
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Parses the body of a POST request.
+    pub fn json(&self) -> Result<Value, BybitError> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
+
+    /// Whether the `X-BAPI-SIGN` header is the signature `credentials` give this request, i.e.
+    /// Bybit would accept it for that key.
+    pub fn is_signed_by(&self, credentials: &Credentials) -> bool {
+        let (Some(timestamp), Some(key), Some(window), Some(signature)) = (
+            self.header("x-bapi-timestamp"),
+            self.header("x-bapi-api-key"),
+            self.header("x-bapi-recv-window"),
+            self.header("x-bapi-sign"),
+        ) else {
+            return false;
+        };
+        let payload = match self.method.as_str() {
+            "GET" => &self.query,
+            _ => &self.body,
+        };
+        key == credentials.api_key()
+            && credentials
+                .sign(&format!("{timestamp}{key}{window}{payload}"))
+                .is_ok_and(|expected| expected == signature)
+    }
+}
+
+/// A local REST server that answers requests with canned replies, in order, and records them.
+///
+/// Requests after the last reply are answered with `404 Not Found`. Connections are kept
+/// alive, so a client's connection pool is exercised as it would be against Bybit.
+pub struct MockClient {
+    config: Config,
+    connections: Arc<AtomicUsize>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockClient {
+    pub async fn start<I>(responses: I) -> Result<Self, BybitError>
+    where
+        I: IntoIterator,
+        I::Item: Into<MockResponse>,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let endpoint = format!("http://{}", listener.local_addr()?);
+        let responses: VecDeque<MockResponse> = responses.into_iter().map(Into::into).collect();
+        let responses = Arc::new(Mutex::new(responses));
+        let connections = Arc::<AtomicUsize>::default();
+        let requests = Arc::<Mutex<Vec<RecordedRequest>>>::default();
+        let (accepted, recorded) = (connections.clone(), requests.clone());
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(serve(socket, responses.clone(), recorded.clone()));
+            }
+        });
+        Ok(Self {
+            config: Config {
+                rest_api_endpoint: Box::leak(endpoint.into_boxed_str()),
+                ..Config::default()
+            },
+            connections,
+            requests,
+        })
+    }
+
+    /// A `Config` whose REST endpoint is this server. The endpoint string is leaked, which is
+    /// fine for the lifetime of a test.
+    pub fn config(&self) -> Config {
+        self.config.clone()
+    }
+
+    /// Builds any of the crate's clients, e.g. `Trader` or `MarketData`, against this server.
+    pub fn client<T: Bybit>(&self, api_key: Option<String>, secret_key: Option<String>) -> T {
+        T::new_with_config(&self.config, api_key, secret_key)
+    }
+
+    /// The requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The number of TCP connections accepted so far.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+}
+
+/// Answers the requests of one connection until the client closes it.
+async fn serve(
+    mut socket: TcpStream,
+    responses: Arc<Mutex<VecDeque<MockResponse>>>,
+    recorded: Arc<Mutex<Vec<RecordedRequest>>>,
+) {
+    while let Some(request) = read_request(&mut socket).await {
+        recorded.lock().unwrap().push(request);
+        let response = responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| MockResponse::new(404, ""));
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
+        let mut head = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+            response.status,
+            response.body.len()
+        );
+        for (name, value) in &response.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        let reply = format!("{head}\r\n{}", response.body);
+        if socket.write_all(reply.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads one HTTP/1.1 request, headers and `Content-Length` body.
+async fn read_request(socket: &mut TcpStream) -> Option<RecordedRequest> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    let header_end = loop {
+        let read = socket.read(&mut buf).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buf[..read]);
+        if let Some(end) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end;
+        }
+    };
+    let head = String::from_utf8_lossy(&data[..header_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let headers: BTreeMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let mut body = data[header_end + 4..].to_vec();
+    while body.len() < length {
+        let read = socket.read(&mut buf).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&buf[..read]);
+    }
+    Some(RecordedRequest {
+        method,
+        // GET urls are built as `{host}/{endpoint}`, which doubles the slash.
+        path: format!("/{}", path.trim_start_matches('/')),
+        query: query.to_string(),
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// How long `MockStream` waits after a subscribe for further requests before playing its script.
+const QUIET_PERIOD: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// A local websocket server that plays a scripted sequence of frames to each connection.
+///
+/// `auth`, `subscribe`, `unsubscribe` and `ping` requests are acknowledged like Bybit does.
+/// The script starts once a connection has subscribed and sent nothing else for 100ms, so every
+/// frame goes through the event loop of the subscription under test. Once it has been played
+/// the connection is closed, which ends a subscription without a `ReconnectConfig` with an
+/// error.
+pub struct MockStream {
+    config: Config,
+    frames: Arc<Mutex<Vec<Value>>>,
+}
+
+impl MockStream {
+    pub async fn start<I>(script: I) -> Result<Self, BybitError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let endpoint = format!("ws://{}", listener.local_addr()?);
+        let script: Arc<Vec<String>> = Arc::new(script.into_iter().map(Into::into).collect());
+        let frames = Arc::<Mutex<Vec<Value>>>::default();
+        let recorded = frames.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(play(socket, script.clone(), recorded.clone()));
+            }
+        });
+        Ok(Self {
+            config: Config {
+                ws_endpoint: Box::leak(endpoint.into_boxed_str()),
+                ..Config::default()
+            },
+            frames,
+        })
+    }
+
+    /// A `Config` whose websocket endpoint is this server, public and private. The endpoint
+    /// string is leaked, which is fine for the lifetime of a test.
+    pub fn config(&self) -> Config {
+        self.config.clone()
+    }
+
+    /// Builds a `Stream` against this server. Pass API keys to use the private helpers.
+    pub fn client<T: Bybit>(&self, api_key: Option<String>, secret_key: Option<String>) -> T {
+        T::new_with_config(&self.config, api_key, secret_key)
+    }
+
+    /// The JSON frames the crate sent so far, on every connection, oldest first.
+    pub fn frames(&self) -> Vec<Value> {
+        self.frames.lock().unwrap().clone()
+    }
+}
+
+async fn play(socket: TcpStream, script: Arc<Vec<String>>, recorded: Arc<Mutex<Vec<Value>>>) {
+    let Ok(mut socket) = tokio_tungstenite::accept_async(socket).await else {
+        return;
+    };
+    let mut subscribed = false;
+    loop {
+        // Large subscriptions arrive as several frames in a row, so the script waits for the
+        // crate to go quiet after its first subscribe.
+        let next = match subscribed {
+            true => match tokio::time::timeout(QUIET_PERIOD, socket.next()).await {
+                Ok(next) => next,
+                Err(_) => break,
+            },
+            false => socket.next().await,
+        };
+        let Some(Ok(message)) = next else { return };
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let Ok(frame) = serde_json::from_str::<Value>(&text) else {
+            continue;
+        };
+        recorded.lock().unwrap().push(frame.clone());
+        let op = frame["op"].as_str().unwrap_or_default();
+        let ack = match op {
+            "auth" => json!({"success": true, "ret_msg": "", "op": "auth", "conn_id": "mock"}),
+            "subscribe" | "unsubscribe" | "ping" => json!({
+                "success": true,
+                "ret_msg": if op == "ping" { "pong" } else { "" },
+                "conn_id": "mock",
+                "req_id": frame["req_id"],
+                "op": op,
+            }),
+            _ => continue,
+        };
+        if socket.send(Message::Text(ack.to_string())).await.is_err() {
+            return;
+        }
+        subscribed |= op == "subscribe";
+    }
+    for frame in script.iter() {
+        if socket.send(Message::Text(frame.clone())).await.is_err() {
+            return;
+        }
+    }
+    let _ = socket.close(None).await;
+}
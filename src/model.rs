@@ -1,8 +1,13 @@
 #![allow(unused_imports)]
 use crate::errors::BybitError;
+use crate::util::{
+    round_to_step, round_to_tick, string_to_float, string_to_float_optional, string_to_u64,
+    RoundingMode,
+};
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, Value};
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{borrow::Cow, collections::BTreeMap, fmt};
 use thiserror::Error;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -34,11 +39,94 @@ pub struct ServerTime {
     pub time_nano: u64,
 }
 
+impl ServerTime {
+    /// The server time in milliseconds, like the timestamps of signed requests.
+    pub fn millis(&self) -> u64 {
+        self.time_nano / 1_000_000
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct AnnouncementRequest<'a> {
+    /// Language of the announcements, e.g. `en-US`.
+    pub locale: Cow<'a, str>,
+    /// Announcement type key, e.g. `maintenance_updates` or `delistings`.
+    pub announcement_type: Option<Cow<'a, str>>,
+    pub tag: Option<Cow<'a, str>>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+impl<'a> AnnouncementRequest<'a> {
+    pub fn new(locale: &'a str) -> AnnouncementRequest<'a> {
+        AnnouncementRequest {
+            locale: Cow::Borrowed(locale),
+            ..Default::default()
+        }
+    }
+
+    /// Only announcements of scheduled maintenance, during which trading may be unavailable.
+    pub fn maintenance(locale: &'a str) -> AnnouncementRequest<'a> {
+        AnnouncementRequest {
+            announcement_type: Some(Cow::Borrowed("maintenance_updates")),
+            ..AnnouncementRequest::new(locale)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnouncementResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: AnnouncementList,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnouncementList {
+    pub total: u64,
+    pub list: Vec<Announcement>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Announcement {
+    pub title: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub announcement_type: AnnouncementType,
+    pub tags: Vec<String>,
+    pub url: String,
+    pub date_timestamp: u64,
+    /// Start of the event the announcement is about, in milliseconds.
+    pub start_date_timestamp: u64,
+    /// End of the event the announcement is about, in milliseconds.
+    pub end_date_timestamp: u64,
+    #[serde(default)]
+    pub publish_time: u64,
+}
+
+impl Announcement {
+    /// Whether `timestamp` (in milliseconds) falls within the announced time range.
+    pub fn is_active_at(&self, timestamp: u64) -> bool {
+        (self.start_date_timestamp..=self.end_date_timestamp).contains(&timestamp)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnnouncementType {
+    pub title: String,
+    pub key: String,
+}
+
 #[derive(Clone, Default)]
 pub struct KlineRequest<'a> {
     pub category: Option<Category>,
     pub symbol: Cow<'a, str>,
-    pub interval: Cow<'a, str>,
+    pub interval: Interval,
     pub start: Option<Cow<'a, str>>,
     pub end: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
@@ -46,12 +134,12 @@ pub struct KlineRequest<'a> {
 
 impl<'a> KlineRequest<'a> {
     pub fn default() -> KlineRequest<'a> {
-        KlineRequest::new(None, "BTCUSDT", "", None, None, None)
+        KlineRequest::new(None, "BTCUSDT", Interval::default(), None, None, None)
     }
     pub fn new(
         category: Option<Category>,
         symbol: &'a str,
-        interval: &'a str,
+        interval: Interval,
         start: Option<&'a str>,
         end: Option<&'a str>,
         limit: Option<u64>,
@@ -59,13 +147,198 @@ impl<'a> KlineRequest<'a> {
         KlineRequest {
             category: category,
             symbol: Cow::Borrowed(symbol),
-            interval: Cow::Borrowed(interval),
+            interval,
             start: start.map(|s| Cow::Borrowed(s)),
             end: end.map(|s| Cow::Borrowed(s)),
             limit,
         }
     }
 }
+/// Kline length (`interval`) of the REST kline endpoints and the `kline` topics. Bybit counts
+/// intraday intervals in minutes, so one minute is `"1"` and not `"1m"`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interval {
+    #[default]
+    #[serde(rename = "1")]
+    OneMinute,
+    #[serde(rename = "3")]
+    ThreeMinutes,
+    #[serde(rename = "5")]
+    FiveMinutes,
+    #[serde(rename = "15")]
+    FifteenMinutes,
+    #[serde(rename = "30")]
+    ThirtyMinutes,
+    #[serde(rename = "60")]
+    OneHour,
+    #[serde(rename = "120")]
+    TwoHours,
+    #[serde(rename = "240")]
+    FourHours,
+    #[serde(rename = "360")]
+    SixHours,
+    #[serde(rename = "720")]
+    TwelveHours,
+    #[serde(rename = "D")]
+    OneDay,
+    #[serde(rename = "W")]
+    OneWeek,
+    #[serde(rename = "M")]
+    OneMonth,
+}
+
+impl Interval {
+    pub const ALL: [Interval; 13] = [
+        Interval::OneMinute,
+        Interval::ThreeMinutes,
+        Interval::FiveMinutes,
+        Interval::FifteenMinutes,
+        Interval::ThirtyMinutes,
+        Interval::OneHour,
+        Interval::TwoHours,
+        Interval::FourHours,
+        Interval::SixHours,
+        Interval::TwelveHours,
+        Interval::OneDay,
+        Interval::OneWeek,
+        Interval::OneMonth,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::OneMinute => "1",
+            Interval::ThreeMinutes => "3",
+            Interval::FiveMinutes => "5",
+            Interval::FifteenMinutes => "15",
+            Interval::ThirtyMinutes => "30",
+            Interval::OneHour => "60",
+            Interval::TwoHours => "120",
+            Interval::FourHours => "240",
+            Interval::SixHours => "360",
+            Interval::TwelveHours => "720",
+            Interval::OneDay => "D",
+            Interval::OneWeek => "W",
+            Interval::OneMonth => "M",
+        }
+    }
+
+    /// The interval lasting `minutes`, e.g. 240 for `FourHours` or 1440 for `OneDay`. Months
+    /// have no fixed length and can only be named directly.
+    pub fn from_minutes(minutes: u32) -> Result<Self, BybitError> {
+        match minutes {
+            1440 => Ok(Interval::OneDay),
+            10080 => Ok(Interval::OneWeek),
+            _ => minutes.to_string().parse(),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Interval {
+    type Err = BybitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Interval::ALL
+            .into_iter()
+            .find(|interval| interval.as_str() == s)
+            .ok_or_else(|| BybitError::InvalidParams(format!("Invalid kline interval: {}", s)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenInfoResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: LeverageTokenInfoSummary,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LeverageTokenInfoSummary {
+    pub list: Vec<LeverageTokenInfo>,
+}
+
+/// Purchase and redemption limits and fees of a leveraged token, e.g. `BTC3L`.
+///
+/// `lt_status` is `1` when the token can be purchased and redeemed, `2` purchase only, `3`
+/// redeem only, `4` neither and `5` while its position is being adjusted.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenInfo {
+    pub lt_coin: String,
+    pub lt_name: String,
+    #[serde(with = "string_to_float")]
+    pub max_purchase: f64,
+    #[serde(with = "string_to_float")]
+    pub min_purchase: f64,
+    #[serde(with = "string_to_float")]
+    pub max_purchase_daily: f64,
+    #[serde(with = "string_to_float")]
+    pub max_redeem: f64,
+    #[serde(with = "string_to_float")]
+    pub min_redeem: f64,
+    #[serde(with = "string_to_float")]
+    pub max_redeem_daily: f64,
+    #[serde(with = "string_to_float")]
+    pub purchase_fee_rate: f64,
+    #[serde(with = "string_to_float")]
+    pub redeem_fee_rate: f64,
+    pub lt_status: String,
+    #[serde(with = "string_to_float")]
+    pub fund_fee: f64,
+    #[serde(with = "string_to_u64")]
+    pub fund_fee_time: u64,
+    #[serde(with = "string_to_float")]
+    pub manage_fee_rate: f64,
+    #[serde(with = "string_to_u64")]
+    pub manage_fee_time: u64,
+    /// Total value of the token's position.
+    #[serde(with = "string_to_float")]
+    pub value: f64,
+    /// Net asset value of one token.
+    #[serde(with = "string_to_float")]
+    pub net_value: f64,
+    /// Tokens in circulation.
+    #[serde(with = "string_to_float")]
+    pub total: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenMarketResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: LeverageTokenMarket,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+/// The live net asset value and basket of a leveraged token.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenMarket {
+    pub lt_coin: String,
+    #[serde(with = "string_to_float")]
+    pub nav: f64,
+    #[serde(with = "string_to_u64")]
+    pub nav_time: u64,
+    #[serde(with = "string_to_float")]
+    pub circulation: f64,
+    /// Position held per token, negative for short tokens.
+    #[serde(with = "string_to_float")]
+    pub basket: f64,
+    /// Real leverage of the token.
+    #[serde(with = "string_to_float")]
+    pub leverage: f64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct KlineResponse {
@@ -92,12 +365,18 @@ pub struct KlineSummary {
 pub struct Kline {
     #[serde(with = "string_to_u64")]
     pub start_time: u64,
-    pub open_price: String,
-    pub high_price: String,
-    pub low_price: String,
-    pub close_price: String,
-    pub volume: String,
-    pub quote_asset_volume: String,
+    #[serde(with = "string_to_float")]
+    pub open_price: f64,
+    #[serde(with = "string_to_float")]
+    pub high_price: f64,
+    #[serde(with = "string_to_float")]
+    pub low_price: f64,
+    #[serde(with = "string_to_float")]
+    pub close_price: f64,
+    #[serde(with = "string_to_float")]
+    pub volume: f64,
+    #[serde(with = "string_to_float")]
+    pub quote_asset_volume: f64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -348,6 +627,135 @@ pub struct OptionsInstrument {
     pub lot_size_filter: LotSizeFilter,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentsInfoResponse {
+    #[serde(rename = "retCode")]
+    pub ret_code: i16,
+    #[serde(rename = "retMsg")]
+    pub ret_msg: String,
+    pub result: InstrumentsInfo,
+    #[serde(rename = "retExtInfo")]
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentsInfo {
+    pub category: String,
+    pub list: Vec<InstrumentInfo>,
+    #[serde(rename = "nextPageCursor", default)]
+    pub next_page_cursor: String,
+}
+
+/// The fields shared by spot, futures and option instruments. Spot instruments
+/// have no `leverageFilter`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentInfo {
+    pub symbol: String,
+    pub status: String,
+    #[serde(rename = "baseCoin")]
+    pub base_coin: String,
+    #[serde(rename = "quoteCoin")]
+    pub quote_coin: String,
+    #[serde(rename = "priceFilter")]
+    pub price_filter: PriceFilter,
+    #[serde(rename = "lotSizeFilter")]
+    pub lot_size_filter: LotSizeFilter,
+    #[serde(rename = "leverageFilter", default)]
+    pub leverage_filter: Option<LeverageFilter>,
+}
+
+impl InstrumentInfo {
+    /// Rounds a price to the nearest multiple of the tick size.
+    pub fn round_price(&self, price: f64) -> f64 {
+        round_to_tick(price, self.price_filter.tick_size, RoundingMode::Nearest)
+    }
+
+    /// Rounds a quantity down to the quantity step, so the order never exceeds
+    /// the amount asked for. Spot instruments use `basePrecision` as their step.
+    pub fn round_qty(&self, qty: f64) -> f64 {
+        let step = self
+            .lot_size_filter
+            .qty_step
+            .or(self.lot_size_filter.base_precision);
+        match step {
+            Some(step) => round_to_step(qty, step),
+            None => qty,
+        }
+    }
+
+    /// Checks an order against the instrument's filters before it is sent, so that orders Bybit
+    /// would reject (e.g. retCode 110007 or 170131) fail locally. `price` is `None` for market
+    /// orders, which skips the price and notional checks.
+    ///
+    /// Returns `BybitError::InvalidParams` naming the first violated filter.
+    pub fn validate_order(&self, price: Option<f64>, qty: f64) -> Result<(), BybitError> {
+        let invalid =
+            |msg: String| Err(BybitError::InvalidParams(format!("{}: {msg}", self.symbol)));
+        let prices = &self.price_filter;
+        let lots = &self.lot_size_filter;
+        if let Some(price) = price {
+            if !on_step(price, prices.tick_size) {
+                return invalid(format!(
+                    "price {price} is not a multiple of tickSize {}",
+                    prices.tick_size
+                ));
+            }
+            if let Some(min) = prices.min_price.filter(|min| price < *min) {
+                return invalid(format!("price {price} is below minPrice {min}"));
+            }
+            if let Some(max) = prices.max_price.filter(|max| price > *max) {
+                return invalid(format!("price {price} is above maxPrice {max}"));
+            }
+        }
+        if let Some(step) = lots.qty_step.or(lots.base_precision) {
+            if !on_step(qty, step) {
+                let filter = if lots.qty_step.is_some() {
+                    "qtyStep"
+                } else {
+                    "basePrecision"
+                };
+                return invalid(format!("qty {qty} is not a multiple of {filter} {step}"));
+            }
+        }
+        if qty < lots.min_order_qty {
+            return invalid(format!(
+                "qty {qty} is below minOrderQty {}",
+                lots.min_order_qty
+            ));
+        }
+        if qty > lots.max_order_qty {
+            return invalid(format!(
+                "qty {qty} is above maxOrderQty {}",
+                lots.max_order_qty
+            ));
+        }
+        if let Some(price) = price {
+            let notional = price * qty;
+            if let Some(min) = lots.min_notional_value.filter(|min| notional < *min) {
+                return invalid(format!(
+                    "order value {notional} is below minNotionalValue {min}"
+                ));
+            }
+            if let Some(min) = lots.min_order_amt.filter(|min| notional < *min) {
+                return invalid(format!("order value {notional} is below minOrderAmt {min}"));
+            }
+            if let Some(max) = lots.max_order_amt.filter(|max| notional > *max) {
+                return invalid(format!("order value {notional} is above maxOrderAmt {max}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `value` is a whole multiple of `step`, allowing for float noise.
+fn on_step(value: f64, step: f64) -> bool {
+    step <= 0.0 || (round_to_tick(value, step, RoundingMode::Nearest) - value).abs() <= step * 1e-6
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RiskParameters {
@@ -371,10 +779,10 @@ pub struct LeverageFilter {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PriceFilter {
-    #[serde(rename = "minPrice")]
-    pub min_price: Option<String>,
-    #[serde(rename = "maxPrice")]
-    pub max_price: Option<String>,
+    #[serde(rename = "minPrice", with = "string_to_float_optional", default)]
+    pub min_price: Option<f64>,
+    #[serde(rename = "maxPrice", with = "string_to_float_optional", default)]
+    pub max_price: Option<f64>,
     #[serde(rename = "tickSize", with = "string_to_float")]
     pub tick_size: f64,
 }
@@ -382,25 +790,60 @@ pub struct PriceFilter {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LotSizeFilter {
-    #[serde(rename = "basePrecision", skip_serializing_if = "Option::is_none")]
-    pub base_precision: Option<String>,
-    #[serde(rename = "quotePrecision", skip_serializing_if = "Option::is_none")]
-    pub quote_precision: Option<String>,
+    #[serde(
+        rename = "basePrecision",
+        with = "string_to_float_optional",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub base_precision: Option<f64>,
+    #[serde(
+        rename = "quotePrecision",
+        with = "string_to_float_optional",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub quote_precision: Option<f64>,
     #[serde(rename = "minOrderQty", with = "string_to_float")]
     pub min_order_qty: f64,
     #[serde(rename = "maxOrderQty", with = "string_to_float")]
     pub max_order_qty: f64,
-    #[serde(rename = "minOrderAmt", skip_serializing_if = "Option::is_none")]
-    pub min_order_amt: Option<String>,
-    #[serde(rename = "maxOrderAmt", skip_serializing_if = "Option::is_none")]
-    pub max_order_amt: Option<String>,
-    #[serde(rename = "qtyStep", skip_serializing_if = "Option::is_none")]
-    pub qty_step: Option<String>,
+    #[serde(
+        rename = "minOrderAmt",
+        with = "string_to_float_optional",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_order_amt: Option<f64>,
+    #[serde(
+        rename = "maxOrderAmt",
+        with = "string_to_float_optional",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_order_amt: Option<f64>,
+    #[serde(
+        rename = "qtyStep",
+        with = "string_to_float_optional",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub qty_step: Option<f64>,
+    /// Smallest order value (price * qty) of linear instruments, in the quote coin.
+    #[serde(
+        rename = "minNotionalValue",
+        with = "string_to_float_optional",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_notional_value: Option<f64>,
     #[serde(
         rename = "postOnlyMaxOrderQty",
+        with = "string_to_float_optional",
+        default,
         skip_serializing_if = "Option::is_none"
     )]
-    pub post_only_max_order_qty: Option<String>,
+    pub post_only_max_order_qty: Option<f64>,
 }
 
 #[derive(Clone, Default)]
@@ -449,6 +892,8 @@ pub struct OrderBook {
     pub timestamp: u64,
     #[serde(rename = "u")]
     pub update_id: u64,
+    #[serde(default)]
+    pub seq: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -599,6 +1044,105 @@ pub struct SpotTicker {
     pub usd_index_price: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionTicker {
+    pub symbol: String,
+    #[serde(rename = "bid1Price", with = "string_to_float")]
+    pub bid_price: f64,
+    #[serde(rename = "bid1Size", with = "string_to_float")]
+    pub bid_size: f64,
+    #[serde(rename = "bid1Iv", with = "string_to_float")]
+    pub bid_iv: f64,
+    #[serde(rename = "ask1Price", with = "string_to_float")]
+    pub ask_price: f64,
+    #[serde(rename = "ask1Size", with = "string_to_float")]
+    pub ask_size: f64,
+    #[serde(rename = "ask1Iv", with = "string_to_float")]
+    pub ask_iv: f64,
+    #[serde(with = "string_to_float")]
+    pub last_price: f64,
+    #[serde(rename = "highPrice24h", with = "string_to_float")]
+    pub high_24h: f64,
+    #[serde(rename = "lowPrice24h", with = "string_to_float")]
+    pub low_24h: f64,
+    #[serde(with = "string_to_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_to_float")]
+    pub index_price: f64,
+    #[serde(with = "string_to_float")]
+    pub mark_iv: f64,
+    #[serde(with = "string_to_float")]
+    pub underlying_price: f64,
+    #[serde(with = "string_to_float")]
+    pub open_interest: f64,
+    #[serde(rename = "turnover24h")]
+    pub turnover_24h: String,
+    #[serde(rename = "volume24h")]
+    pub volume_24h: String,
+    pub total_volume: String,
+    pub total_turnover: String,
+    #[serde(with = "string_to_float")]
+    pub delta: f64,
+    #[serde(with = "string_to_float")]
+    pub gamma: f64,
+    #[serde(with = "string_to_float")]
+    pub vega: f64,
+    #[serde(with = "string_to_float")]
+    pub theta: f64,
+    pub predicted_delivery_price: String,
+    #[serde(rename = "change24h")]
+    pub change_24h: String,
+}
+
+/// A ticker from `get_tickers`, in the shape of its category. Linear and inverse
+/// contracts share the futures shape; options carry greeks and implied volatility.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Ticker {
+    Option(OptionTicker),
+    Futures(FuturesTicker),
+    Spot(SpotTicker),
+}
+
+impl Ticker {
+    pub fn symbol(&self) -> &str {
+        match self {
+            Ticker::Option(t) => &t.symbol,
+            Ticker::Futures(t) => &t.symbol,
+            Ticker::Spot(t) => &t.symbol,
+        }
+    }
+
+    pub fn last_price(&self) -> f64 {
+        match self {
+            Ticker::Option(t) => t.last_price,
+            Ticker::Futures(t) => t.last_price,
+            Ticker::Spot(t) => t.last_price,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TickersResponse {
+    #[serde(rename = "retCode")]
+    pub ret_code: i16,
+    #[serde(rename = "retMsg")]
+    pub ret_msg: String,
+    pub result: TickersInfo,
+    #[serde(rename = "retExtInfo")]
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TickersInfo {
+    pub category: String,
+    pub list: Vec<Ticker>,
+}
+
 #[derive(Clone, Default)]
 pub struct FundingHistoryRequest<'a> {
     pub category: Category,
@@ -682,6 +1226,10 @@ impl<'a> RecentTradesRequest<'a> {
             limit,
         }
     }
+    /// Recent option trades are queried by base coin rather than by symbol.
+    pub fn option(base_coin: &'a str, limit: Option<u64>) -> RecentTradesRequest<'a> {
+        RecentTradesRequest::new(Category::Option, None, Some(base_coin), limit)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -715,30 +1263,56 @@ pub struct RecentTrade {
     #[serde(rename = "size", with = "string_to_float")]
     pub qty: f64,
     pub side: String,
-    #[serde(rename = "time")]
-    pub timestamp: String,
+    #[serde(rename = "time", with = "string_to_u64")]
+    pub timestamp: u64,
     #[serde(rename = "isBlockTrade")]
     pub is_block_trade: bool,
 }
 
+/// Converts a REST trade into the `publicTrade` websocket shape so a backfilled tape
+/// and the live stream can be handled as one series. The REST endpoint does not send
+/// a tick direction, so `tick_direction` is left empty.
+impl From<RecentTrade> for WsTrade {
+    fn from(trade: RecentTrade) -> Self {
+        WsTrade {
+            timestamp: trade.timestamp,
+            symbol: trade.symbol,
+            side: trade.side,
+            volume: trade.qty,
+            price: trade.price,
+            tick_direction: String::new(),
+            id: trade.exec_id,
+            buyer_is_maker: trade.is_block_trade,
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct OpenInterestRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
-    pub interval: Cow<'a, str>,
+    pub interval: OiInterval,
     pub start: Option<Cow<'a, str>>,
     pub end: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> OpenInterestRequest<'a> {
     pub fn default() -> OpenInterestRequest<'a> {
-        OpenInterestRequest::new(Category::Linear, "BTCUSDT", "4h", None, None, None)
+        OpenInterestRequest::new(
+            Category::Linear,
+            "BTCUSDT",
+            OiInterval::FourHours,
+            None,
+            None,
+            None,
+        )
     }
     pub fn new(
         category: Category,
         symbol: &'a str,
-        interval: &'a str,
+        interval: OiInterval,
         start: Option<&'a str>,
         end: Option<&'a str>,
         limit: Option<u64>,
@@ -746,10 +1320,42 @@ impl<'a> OpenInterestRequest<'a> {
         OpenInterestRequest {
             category,
             symbol: Cow::Borrowed(symbol),
-            interval: Cow::Borrowed(interval),
+            interval,
             start: start.map(|s| Cow::Borrowed(s)),
             end: end.map(|s| Cow::Borrowed(s)),
             limit,
+            cursor: None,
+        }
+    }
+}
+
+/// Bucket size of the open interest history (`intervalTime`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OiInterval {
+    #[serde(rename = "5min")]
+    FiveMinutes,
+    #[serde(rename = "15min")]
+    FifteenMinutes,
+    #[serde(rename = "30min")]
+    ThirtyMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[default]
+    #[serde(rename = "4h")]
+    FourHours,
+    #[serde(rename = "1d")]
+    OneDay,
+}
+
+impl OiInterval {
+    pub fn as_str(&self) -> &str {
+        match self {
+            OiInterval::FiveMinutes => "5min",
+            OiInterval::FifteenMinutes => "15min",
+            OiInterval::ThirtyMinutes => "30min",
+            OiInterval::OneHour => "1h",
+            OiInterval::FourHours => "4h",
+            OiInterval::OneDay => "1d",
         }
     }
 }
@@ -772,7 +1378,11 @@ pub struct OpenInterestSummary {
     pub symbol: String,
     pub category: String,
     pub list: Vec<OpenInterest>,
-    #[serde(rename = "nextPageCursor", skip_serializing_if = "String::is_empty")]
+    #[serde(
+        rename = "nextPageCursor",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
     pub next_page_cursor: String,
 }
 
@@ -912,8 +1522,11 @@ pub struct RiskLimit {
     pub initial_margin: f64,
     #[serde(rename = "isLowestRisk")]
     pub is_lowest_risk: u8,
-    #[serde(rename = "maxLeverage")]
-    pub max_leverage: String,
+    #[serde(rename = "maxLeverage", with = "string_to_float")]
+    pub max_leverage: f64,
+    /// Maintenance margin deduction; empty for inverse contracts.
+    #[serde(rename = "mmDeduction", default, with = "string_to_float_optional")]
+    pub mm_deduction: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -939,7 +1552,8 @@ pub struct DeliveryPriceSummary {
 #[serde(rename_all = "camelCase")]
 pub struct DeliveryPrice {
     pub symbol: String,
-    pub delivery_price: String,
+    #[serde(with = "string_to_float")]
+    pub delivery_price: f64,
     #[serde(with = "string_to_u64")]
     pub delivery_time: u64,
 }
@@ -998,7 +1612,7 @@ impl Category {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Side {
     #[default]
     Buy,
@@ -1014,7 +1628,7 @@ impl Side {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum OrderType {
     Limit,
     #[default]
@@ -1030,7 +1644,7 @@ impl OrderType {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum TimeInForce {
     #[default]
     GTC,
@@ -1049,6 +1663,55 @@ impl TimeInForce {
         }
     }
 }
+
+/// Lifecycle state of an order, as sent in `orderStatus`.
+///
+/// The type is named `OrderState` because `OrderStatus` is already taken by the
+/// `orderId`/`orderLinkId` pair that order entry endpoints return.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderState {
+    Created,
+    New,
+    Rejected,
+    PartiallyFilled,
+    PartiallyFilledCanceled,
+    Filled,
+    Cancelled,
+    Untriggered,
+    Triggered,
+    Deactivated,
+    Active,
+}
+
+impl OrderState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            OrderState::Created => "Created",
+            OrderState::New => "New",
+            OrderState::Rejected => "Rejected",
+            OrderState::PartiallyFilled => "PartiallyFilled",
+            OrderState::PartiallyFilledCanceled => "PartiallyFilledCanceled",
+            OrderState::Filled => "Filled",
+            OrderState::Cancelled => "Cancelled",
+            OrderState::Untriggered => "Untriggered",
+            OrderState::Triggered => "Triggered",
+            OrderState::Deactivated => "Deactivated",
+            OrderState::Active => "Active",
+        }
+    }
+
+    /// Whether the order can no longer fill.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            OrderState::Rejected
+                | OrderState::PartiallyFilledCanceled
+                | OrderState::Filled
+                | OrderState::Cancelled
+                | OrderState::Deactivated
+        )
+    }
+}
 #[derive(Clone, Default, Serialize)]
 pub struct OrderRequest<'a> {
     pub category: Category,                 // String
@@ -1062,10 +1725,10 @@ pub struct OrderRequest<'a> {
     pub trigger_direction: Option<bool>,    // String
     pub order_filter: Option<Cow<'a, str>>, // String
     pub trigger_price: Option<f64>,
-    pub trigger_by: Option<Cow<'a, str>>,    // String
-    pub order_iv: Option<f64>,               // String
-    pub time_in_force: Option<Cow<'a, str>>, // String
-    pub position_idx: Option<u8>,
+    pub trigger_by: Option<Cow<'a, str>>,   // String
+    pub order_iv: Option<f64>,              // String
+    pub time_in_force: Option<TimeInForce>, // String
+    pub position_idx: Option<PositionIdx>,
     pub order_link_id: Option<Cow<'a, str>>,
     pub take_profit: Option<f64>,
     pub stop_loss: Option<f64>,
@@ -1078,8 +1741,8 @@ pub struct OrderRequest<'a> {
     pub tpsl_mode: Option<Cow<'a, str>>,
     pub tp_limit_price: Option<f64>,
     pub sl_limit_price: Option<f64>,
-    pub tp_order_type: Option<Cow<'a, str>>,
-    pub sl_order_type: Option<Cow<'a, str>>,
+    pub tp_order_type: Option<OrderType>,
+    pub sl_order_type: Option<OrderType>,
 }
 
 impl<'a> OrderRequest<'a> {
@@ -1130,8 +1793,8 @@ impl<'a> OrderRequest<'a> {
         trigger_price: Option<f64>,
         trigger_by: Option<&'a str>,
         order_iv: Option<f64>,
-        time_in_force: Option<&'a str>,
-        position_idx: Option<u8>,
+        time_in_force: Option<TimeInForce>,
+        position_idx: Option<PositionIdx>,
         order_link_id: Option<&'a str>,
         take_profit: Option<f64>,
         stop_loss: Option<f64>,
@@ -1144,8 +1807,8 @@ impl<'a> OrderRequest<'a> {
         tpsl_mode: Option<&'a str>,
         tp_limit_price: Option<f64>,
         sl_limit_price: Option<f64>,
-        tp_order_type: Option<&'a str>,
-        sl_order_type: Option<&'a str>,
+        tp_order_type: Option<OrderType>,
+        sl_order_type: Option<OrderType>,
     ) -> Self {
         Self {
             category,
@@ -1161,7 +1824,7 @@ impl<'a> OrderRequest<'a> {
             trigger_price,
             trigger_by: trigger_by.map(Cow::Borrowed),
             order_iv,
-            time_in_force: time_in_force.map(Cow::Borrowed),
+            time_in_force,
             position_idx,
             order_link_id: order_link_id.map(Cow::Borrowed),
             take_profit,
@@ -1175,10 +1838,36 @@ impl<'a> OrderRequest<'a> {
             tpsl_mode: tpsl_mode.map(Cow::Borrowed),
             tp_limit_price,
             sl_limit_price,
-            tp_order_type: tp_order_type.map(Cow::Borrowed),
-            sl_order_type: sl_order_type.map(Cow::Borrowed),
+            tp_order_type,
+            sl_order_type,
+        }
+    }
+    /// A market order for any category; set further fields with struct update syntax.
+    pub fn market(category: Category, symbol: &'a str, side: Side, qty: f64) -> Self {
+        Self {
+            category,
+            symbol: Cow::Borrowed(symbol),
+            side,
+            order_type: OrderType::Market,
+            qty,
+            ..Self::default()
+        }
+    }
+
+    /// A good-till-cancelled limit order for any category.
+    pub fn limit(category: Category, symbol: &'a str, side: Side, qty: f64, price: f64) -> Self {
+        Self {
+            category,
+            symbol: Cow::Borrowed(symbol),
+            side,
+            order_type: OrderType::Limit,
+            qty,
+            price: Some(price),
+            time_in_force: Some(TimeInForce::GTC),
+            ..Self::default()
         }
     }
+
     pub fn spot_limit_with_market_tpsl(
         symbol: &'a str,
         side: Side,
@@ -1194,11 +1883,11 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Limit,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::PostOnly.as_str())),
+            time_in_force: Some(TimeInForce::PostOnly),
             take_profit: Some(tp),
             stop_loss: Some(sl),
-            tp_order_type: Some(Cow::Borrowed("Market")),
-            sl_order_type: Some(Cow::Borrowed("Market")),
+            tp_order_type: Some(OrderType::Market),
+            sl_order_type: Some(OrderType::Market),
             ..Self::default()
         }
     }
@@ -1217,13 +1906,13 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Limit,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::PostOnly.as_str())),
+            time_in_force: Some(TimeInForce::PostOnly),
             take_profit: Some(tp),
             stop_loss: Some(sl),
             tp_limit_price: Some(tp),
             sl_limit_price: Some(sl),
-            tp_order_type: Some(Cow::Borrowed("Limit")),
-            sl_order_type: Some(Cow::Borrowed("Limit")),
+            tp_order_type: Some(OrderType::Limit),
+            sl_order_type: Some(OrderType::Limit),
             ..Self::default()
         }
     }
@@ -1235,7 +1924,7 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Limit,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::PostOnly.as_str())),
+            time_in_force: Some(TimeInForce::PostOnly),
             ..Self::default()
         }
     }
@@ -1253,7 +1942,7 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Limit,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::GTC.as_str())),
+            time_in_force: Some(TimeInForce::GTC),
             order_link_id: order_link_id.map(Cow::Borrowed),
             order_filter: Some(Cow::Borrowed("tpslOrder")),
             ..Self::default()
@@ -1267,7 +1956,7 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Market,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::PostOnly.as_str())),
+            time_in_force: Some(TimeInForce::PostOnly),
             is_leverage: Some(true),
             ..Self::default()
         }
@@ -1280,7 +1969,7 @@ impl<'a> OrderRequest<'a> {
             side,
             order_type: OrderType::Market,
             qty,
-            time_in_force: Some(Cow::Borrowed(TimeInForce::IOC.as_str())),
+            time_in_force: Some(TimeInForce::IOC),
             ..Self::default()
         }
     }
@@ -1300,13 +1989,13 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Limit,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::PostOnly.as_str())),
+            time_in_force: Some(TimeInForce::PostOnly),
             reduce_only: Some(false),
             take_profit: Some(tp),
             stop_loss: Some(sl),
             tpsl_mode: Some(Cow::Borrowed("Full")),
-            tp_order_type: Some(Cow::Borrowed("Market")),
-            sl_order_type: Some(Cow::Borrowed("Market")),
+            tp_order_type: Some(OrderType::Market),
+            sl_order_type: Some(OrderType::Market),
             ..Self::default()
         }
     }
@@ -1326,13 +2015,13 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Limit,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::PostOnly.as_str())),
+            time_in_force: Some(TimeInForce::PostOnly),
             reduce_only: Some(false),
             take_profit: Some(tp),
             stop_loss: Some(sl),
             tpsl_mode: Some(Cow::Borrowed("Partial")),
-            tp_order_type: Some(Cow::Borrowed("Limit")),
-            sl_order_type: Some(Cow::Borrowed("Limit")),
+            tp_order_type: Some(OrderType::Limit),
+            sl_order_type: Some(OrderType::Limit),
             tp_limit_price: Some(tp),
             sl_limit_price: Some(sl),
             ..Self::default()
@@ -1346,7 +2035,7 @@ impl<'a> OrderRequest<'a> {
             side,
             order_type: OrderType::Market,
             qty,
-            time_in_force: Some(Cow::Borrowed(TimeInForce::IOC.as_str())),
+            time_in_force: Some(TimeInForce::IOC),
             reduce_only: Some(false),
             ..Self::default()
         }
@@ -1366,7 +2055,7 @@ impl<'a> OrderRequest<'a> {
             order_type: OrderType::Limit,
             qty,
             price: Some(price),
-            time_in_force: Some(Cow::Borrowed(TimeInForce::GTC.as_str())),
+            time_in_force: Some(TimeInForce::GTC),
             order_link_id: Some(Cow::Borrowed(order_link_id)),
             reduce_only: Some(true),
             ..Self::default()
@@ -1380,7 +2069,7 @@ impl<'a> OrderRequest<'a> {
             side,
             order_type: OrderType::Market,
             qty,
-            time_in_force: Some(Cow::Borrowed(TimeInForce::IOC.as_str())),
+            time_in_force: Some(TimeInForce::IOC),
             reduce_only: Some(true),
             ..Self::default()
         }
@@ -1404,7 +2093,7 @@ pub struct AmendOrderRequest<'a> {
     pub order_link_id: Option<Cow<'a, str>>,
     pub order_iv: Option<f64>, // String
     pub trigger_price: Option<f64>,
-    pub qty: f64,           // String
+    pub qty: Option<f64>,   // String
     pub price: Option<f64>, // String
     pub tpsl_mode: Option<Cow<'a, str>>,
     pub take_profit: Option<f64>,
@@ -1425,7 +2114,7 @@ impl<'a> AmendOrderRequest<'a> {
             order_link_id: None,
             order_iv: None,
             trigger_price: None,
-            qty: 0.00,
+            qty: None,
             price: None,
             tpsl_mode: None,
             take_profit: None,
@@ -1444,7 +2133,7 @@ impl<'a> AmendOrderRequest<'a> {
         order_link_id: Option<&'a str>,
         order_iv: Option<f64>,
         trigger_price: Option<f64>,
-        qty: f64,
+        qty: Option<f64>,
         price: Option<f64>,
         tpsl_mode: Option<&'a str>,
         take_profit: Option<f64>,
@@ -1498,7 +2187,7 @@ pub struct CancelOrderResponse {
 #[derive(Clone, Default)]
 pub struct OpenOrdersRequest<'a> {
     pub category: Category,
-    pub symbol: Cow<'a, str>,
+    pub symbol: Option<Cow<'a, str>>,
     pub base_coin: Option<Cow<'a, str>>,
     pub settle_coin: Option<Cow<'a, str>>,
     pub order_id: Option<Cow<'a, str>>,
@@ -1506,13 +2195,14 @@ pub struct OpenOrdersRequest<'a> {
     pub open_only: Option<usize>,
     pub order_filter: Option<Cow<'a, str>>,
     pub limit: Option<usize>,
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> OpenOrdersRequest<'a> {
     pub fn default() -> Self {
         Self {
             category: Category::Linear,
-            symbol: Cow::Borrowed("BTCUSDT"),
+            symbol: Some(Cow::Borrowed("BTCUSDT")),
             base_coin: None,
             settle_coin: None,
             order_id: None,
@@ -1520,6 +2210,7 @@ impl<'a> OpenOrdersRequest<'a> {
             open_only: None,
             order_filter: None,
             limit: None,
+            cursor: None,
         }
     }
 
@@ -1536,7 +2227,7 @@ impl<'a> OpenOrdersRequest<'a> {
     ) -> Self {
         Self {
             category,
-            symbol: Cow::Borrowed(symbol),
+            symbol: Some(Cow::Borrowed(symbol)),
             base_coin: base_coin.map(Cow::Borrowed),
             settle_coin: settle_coin.map(Cow::Borrowed),
             order_id: order_id.map(Cow::Borrowed),
@@ -1547,6 +2238,17 @@ impl<'a> OpenOrdersRequest<'a> {
             },
             order_filter: order_filter.map(Cow::Borrowed),
             limit,
+            cursor: None,
+        }
+    }
+
+    /// Every open order settled in `settle_coin`, across all symbols.
+    pub fn for_settle_coin(category: Category, settle_coin: &'a str) -> Self {
+        Self {
+            category,
+            symbol: None,
+            settle_coin: Some(Cow::Borrowed(settle_coin)),
+            ..Self::default()
         }
     }
 }
@@ -1592,10 +2294,11 @@ pub struct OrderHistoryRequest<'a> {
     pub order_id: Option<Cow<'a, str>>,
     pub order_link_id: Option<Cow<'a, str>>,
     pub order_filter: Option<Cow<'a, str>>,
-    pub order_status: Option<Cow<'a, str>>,
+    pub order_status: Option<OrderState>,
     pub start_time: Option<Cow<'a, str>>,
     pub end_time: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> OrderHistoryRequest<'a> {
@@ -1612,6 +2315,7 @@ impl<'a> OrderHistoryRequest<'a> {
             start_time: None,
             end_time: None,
             limit: None,
+            cursor: None,
         }
     }
     pub fn new(
@@ -1622,7 +2326,7 @@ impl<'a> OrderHistoryRequest<'a> {
         order_id: Option<&'a str>,
         order_link_id: Option<&'a str>,
         order_filter: Option<&'a str>,
-        order_status: Option<&'a str>,
+        order_status: Option<OrderState>,
         start_time: Option<&'a str>,
         end_time: Option<&'a str>,
         limit: Option<u64>,
@@ -1635,10 +2339,11 @@ impl<'a> OrderHistoryRequest<'a> {
             order_id: order_id.map(Cow::Borrowed),
             order_link_id: order_link_id.map(Cow::Borrowed),
             order_filter: order_filter.map(Cow::Borrowed),
-            order_status: order_status.map(Cow::Borrowed),
+            order_status,
             start_time: start_time.map(Cow::Borrowed),
             end_time: end_time.map(Cow::Borrowed),
             limit,
+            cursor: None,
         }
     }
 }
@@ -1661,10 +2366,22 @@ pub struct OrderHistoryResponse {
 pub struct OrderHistory {
     pub category: String,
     pub list: Vec<Orders>,
-    #[serde(rename = "nextPageCursor", skip_serializing_if = "String::is_empty")]
+    #[serde(
+        rename = "nextPageCursor",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
     pub next_page_cursor: String,
 }
 
+impl OrderHistory {
+    /// Splits the page into its orders and the cursor of the next page, if any.
+    pub fn into_page(self) -> (Vec<Orders>, Option<String>) {
+        let cursor = Some(self.next_page_cursor).filter(|cursor| !cursor.is_empty());
+        (self.list, cursor)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Orders {
@@ -1690,8 +2407,8 @@ pub struct Orders {
     pub cancel_type: String,
     #[serde(rename = "rejectReason")]
     pub reject_reason: String,
-    #[serde(rename = "avgPrice", with = "string_to_float")]
-    pub avg_price: f64,
+    #[serde(rename = "avgPrice", with = "string_to_float_optional", default)]
+    pub avg_price: Option<f64>,
     #[serde(rename = "leavesQty", with = "string_to_float")]
     pub leaves_qty: f64,
     #[serde(rename = "leavesValue", with = "string_to_float")]
@@ -1703,19 +2420,19 @@ pub struct Orders {
     #[serde(rename = "cumExecFee", with = "string_to_float")]
     pub cum_exec_fee: f64,
     #[serde(rename = "timeInForce")]
-    pub time_in_force: String,
+    pub time_in_force: TimeInForce,
     #[serde(rename = "orderType")]
     pub order_type: OrderType,
     #[serde(rename = "stopOrderType")]
     pub stop_order_type: String,
     #[serde(rename = "orderIv", skip_serializing_if = "String::is_empty")]
     pub order_iv: String,
-    #[serde(rename = "triggerPrice", with = "string_to_float")]
-    pub trigger_price: f64,
-    #[serde(rename = "takeProfit", with = "string_to_float")]
-    pub take_profit: f64,
-    #[serde(rename = "stopLoss", with = "string_to_float")]
-    pub stop_loss: f64,
+    #[serde(rename = "triggerPrice", with = "string_to_float_optional", default)]
+    pub trigger_price: Option<f64>,
+    #[serde(rename = "takeProfit", with = "string_to_float_optional", default)]
+    pub take_profit: Option<f64>,
+    #[serde(rename = "stopLoss", with = "string_to_float_optional", default)]
+    pub stop_loss: Option<f64>,
     #[serde(rename = "tpTriggerBy")]
     pub tp_trigger_by: String,
     #[serde(rename = "slTriggerBy")]
@@ -1724,8 +2441,12 @@ pub struct Orders {
     pub trigger_direction: i32,
     #[serde(rename = "triggerBy")]
     pub trigger_by: String,
-    #[serde(rename = "lastPriceOnCreated", with = "string_to_float")]
-    pub last_price_on_created: f64,
+    #[serde(
+        rename = "lastPriceOnCreated",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub last_price_on_created: Option<f64>,
     #[serde(rename = "reduceOnly")]
     pub reduce_only: bool,
     #[serde(rename = "closeOnTrigger")]
@@ -1750,6 +2471,31 @@ pub struct Orders {
     pub updated_time: u64,
 }
 
+impl Orders {
+    /// Parses `order_status`, or returns `None` for a status this crate does not know.
+    pub fn state(&self) -> Option<OrderState> {
+        from_value(Value::String(self.order_status.clone())).ok()
+    }
+
+    /// Whether Bybit reports the whole `qty` as executed.
+    pub fn is_fully_filled(&self) -> bool {
+        self.state() == Some(OrderState::Filled)
+    }
+
+    /// Executed share of `qty`, from `0.0` to `1.0`. Meaningless for spot market orders whose
+    /// `qty` is given in the quote coin.
+    pub fn fill_ratio(&self) -> f64 {
+        fill_ratio(self.cum_exec_qty, self.qty)
+    }
+}
+
+fn fill_ratio(cum_exec_qty: f64, qty: f64) -> f64 {
+    match qty > 0.0 {
+        true => (cum_exec_qty / qty).min(1.0),
+        false => 0.0,
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct CancelallRequest<'a> {
     pub category: Category,
@@ -1809,6 +2555,113 @@ pub struct CancelledList {
     pub list: Vec<OrderStatus>,
 }
 
+/// Product group covered by disconnect cancel all (DCP).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DcpProduct {
+    #[default]
+    Options,
+    Derivatives,
+    Spot,
+}
+
+impl DcpProduct {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DcpProduct::Options => "OPTIONS",
+            DcpProduct::Derivatives => "DERIVATIVES",
+            DcpProduct::Spot => "SPOT",
+        }
+    }
+
+    /// The private websocket topic that keeps DCP armed for this product.
+    pub fn topic(&self) -> &str {
+        match self {
+            DcpProduct::Options => "dcp.option",
+            DcpProduct::Derivatives => "dcp.future",
+            DcpProduct::Spot => "dcp.spot",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DcpResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: Empty,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+/// Progress of a leveraged token purchase or redemption (`ltOrderStatus`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LtOrderStatus {
+    #[serde(rename = "1")]
+    Completed,
+    #[serde(rename = "2")]
+    InProgress,
+    #[serde(rename = "3")]
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenPurchaseResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: LeverageTokenPurchase,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+/// A leveraged token purchase. `amount` is paid in `value_coin`, `exec_qty` is the number of
+/// tokens received so far and `exec_amt` what they cost.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenPurchase {
+    pub lt_coin: String,
+    pub lt_order_status: LtOrderStatus,
+    #[serde(with = "string_to_float_optional", default)]
+    pub exec_qty: Option<f64>,
+    #[serde(with = "string_to_float_optional", default)]
+    pub exec_amt: Option<f64>,
+    #[serde(with = "string_to_float")]
+    pub amount: f64,
+    pub purchase_id: String,
+    #[serde(default)]
+    pub serial_no: String,
+    pub value_coin: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenRedemptionResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: LeverageTokenRedemption,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+/// A leveraged token redemption. `quantity` tokens are redeemed, `exec_qty` of them so far, for
+/// `exec_amt` paid out in `value_coin`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageTokenRedemption {
+    pub lt_coin: String,
+    pub lt_order_status: LtOrderStatus,
+    #[serde(with = "string_to_float")]
+    pub quantity: f64,
+    #[serde(with = "string_to_float_optional", default)]
+    pub exec_qty: Option<f64>,
+    #[serde(with = "string_to_float_optional", default)]
+    pub exec_amt: Option<f64>,
+    pub redeem_id: String,
+    #[serde(default)]
+    pub serial_no: String,
+    pub value_coin: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeHistoryResponse {
@@ -1822,11 +2675,25 @@ pub struct TradeHistoryResponse {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeHistorySummary {
-    #[serde(rename = "nextPageCursor", skip_serializing_if = "String::is_empty")]
+    #[serde(
+        rename = "nextPageCursor",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
     pub next_page_cursor: String,
     pub category: String,
     pub list: Vec<TradeHistory>,
 }
+
+impl TradeHistorySummary {
+    /// Splits the page into its executions and the cursor of the next page, if any.
+    pub fn into_page(self) -> (Vec<TradeHistory>, Option<String>) {
+        let cursor = Some(self.next_page_cursor).filter(|cursor| !cursor.is_empty());
+        (self.list, cursor)
+    }
+}
+
+/// One fill from `/v5/execution/list`.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeHistory {
@@ -1858,8 +2725,8 @@ pub struct TradeHistory {
     pub stop_order_type: String,
     #[serde(rename = "leavesQty")]
     pub leaves_qty: String,
-    #[serde(rename = "execTime")]
-    pub exec_time: String,
+    #[serde(rename = "execTime", with = "string_to_u64")]
+    pub exec_time: u64,
     #[serde(
         rename = "feeCurrency",
         default,
@@ -1868,10 +2735,11 @@ pub struct TradeHistory {
     pub fee_currency: String,
     #[serde(rename = "isMaker")]
     pub is_maker: bool,
-    #[serde(rename = "execFee")]
-    pub exec_fee: String,
-    #[serde(rename = "feeRate")]
-    pub fee_rate: String,
+    /// Fee paid in `fee_currency`; negative for maker rebates.
+    #[serde(rename = "execFee", with = "string_to_float")]
+    pub exec_fee: f64,
+    #[serde(rename = "feeRate", with = "string_to_float")]
+    pub fee_rate: f64,
     #[serde(rename = "execId")]
     pub exec_id: String,
     #[serde(rename = "tradeIv", default, skip_serializing_if = "String::is_empty")]
@@ -1884,8 +2752,8 @@ pub struct TradeHistory {
     pub block_trade_id: String,
     #[serde(rename = "markPrice")]
     pub mark_price: String,
-    #[serde(rename = "execPrice")]
-    pub exec_price: String,
+    #[serde(rename = "execPrice", with = "string_to_float")]
+    pub exec_price: f64,
     #[serde(rename = "markIv", default, skip_serializing_if = "String::is_empty")]
     pub mark_iv: String,
     #[serde(rename = "orderQty")]
@@ -1896,8 +2764,8 @@ pub struct TradeHistory {
     pub exec_value: String,
     #[serde(rename = "execType")]
     pub exec_type: String,
-    #[serde(rename = "execQty")]
-    pub exec_qty: String,
+    #[serde(rename = "execQty", with = "string_to_float")]
+    pub exec_qty: f64,
     #[serde(
         rename = "closedSize",
         default,
@@ -1918,6 +2786,7 @@ pub struct TradeHistoryRequest<'a> {
     pub end_time: Option<Cow<'a, str>>,
     pub exec_type: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> TradeHistoryRequest<'a> {
@@ -1955,6 +2824,7 @@ impl<'a> TradeHistoryRequest<'a> {
             end_time: end_time.map(|s| Cow::Borrowed(s)),
             exec_type: exec_type.map(|s| Cow::Borrowed(s)),
             limit,
+            cursor: None,
         }
     }
 }
@@ -2010,7 +2880,7 @@ pub struct OrderConfirmationList {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderConfirmation {
-    pub code: i16,
+    pub code: i32,
     pub msg: String,
 }
 
@@ -2071,6 +2941,53 @@ impl<'a> BatchCancelRequest<'a> {
     }
 }
 
+/// One order of a `Trader::cancel_batch` call, identified by `orderId` or `orderLinkId`.
+#[derive(Clone, Debug)]
+pub struct CancelRequest<'a> {
+    pub symbol: Cow<'a, str>,
+    pub order_id: Option<Cow<'a, str>>,
+    pub order_link_id: Option<Cow<'a, str>>,
+}
+
+impl<'a> CancelRequest<'a> {
+    pub fn by_order_id(symbol: &'a str, order_id: &'a str) -> Self {
+        Self {
+            symbol: Cow::Borrowed(symbol),
+            order_id: Some(Cow::Borrowed(order_id)),
+            order_link_id: None,
+        }
+    }
+
+    pub fn by_order_link_id(symbol: &'a str, order_link_id: &'a str) -> Self {
+        Self {
+            symbol: Cow::Borrowed(symbol),
+            order_id: None,
+            order_link_id: Some(Cow::Borrowed(order_link_id)),
+        }
+    }
+
+    fn into_cancel_order(self, category: Category) -> CancelOrderRequest<'a> {
+        CancelOrderRequest {
+            category,
+            symbol: self.symbol,
+            order_id: self.order_id,
+            order_link_id: self.order_link_id,
+            order_filter: None,
+        }
+    }
+}
+
+impl<'a> BatchCancelRequest<'a> {
+    /// Builds a batch from `cancels`, all in `category`.
+    pub fn from_cancels(category: Category, cancels: Vec<CancelRequest<'a>>) -> Self {
+        let requests = cancels
+            .into_iter()
+            .map(|cancel| cancel.into_cancel_order(category))
+            .collect();
+        BatchCancelRequest { category, requests }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchCancelResponse {
@@ -2101,6 +3018,85 @@ pub struct CanceledOrder {
     pub order_link_id: String,
 }
 
+/// A single order of a batch request, paired with its own status from `retExtInfo.list`.
+/// A non-zero `code` means Bybit rejected that order while the rest of the batch went through.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchOrderResult {
+    pub category: String,
+    pub symbol: String,
+    pub order_id: String,
+    pub order_link_id: String,
+    pub code: i32,
+    pub msg: String,
+}
+
+impl BatchOrderResult {
+    pub fn is_success(&self) -> bool {
+        self.code == 0
+    }
+}
+
+/// Zips a batch's `result.list` with `retExtInfo.list`, which Bybit returns in request order.
+/// `ids` picks an order's category, symbol, order id and order link id.
+fn zip_batch_results<T>(
+    list: &[T],
+    ext: &[OrderConfirmation],
+    ids: fn(&T) -> [&String; 4],
+) -> Vec<BatchOrderResult> {
+    list.iter()
+        .zip(ext)
+        .map(|(order, status)| {
+            let [category, symbol, order_id, order_link_id] = ids(order);
+            BatchOrderResult {
+                category: category.clone(),
+                symbol: symbol.clone(),
+                order_id: order_id.clone(),
+                order_link_id: order_link_id.clone(),
+                code: status.code,
+                msg: status.msg.clone(),
+            }
+        })
+        .collect()
+}
+
+impl BatchPlaceResponse {
+    pub fn results(&self) -> Vec<BatchOrderResult> {
+        zip_batch_results(&self.result.list, &self.ret_ext_info.list, |order| {
+            [
+                &order.category,
+                &order.symbol,
+                &order.order_id,
+                &order.order_link_id,
+            ]
+        })
+    }
+}
+
+impl BatchAmendResponse {
+    pub fn results(&self) -> Vec<BatchOrderResult> {
+        zip_batch_results(&self.result.list, &self.ret_ext_info.list, |order| {
+            [
+                &order.category,
+                &order.symbol,
+                &order.order_id,
+                &order.order_link_id,
+            ]
+        })
+    }
+}
+
+impl BatchCancelResponse {
+    pub fn results(&self) -> Vec<BatchOrderResult> {
+        zip_batch_results(&self.result.list, &self.ret_ext_info.list, |order| {
+            [
+                &order.category,
+                &order.symbol,
+                &order.order_id,
+                &order.order_link_id,
+            ]
+        })
+    }
+}
 
 #[derive(Clone)]
 pub enum RequestType<'a> {
@@ -2120,6 +3116,7 @@ pub struct PositionRequest<'a> {
     pub base_coin: Option<Cow<'a, str>>,
     pub settle_coin: Option<Cow<'a, str>>,
     pub limit: Option<usize>,
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> PositionRequest<'a> {
@@ -2139,6 +3136,7 @@ impl<'a> PositionRequest<'a> {
             base_coin: base_coin.map(Cow::Borrowed),
             settle_coin: settle_coin.map(Cow::Borrowed),
             limit,
+            cursor: None,
         }
     }
 }
@@ -2164,7 +3162,7 @@ pub struct InfoResult {
 #[serde(rename_all = "camelCase")]
 pub struct PositionInfo {
     #[serde(rename = "positionIdx")]
-    pub position_idx: i32,
+    pub position_idx: PositionIdx,
     pub risk_id: i32,
     #[serde(rename = "riskLimitValue", with = "string_to_float")]
     pub risk_limit_value: f64,
@@ -2224,15 +3222,26 @@ pub struct PositionInfo {
 pub struct LeverageRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
-    pub leverage: i8,
+    pub buy_leverage: f64,
+    pub sell_leverage: f64,
 }
 
 impl<'a> LeverageRequest<'a> {
     pub fn new(category: Category, symbol: &'a str, leverage: i8) -> Self {
+        Self::with_leverage(category, symbol, leverage.into(), leverage.into())
+    }
+    /// Separate buy and sell leverage, as used by isolated margin in hedge mode.
+    pub fn with_leverage(
+        category: Category,
+        symbol: &'a str,
+        buy_leverage: f64,
+        sell_leverage: f64,
+    ) -> Self {
         Self {
             category,
             symbol: Cow::Borrowed(symbol),
-            leverage,
+            buy_leverage,
+            sell_leverage,
         }
     }
     pub fn default() -> LeverageRequest<'a> {
@@ -2253,29 +3262,60 @@ pub struct LeverageResponse {
     pub time: u64,
 }
 
+/// Per-symbol margin mode used by `set_margin_mode` (`tradeMode`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarginMode {
+    #[default]
+    Cross,
+    Isolated,
+}
+
+impl MarginMode {
+    pub fn as_i8(&self) -> i8 {
+        match self {
+            MarginMode::Cross => 0,
+            MarginMode::Isolated => 1,
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct ChangeMarginRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
-    pub trade_mode: i8,
-    pub leverage: i8,
+    pub trade_mode: MarginMode,
+    pub buy_leverage: f64,
+    pub sell_leverage: f64,
 }
 
 impl<'a> ChangeMarginRequest<'a> {
-    pub fn new(category: Category, symbol: &'a str, trade_mode: i8, leverage: i8) -> Self {
+    pub fn new(category: Category, symbol: &'a str, trade_mode: MarginMode, leverage: i8) -> Self {
+        Self::with_leverage(
+            category,
+            symbol,
+            trade_mode,
+            leverage.into(),
+            leverage.into(),
+        )
+    }
+    /// Separate buy and sell leverage, as used by isolated margin in hedge mode.
+    pub fn with_leverage(
+        category: Category,
+        symbol: &'a str,
+        trade_mode: MarginMode,
+        buy_leverage: f64,
+        sell_leverage: f64,
+    ) -> Self {
         Self {
             category,
             symbol: Cow::Borrowed(symbol),
-            trade_mode: match trade_mode {
-                1 => 1,
-                0 => 0,
-                _ => 0,
-            },
-            leverage,
+            trade_mode,
+            buy_leverage,
+            sell_leverage,
         }
     }
     pub fn default() -> ChangeMarginRequest<'a> {
-        ChangeMarginRequest::new(Category::Linear, "BTCUSDT", 0, 10)
+        ChangeMarginRequest::new(Category::Linear, "BTCUSDT", MarginMode::Cross, 10)
     }
 }
 
@@ -2292,34 +3332,106 @@ pub struct ChangeMarginResponse {
     pub time: u64,
 }
 
-#[derive(Clone, Default)]
-pub struct MarginModeRequest<'a> {
-    pub category: Category,
-    pub mode: i8,
-    pub symbol: Option<Cow<'a, str>>,
-    pub coin: Option<Cow<'a, str>>,
+/// Position mode used by `set_position_mode` (`mode`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionMode {
+    /// One position per symbol (`positionIdx` 0).
+    #[default]
+    OneWay,
+    /// Separate long and short positions (`positionIdx` 1 and 2).
+    Hedge,
 }
 
-impl<'a> MarginModeRequest<'a> {
-    pub fn new(
-        category: Category,
-        mode: i8,
-        symbol: Option<&'a str>,
-        coin: Option<&'a str>,
-    ) -> Self {
-        Self {
-            category,
-            mode,
-            symbol: symbol.map(|s| Cow::Borrowed(s)),
-            coin: coin.map(|s| Cow::Borrowed(s)),
+impl PositionMode {
+    pub fn as_i8(&self) -> i8 {
+        match self {
+            PositionMode::OneWay => 0,
+            PositionMode::Hedge => 3,
         }
     }
-    pub fn default() -> MarginModeRequest<'a> {
-        MarginModeRequest::new(Category::Linear, 1, None, None)
-    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// Position a request applies to (`positionIdx`). One-way mode only has `OneWay`, hedge mode
+/// holds a long `HedgeBuy` and a short `HedgeSell` position per symbol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionIdx {
+    #[default]
+    OneWay,
+    HedgeBuy,
+    HedgeSell,
+}
+
+impl PositionIdx {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            PositionIdx::OneWay => 0,
+            PositionIdx::HedgeBuy => 1,
+            PositionIdx::HedgeSell => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for PositionIdx {
+    type Error = BybitError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PositionIdx::OneWay),
+            1 => Ok(PositionIdx::HedgeBuy),
+            2 => Ok(PositionIdx::HedgeSell),
+            _ => Err(BybitError::InvalidParams(format!(
+                "invalid position idx {value}"
+            ))),
+        }
+    }
+}
+
+impl From<PositionIdx> for Value {
+    fn from(idx: PositionIdx) -> Self {
+        idx.as_u8().into()
+    }
+}
+
+impl Serialize for PositionIdx {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for PositionIdx {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PositionIdx::try_from(u8::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MarginModeRequest<'a> {
+    pub category: Category,
+    pub mode: PositionMode,
+    pub symbol: Option<Cow<'a, str>>,
+    pub coin: Option<Cow<'a, str>>,
+}
+
+impl<'a> MarginModeRequest<'a> {
+    pub fn new(
+        category: Category,
+        mode: PositionMode,
+        symbol: Option<&'a str>,
+        coin: Option<&'a str>,
+    ) -> Self {
+        Self {
+            category,
+            mode,
+            symbol: symbol.map(|s| Cow::Borrowed(s)),
+            coin: coin.map(|s| Cow::Borrowed(s)),
+        }
+    }
+    pub fn default() -> MarginModeRequest<'a> {
+        MarginModeRequest::new(Category::Linear, PositionMode::OneWay, None, None)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginModeResponse {
     #[serde(rename = "retCode")]
@@ -2337,7 +3449,7 @@ pub struct SetRiskLimit<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
     pub risk_id: i8,
-    pub position_idx: Option<i32>,
+    pub position_idx: Option<PositionIdx>,
 }
 
 impl<'a> SetRiskLimit<'a> {
@@ -2345,7 +3457,7 @@ impl<'a> SetRiskLimit<'a> {
         category: Category,
         symbol: &'a str,
         risk_id: i8,
-        position_idx: Option<i32>,
+        position_idx: Option<PositionIdx>,
     ) -> Self {
         Self {
             category,
@@ -2381,12 +3493,39 @@ pub struct SetRiskLimitResult {
     pub category: String,
 }
 
+/// A take-profit, stop-loss or trailing-stop level for `set_trading_stop`.
+///
+/// Leaving the request field at `None` keeps the current level on Bybit, while `Clear`
+/// sends `"0"` and removes it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopLevel {
+    Price(f64),
+    Clear,
+}
+
+impl StopLevel {
+    pub fn as_param(&self) -> String {
+        match self {
+            StopLevel::Price(price) => price.to_string(),
+            StopLevel::Clear => "0".to_string(),
+        }
+    }
+}
+
+impl From<f64> for StopLevel {
+    fn from(price: f64) -> Self {
+        StopLevel::Price(price)
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct TradingStopRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
-    pub take_profit: Option<f64>,
-    pub stop_loss: Option<f64>,
+    pub take_profit: Option<StopLevel>,
+    pub stop_loss: Option<StopLevel>,
+    /// Trailing distance in price units.
+    pub trailing_stop: Option<StopLevel>,
     pub tp_trigger_by: Option<Cow<'a, str>>,
     pub sl_trigger_by: Option<Cow<'a, str>>,
     pub tpsl_mode: Option<Cow<'a, str>>,
@@ -2396,7 +3535,7 @@ pub struct TradingStopRequest<'a> {
     pub sl_size: Option<f64>,
     pub tp_limit_price: Option<f64>,
     pub sl_limit_price: Option<f64>,
-    pub position_idx: i32,
+    pub position_idx: PositionIdx,
 }
 
 impl<'a> TradingStopRequest<'a> {
@@ -2414,13 +3553,14 @@ impl<'a> TradingStopRequest<'a> {
         sl_size: Option<f64>,
         tp_limit_price: Option<f64>,
         sl_limit_price: Option<f64>,
-        position_idx: i32,
+        position_idx: PositionIdx,
     ) -> Self {
         Self {
             category,
             symbol: Cow::Borrowed(symbol),
-            take_profit,
-            stop_loss,
+            take_profit: take_profit.map(StopLevel::Price),
+            stop_loss: stop_loss.map(StopLevel::Price),
+            trailing_stop: None,
             tp_trigger_by: tp_trigger_by.map(|s| Cow::Borrowed(s)),
             sl_trigger_by: sl_trigger_by.map(|s| Cow::Borrowed(s)),
             tpsl_mode: tpsl_mode.map(|s| Cow::Borrowed(s)),
@@ -2449,7 +3589,7 @@ impl<'a> TradingStopRequest<'a> {
             None,
             None,
             None,
-            1,
+            PositionIdx::HedgeBuy,
         )
     }
 }
@@ -2472,7 +3612,7 @@ pub struct AddMarginRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
     pub auto_add: bool,
-    pub position_idx: Option<i32>,
+    pub position_idx: Option<PositionIdx>,
 }
 
 impl<'a> AddMarginRequest<'a> {
@@ -2480,7 +3620,7 @@ impl<'a> AddMarginRequest<'a> {
         category: Category,
         symbol: &'a str,
         auto_add: bool,
-        position_idx: Option<i32>,
+        position_idx: Option<PositionIdx>,
     ) -> Self {
         Self {
             category,
@@ -2512,7 +3652,7 @@ pub struct AddReduceMarginRequest<'a> {
     pub category: Category,
     pub symbol: Cow<'a, str>,
     pub margin: f64,
-    pub position_idx: Option<i32>,
+    pub position_idx: Option<PositionIdx>,
 }
 
 impl<'a> AddReduceMarginRequest<'a> {
@@ -2520,7 +3660,7 @@ impl<'a> AddReduceMarginRequest<'a> {
         category: Category,
         symbol: &'a str,
         margin: f64,
-        position_idx: Option<i32>,
+        position_idx: Option<PositionIdx>,
     ) -> Self {
         Self {
             category,
@@ -2600,6 +3740,7 @@ pub struct ClosedPnlRequest<'a> {
     pub start_time: Option<Cow<'a, str>>,
     pub end_time: Option<Cow<'a, str>>,
     pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
 }
 
 impl<'a> ClosedPnlRequest<'a> {
@@ -2616,6 +3757,7 @@ impl<'a> ClosedPnlRequest<'a> {
             start_time: start_time.map(|s| Cow::Borrowed(s)),
             end_time: end_time.map(|s| Cow::Borrowed(s)),
             limit,
+            cursor: None,
         }
     }
     pub fn default() -> ClosedPnlRequest<'a> {
@@ -2642,23 +3784,34 @@ pub struct ClosedPnlResult {
     pub list: Vec<ClosedPnlItem>,
 }
 
+impl ClosedPnlResult {
+    /// Splits the page into its items and the cursor of the next page, if any.
+    pub fn into_page(self) -> (Vec<ClosedPnlItem>, Option<String>) {
+        let cursor = self.next_page_cursor.filter(|cursor| !cursor.is_empty());
+        (self.list, cursor)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ClosedPnlItem {
     pub symbol: String,
     pub order_type: String,
     pub leverage: String,
-    pub updated_time: String,
-    pub side: String,
+    #[serde(with = "string_to_u64")]
+    pub updated_time: u64,
+    pub side: Side,
     pub order_id: String,
     #[serde(with = "string_to_float")]
     pub closed_pnl: f64,
     #[serde(rename = "avgEntryPrice", with = "string_to_float")]
     pub avg_entry_price: f64,
-    pub qty: String,
+    #[serde(with = "string_to_float")]
+    pub qty: f64,
     #[serde(with = "string_to_float")]
     pub cum_entry_value: f64,
-    pub created_time: String,
+    #[serde(with = "string_to_u64")]
+    pub created_time: u64,
     #[serde(with = "string_to_float")]
     pub order_price: f64,
     pub closed_size: String,
@@ -2821,6 +3974,27 @@ pub struct MoveHistoryEntry {
 //
 // = = = = = = = = = = = = = = = = = = ==  = = = = ==  = = == = =  = = = =
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccountType {
+    #[default]
+    Unified,
+    Contract,
+    Spot,
+    /// The funding account; only valid for asset transfers.
+    Fund,
+}
+
+impl AccountType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccountType::Unified => "UNIFIED",
+            AccountType::Contract => "CONTRACT",
+            AccountType::Spot => "SPOT",
+            AccountType::Fund => "FUND",
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct WalletResponse {
@@ -3052,9 +4226,14 @@ pub struct FeeRateList {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FeeRate {
+    /// Empty for options, whose rates are reported per `base_coin`.
     pub symbol: String,
-    pub maker_fee_rate: String,
-    pub taker_fee_rate: String,
+    #[serde(default)]
+    pub base_coin: String,
+    #[serde(with = "string_to_float")]
+    pub maker_fee_rate: f64,
+    #[serde(with = "string_to_float")]
+    pub taker_fee_rate: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -3070,14 +4249,89 @@ pub struct AccountInfoResponse {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountInfo {
-    pub margin_mode: String,
-    pub updated_time: String,
-    pub unified_margin_status: i8,
-    pub dcp_status: String,
+    pub margin_mode: AccountMarginMode,
+    #[serde(with = "string_to_u64")]
+    pub updated_time: u64,
+    pub unified_margin_status: UnifiedMarginStatus,
+    pub dcp_status: Toggle,
     pub time_window: i32,
     pub smp_group: i8,
     pub is_master_trader: bool,
-    pub spot_hedging_status: String,
+    pub spot_hedging_status: Toggle,
+}
+
+/// Account wide margin mode reported by `get_account_info`. `as_str` gives the value
+/// `set_margin_mode` expects.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountMarginMode {
+    IsolatedMargin,
+    RegularMargin,
+    PortfolioMargin,
+}
+
+impl AccountMarginMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccountMarginMode::IsolatedMargin => "ISOLATED_MARGIN",
+            AccountMarginMode::RegularMargin => "REGULAR_MARGIN",
+            AccountMarginMode::PortfolioMargin => "PORTFOLIO_MARGIN",
+        }
+    }
+}
+
+/// Which account generation the API key belongs to (`unifiedMarginStatus`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(from = "i8", into = "i8")]
+pub enum UnifiedMarginStatus {
+    Classic,
+    Uta1,
+    Uta1Pro,
+    Uta2,
+    Uta2Pro,
+    /// A status this crate does not know yet.
+    Other(i8),
+}
+
+impl UnifiedMarginStatus {
+    /// Whether the account is a unified trading account of any generation.
+    pub fn is_unified(&self) -> bool {
+        !matches!(self, UnifiedMarginStatus::Classic)
+    }
+}
+
+impl From<i8> for UnifiedMarginStatus {
+    fn from(status: i8) -> Self {
+        match status {
+            1 => UnifiedMarginStatus::Classic,
+            3 => UnifiedMarginStatus::Uta1,
+            4 => UnifiedMarginStatus::Uta1Pro,
+            5 => UnifiedMarginStatus::Uta2,
+            6 => UnifiedMarginStatus::Uta2Pro,
+            other => UnifiedMarginStatus::Other(other),
+        }
+    }
+}
+
+impl From<UnifiedMarginStatus> for i8 {
+    fn from(status: UnifiedMarginStatus) -> Self {
+        match status {
+            UnifiedMarginStatus::Classic => 1,
+            UnifiedMarginStatus::Uta1 => 3,
+            UnifiedMarginStatus::Uta1Pro => 4,
+            UnifiedMarginStatus::Uta2 => 5,
+            UnifiedMarginStatus::Uta2Pro => 6,
+            UnifiedMarginStatus::Other(other) => other,
+        }
+    }
+}
+
+/// An `"ON"`/`"OFF"` account setting.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Toggle {
+    On,
+    Off,
 }
 
 #[derive(Clone, Default)]
@@ -3208,143 +4462,899 @@ pub struct SpotHedgingResponse {
     pub ret_msg: String,
 }
 
-// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
-// HEADER STRUCT FOR TRADESTREM RESPONSE
-// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Header {
-    #[serde(rename = "X-Bapi-Limit")]
-    pub x_bapi_limit: String,
-    #[serde(rename = "X-Bapi-Limit-Status")]
-    pub x_bapi_limit_status: String,
-    #[serde(rename = "X-Bapi-Limit-Reset-Timestamp")]
-    pub x_bapi_limit_reset_timestamp: String,
-    #[serde(rename = "Traceid")]
-    pub traceid: String,
-    #[serde(rename = "Timenow")]
-    pub timenow: String,
-}
-
-// = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = =
-//
-// WEBSOCKET STRUCTS AND RESPONSES
-//
-// = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = =
-
-#[derive(Clone, Debug, Default)]
-pub struct Subscription<'a> {
-    pub op: &'a str,
-    pub args: Vec<&'a str>,
-}
-
-impl<'a> Subscription<'a> {
-    pub fn new(op: &'a str, args: Vec<&'a str>) -> Self {
-        Self { op, args }
-    }
-    pub fn default() -> Subscription<'a> {
-        Subscription::new("subscribe", vec![])
-    }
+#[serde(rename_all = "camelCase")]
+pub struct BorrowQuotaResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: BorrowQuota,
+    pub ret_ext_info: Empty,
+    pub time: u64,
 }
 
+/// How much of a spot symbol can be traded, borrowing included, on one side.
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(untagged)]
-pub enum WebsocketEvents {
-    OrderBookEvent(OrderBookUpdate),
-    TradeEvent(TradeUpdate),
-    TickerEvent(WsTicker),
-    LiquidationEvent(Liquidation),
-    KlineEvent(WsKline),
-    PositionEvent(PositionEvent),
-    ExecutionEvent(Execution),
-    OrderEvent(OrderEvent),
-    Wallet(WalletEvent),
-    TradeStream(TradeStreamEvent),
-    FastExecEvent(FastExecution)
+#[serde(rename_all = "camelCase")]
+pub struct BorrowQuota {
+    pub symbol: String,
+    pub side: Side,
+    /// The coin that would be borrowed: the quote coin for buys, the base coin for sells.
+    pub borrow_coin: String,
+    /// Most base coin quantity tradable with spot margin.
+    #[serde(with = "string_to_float")]
+    pub max_trade_qty: f64,
+    /// Most quote coin amount tradable with spot margin.
+    #[serde(with = "string_to_float")]
+    pub max_trade_amount: f64,
+    /// Most base coin quantity tradable without borrowing.
+    #[serde(with = "string_to_float")]
+    pub spot_max_trade_qty: f64,
+    /// Most quote coin amount tradable without borrowing.
+    #[serde(with = "string_to_float")]
+    pub spot_max_trade_amount: f64,
+    /// Only reported by some account types.
+    #[serde(default, with = "string_to_float_optional")]
+    pub available_balance: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(untagged)]
-pub enum Tickers {
-    Linear(LinearTickerData),
-    Spot(SpotTickerData),
+#[serde(rename_all = "camelCase")]
+pub struct SpotMarginModeResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: SpotMarginMode,
+    pub ret_ext_info: Empty,
+    pub time: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(untagged)]
-pub enum PongResponse {
-    PublicPong(PongData),
-    PrivatePong(PongData),
+#[serde(rename_all = "camelCase")]
+pub struct SpotMarginMode {
+    /// `"1"` when spot margin trading is on, `"0"` when it is off.
+    pub spot_margin_mode: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PongData {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ret_code: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub success: Option<bool>,
+#[serde(rename_all = "camelCase")]
+pub struct SpotLeverageResponse {
+    pub ret_code: i32,
     pub ret_msg: String,
-    pub conn_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub req_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub args: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub data: Option<Vec<String>>,
-    pub op: String,
+    pub ret_ext_info: Empty,
+    pub time: u64,
 }
 
-unsafe impl Send for PongData {}
-unsafe impl Sync for PongData {}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct TradeStreamEvent {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub req_id: Option<String>,
+pub struct SpotMarginStateResponse {
     pub ret_code: i32,
     pub ret_msg: String,
-    pub op: String,
-    pub data: OrderStatus,
-    pub header: Header,
-    pub conn_id: String,
+    pub result: SpotMarginState,
+    pub ret_ext_info: Empty,
+    pub time: u64,
 }
 
-unsafe impl Send for TradeStreamEvent {}
-unsafe impl Sync for TradeStreamEvent {}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct OrderBookUpdate {
-    #[serde(rename = "topic")]
-    pub topic: String,
-    #[serde(rename = "type")]
-    pub event_type: String,
-    #[serde(rename = "ts")]
-    pub timestamp: u64,
-    pub data: WsOrderBook,
-    pub cts: u64,
+pub struct SpotMarginState {
+    /// Empty until a spot leverage has been set.
+    pub spot_leverage: String,
+    pub spot_margin_mode: String,
+    pub effective_leverage: String,
 }
 
-unsafe impl Send for OrderBookUpdate {}
-unsafe impl Sync for OrderBookUpdate {}
+// = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = =
+//
+//  ASSET STRUCTS AND RESPONSES
+//
+// = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = =
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct WsOrderBook {
-    #[serde(rename = "s")]
-    pub symbol: String,
-    #[serde(rename = "a")]
-    pub asks: Vec<Ask>,
-    #[serde(rename = "b")]
-    pub bids: Vec<Bid>,
-    #[serde(rename = "u")]
-    pub update_id: u64,
-    pub seq: u64,
+#[derive(Clone, Default)]
+pub struct InternalTransferRequest<'a> {
+    /// UUID identifying the transfer; generated when left empty.
+    pub transfer_id: Option<Cow<'a, str>>,
+    pub coin: Cow<'a, str>,
+    pub amount: f64,
+    pub from_account_type: AccountType,
+    pub to_account_type: AccountType,
 }
 
-unsafe impl Send for WsOrderBook {}
-unsafe impl Sync for WsOrderBook {}
+impl<'a> InternalTransferRequest<'a> {
+    pub fn new(coin: &'a str, amount: f64, from: AccountType, to: AccountType) -> Self {
+        Self {
+            transfer_id: None,
+            coin: Cow::Borrowed(coin),
+            amount,
+            from_account_type: from,
+            to_account_type: to,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalTransferResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: InternalTransferResult,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalTransferResult {
+    pub transfer_id: String,
+    /// `SUCCESS`, `PENDING` or `FAILED`.
+    #[serde(default)]
+    pub status: String,
+}
+
+#[derive(Clone, Default)]
+pub struct TransferHistoryRequest<'a> {
+    pub transfer_id: Option<Cow<'a, str>>,
+    pub coin: Option<Cow<'a, str>>,
+    pub status: Option<Cow<'a, str>>,
+    pub start_time: Option<Cow<'a, str>>,
+    pub end_time: Option<Cow<'a, str>>,
+    pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
+}
+
+impl<'a> TransferHistoryRequest<'a> {
+    pub fn new(
+        coin: Option<&'a str>,
+        start_time: Option<&'a str>,
+        end_time: Option<&'a str>,
+        limit: Option<u64>,
+    ) -> Self {
+        Self {
+            coin: coin.map(Cow::Borrowed),
+            start_time: start_time.map(Cow::Borrowed),
+            end_time: end_time.map(Cow::Borrowed),
+            limit,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferHistoryResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: TransferHistoryList,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferHistoryList {
+    pub list: Vec<TransferRecord>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub next_page_cursor: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferRecord {
+    pub transfer_id: String,
+    pub coin: String,
+    #[serde(with = "string_to_float")]
+    pub amount: f64,
+    pub from_account_type: String,
+    pub to_account_type: String,
+    #[serde(with = "string_to_u64")]
+    pub timestamp: u64,
+    pub status: String,
+}
+
+/// Query for `/v5/asset/delivery-record`. `exp_date` filters by expiry, e.g. `25MAR22`.
+#[derive(Clone)]
+pub struct DeliveryRecordRequest<'a> {
+    pub category: Category,
+    pub symbol: Option<Cow<'a, str>>,
+    pub exp_date: Option<Cow<'a, str>>,
+    pub start_time: Option<Cow<'a, str>>,
+    pub end_time: Option<Cow<'a, str>>,
+    pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
+}
+
+impl<'a> DeliveryRecordRequest<'a> {
+    pub fn new(
+        category: Category,
+        symbol: Option<&'a str>,
+        exp_date: Option<&'a str>,
+        limit: Option<u64>,
+        cursor: Option<&'a str>,
+    ) -> Self {
+        Self {
+            category,
+            symbol: symbol.map(Cow::Borrowed),
+            exp_date: exp_date.map(Cow::Borrowed),
+            start_time: None,
+            end_time: None,
+            limit,
+            cursor: cursor.map(Cow::Borrowed),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryRecordResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: DeliveryRecordSummary,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryRecordSummary {
+    pub category: String,
+    pub list: Vec<DeliveryRecord>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub next_page_cursor: String,
+}
+
+impl DeliveryRecordSummary {
+    /// Splits the page into its records and the cursor of the next page, if any.
+    pub fn into_page(self) -> (Vec<DeliveryRecord>, Option<String>) {
+        let cursor = Some(self.next_page_cursor).filter(|cursor| !cursor.is_empty());
+        (self.list, cursor)
+    }
+}
+
+/// The delivery of an expired option or dated futures position.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryRecord {
+    pub delivery_time: u64,
+    pub symbol: String,
+    pub side: Side,
+    #[serde(with = "string_to_float")]
+    pub position: f64,
+    #[serde(with = "string_to_float_optional", default)]
+    pub entry_price: Option<f64>,
+    #[serde(with = "string_to_float")]
+    pub delivery_price: f64,
+    /// Strike price of an option, `None` for futures.
+    #[serde(with = "string_to_float_optional", default)]
+    pub strike: Option<f64>,
+    #[serde(with = "string_to_float")]
+    pub fee: f64,
+    /// Realised PnL of the delivery.
+    #[serde(with = "string_to_float")]
+    pub delivery_rpl: f64,
+}
+
+/// Query for `/v5/asset/settlement-record`, which covers USDC perpetuals only.
+#[derive(Clone)]
+pub struct SettlementRecordRequest<'a> {
+    pub category: Category,
+    pub symbol: Option<Cow<'a, str>>,
+    pub start_time: Option<Cow<'a, str>>,
+    pub end_time: Option<Cow<'a, str>>,
+    pub limit: Option<u64>,
+    pub cursor: Option<Cow<'a, str>>,
+}
+
+impl<'a> SettlementRecordRequest<'a> {
+    pub fn new(symbol: Option<&'a str>, limit: Option<u64>, cursor: Option<&'a str>) -> Self {
+        Self {
+            category: Category::Linear,
+            symbol: symbol.map(Cow::Borrowed),
+            start_time: None,
+            end_time: None,
+            limit,
+            cursor: cursor.map(Cow::Borrowed),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementRecordResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: SettlementRecordSummary,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementRecordSummary {
+    pub category: String,
+    pub list: Vec<SettlementRecord>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub next_page_cursor: String,
+}
+
+impl SettlementRecordSummary {
+    /// Splits the page into its records and the cursor of the next page, if any.
+    pub fn into_page(self) -> (Vec<SettlementRecord>, Option<String>) {
+        let cursor = Some(self.next_page_cursor).filter(|cursor| !cursor.is_empty());
+        (self.list, cursor)
+    }
+}
+
+/// The session settlement of a USDC perpetual position.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementRecord {
+    pub symbol: String,
+    pub side: Side,
+    #[serde(with = "string_to_float")]
+    pub size: f64,
+    #[serde(with = "string_to_float")]
+    pub session_avg_price: f64,
+    #[serde(with = "string_to_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_to_float")]
+    pub realised_pnl: f64,
+    #[serde(with = "string_to_u64")]
+    pub created_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddressResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: DepositAddress,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddress {
+    pub coin: String,
+    pub chains: Vec<DepositChain>,
+}
+
+impl DepositAddress {
+    /// The entry for `chain` (e.g. `ETH`, `TRX`), if the coin can be deposited on it.
+    pub fn chain(&self, chain: &str) -> Option<&DepositChain> {
+        self.chains.iter().find(|entry| entry.chain == chain)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositChain {
+    /// Display name of the network, e.g. `ERC20`.
+    pub chain_type: String,
+    pub address_deposit: String,
+    /// Memo or tag that must accompany the deposit; empty when the chain has none.
+    pub tag_deposit: String,
+    /// Chain code to pass back as `chain`.
+    pub chain: String,
+    #[serde(default)]
+    pub batch_release_limit: String,
+    #[serde(default)]
+    pub contract_address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalanceResponse {
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub result: CoinBalanceResult,
+    pub ret_ext_info: Empty,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalanceResult {
+    pub account_type: String,
+    #[serde(default)]
+    pub biz_type: i32,
+    #[serde(default)]
+    pub account_id: String,
+    pub member_id: String,
+    pub balance: CoinBalance,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalance {
+    pub coin: String,
+    #[serde(with = "string_to_float")]
+    pub wallet_balance: f64,
+    /// Amount that can be transferred out right now.
+    #[serde(with = "string_to_float")]
+    pub transfer_balance: f64,
+    #[serde(with = "string_to_float_optional", default)]
+    pub bonus: Option<f64>,
+}
+
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+// HEADER STRUCT FOR TRADESTREM RESPONSE
+// = = = = = = = = = = = = ==  = == = =  =  = = = = ==
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Header {
+    #[serde(rename = "X-Bapi-Limit")]
+    pub x_bapi_limit: String,
+    #[serde(rename = "X-Bapi-Limit-Status")]
+    pub x_bapi_limit_status: String,
+    #[serde(rename = "X-Bapi-Limit-Reset-Timestamp")]
+    pub x_bapi_limit_reset_timestamp: String,
+    #[serde(rename = "Traceid")]
+    pub traceid: String,
+    #[serde(rename = "Timenow")]
+    pub timenow: String,
+}
+
+// = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = =
+//
+// WEBSOCKET STRUCTS AND RESPONSES
+//
+// = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = = =
+
+/// The `op` of a websocket subscription frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubscriptionOp {
+    #[default]
+    Subscribe,
+    Unsubscribe,
+}
+
+impl SubscriptionOp {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SubscriptionOp::Subscribe => "subscribe",
+            SubscriptionOp::Unsubscribe => "unsubscribe",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Subscription<'a> {
+    pub op: SubscriptionOp,
+    pub args: Vec<&'a str>,
+}
+
+impl<'a> Subscription<'a> {
+    pub fn new(op: SubscriptionOp, args: Vec<&'a str>) -> Self {
+        Self { op, args }
+    }
+    pub fn default() -> Subscription<'a> {
+        Subscription::new(SubscriptionOp::Subscribe, vec![])
+    }
+}
+
+/// Collects validated `Topic`s into subscription frames.
+///
+/// Duplicate topics are dropped, and `build` splits the topics into frames of at most
+/// `MAX_ARGS_PER_REQUEST` args, since Bybit ignores larger requests.
+///
+/// ```ignore
+/// let builder = SubscriptionBuilder::subscribe()
+///     .topic(Topic::orderbook(50, "BTCUSDT")?)
+///     .topic(Topic::public_trade("BTCUSDT"));
+/// stream.ws_subscribe(builder.subscription(), Category::Linear, None, None, handler).await?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SubscriptionBuilder {
+    op: SubscriptionOp,
+    topics: Vec<String>,
+}
+
+impl SubscriptionBuilder {
+    pub fn new(op: SubscriptionOp) -> Self {
+        Self {
+            op,
+            topics: Vec::new(),
+        }
+    }
+    pub fn subscribe() -> Self {
+        Self::new(SubscriptionOp::Subscribe)
+    }
+    pub fn unsubscribe() -> Self {
+        Self::new(SubscriptionOp::Unsubscribe)
+    }
+
+    pub fn topic(mut self, topic: Topic) -> Self {
+        let topic = topic.to_string();
+        if !self.topics.contains(&topic) {
+            self.topics.push(topic);
+        }
+        self
+    }
+
+    pub fn topics<I: IntoIterator<Item = Topic>>(self, topics: I) -> Self {
+        topics.into_iter().fold(self, Self::topic)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.topics.is_empty()
+    }
+
+    /// All topics as one subscription. `Stream::ws_subscribe` splits it into frames itself.
+    pub fn subscription(&self) -> Subscription<'_> {
+        Subscription::new(self.op, self.topics.iter().map(String::as_str).collect())
+    }
+
+    /// One subscription per frame, each with at most `MAX_ARGS_PER_REQUEST` topics.
+    pub fn build(&self) -> Vec<Subscription<'_>> {
+        self.topics
+            .chunks(crate::ws::MAX_ARGS_PER_REQUEST)
+            .map(|chunk| Subscription::new(self.op, chunk.iter().map(String::as_str).collect()))
+            .collect()
+    }
+}
+
+/// A websocket topic; all but `Dcp` are public. Constructors validate the parameters Bybit
+/// accepts, and `Display` renders the topic string used in a subscribe request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Topic {
+    OrderBook { depth: u16, symbol: String },
+    PublicTrade { symbol: String },
+    Ticker { symbol: String },
+    Kline { interval: String, symbol: String },
+    LtKline { interval: String, symbol: String },
+    Liquidation { symbol: String },
+    AllLiquidation { symbol: String },
+    Insurance { coin: String },
+    Dcp { product: DcpProduct },
+}
+
+impl Topic {
+    /// Depths offered across categories: 1/50/200 (spot), 1/50/200/500 (linear, inverse)
+    /// and 25/100 (option).
+    pub const ORDERBOOK_DEPTHS: [u16; 6] = [1, 25, 50, 100, 200, 500];
+    pub const KLINE_INTERVALS: [&'static str; 13] = [
+        "1", "3", "5", "15", "30", "60", "120", "240", "360", "720", "D", "W", "M",
+    ];
+
+    pub fn orderbook(depth: u16, symbol: &str) -> Result<Self, BybitError> {
+        if !Self::ORDERBOOK_DEPTHS.contains(&depth) {
+            return Err(BybitError::Base(format!(
+                "Invalid orderbook depth: {}",
+                depth
+            )));
+        }
+        Ok(Topic::OrderBook {
+            depth,
+            symbol: symbol.trim().to_uppercase(),
+        })
+    }
+
+    fn check_kline_interval(interval: &str) -> Result<(), BybitError> {
+        interval.parse::<Interval>().map(|_| ())
+    }
+
+    pub fn kline(interval: &str, symbol: &str) -> Result<Self, BybitError> {
+        Self::check_kline_interval(interval)?;
+        Ok(Topic::Kline {
+            interval: interval.to_string(),
+            symbol: symbol.trim().to_uppercase(),
+        })
+    }
+
+    /// Kline of a leveraged token's net asset value, e.g. `EOS3LUSDT`, on the spot stream.
+    /// Mark and index price klines have no stream; use `MarketData::get_mark_price_klines`
+    /// and `get_index_price_klines` instead.
+    pub fn lt_kline(interval: &str, symbol: &str) -> Result<Self, BybitError> {
+        Self::check_kline_interval(interval)?;
+        Ok(Topic::LtKline {
+            interval: interval.to_string(),
+            symbol: symbol.trim().to_uppercase(),
+        })
+    }
+
+    pub fn public_trade(symbol: &str) -> Self {
+        Topic::PublicTrade {
+            symbol: symbol.trim().to_uppercase(),
+        }
+    }
+
+    pub fn ticker(symbol: &str) -> Self {
+        Topic::Ticker {
+            symbol: symbol.trim().to_uppercase(),
+        }
+    }
+
+    pub fn liquidation(symbol: &str) -> Self {
+        Topic::Liquidation {
+            symbol: symbol.trim().to_uppercase(),
+        }
+    }
+
+    /// Every liquidation, where `liquidation` only sends the largest one per second.
+    pub fn all_liquidation(symbol: &str) -> Self {
+        Topic::AllLiquidation {
+            symbol: symbol.trim().to_uppercase(),
+        }
+    }
+
+    /// Insurance pool updates: `USDT` and `USDC` on the linear stream, `inverse` on the
+    /// inverse stream.
+    pub fn insurance(coin: &str) -> Self {
+        let coin = match coin.trim().to_uppercase().as_str() {
+            "INVERSE" => "inverse".to_string(),
+            coin => coin.to_string(),
+        };
+        Topic::Insurance { coin }
+    }
+
+    /// Private topic that arms disconnect cancel all for `product`, see `Stream::ws_set_dcp`.
+    pub fn dcp(product: DcpProduct) -> Self {
+        Topic::Dcp { product }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topic::OrderBook { depth, symbol } => write!(f, "orderbook.{}.{}", depth, symbol),
+            Topic::PublicTrade { symbol } => write!(f, "publicTrade.{}", symbol),
+            Topic::Ticker { symbol } => write!(f, "tickers.{}", symbol),
+            Topic::Kline { interval, symbol } => write!(f, "kline.{}.{}", interval, symbol),
+            Topic::LtKline { interval, symbol } => write!(f, "kline_lt.{}.{}", interval, symbol),
+            Topic::Liquidation { symbol } => write!(f, "liquidation.{}", symbol),
+            Topic::AllLiquidation { symbol } => write!(f, "allLiquidation.{}", symbol),
+            Topic::Insurance { coin } => write!(f, "insurance.{}", coin),
+            Topic::Dcp { product } => f.write_str(product.topic()),
+        }
+    }
+}
+
+/// A decoded websocket push. Frames are routed to a variant by their `topic` prefix, see
+/// `WebsocketEvents::from_value`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum WebsocketEvents {
+    OrderBookEvent(OrderBookUpdate),
+    TradeEvent(TradeUpdate),
+    TickerEvent(WsTicker),
+    LiquidationEvent(Liquidation),
+    AllLiquidationEvent(AllLiquidation),
+    InsuranceEvent(InsuranceEvent),
+    KlineEvent(WsKline),
+    LtKlineEvent(WsLtKline),
+    PositionEvent(PositionEvent),
+    ExecutionEvent(Execution),
+    OrderEvent(OrderEvent),
+    Wallet(WalletEvent),
+    TradeStream(TradeStreamEvent),
+    FastExecEvent(FastExecution),
+    GreeksEvent(GreeksEvent),
+    /// A frame with an unknown topic, or whose payload does not fit the variant of its
+    /// topic, kept as raw JSON so new or changed payloads are not silently lost.
+    Unknown(Value),
+}
+
+impl WebsocketEvents {
+    /// Decodes a frame into the variant its `topic` names, e.g. `orderbook.50.BTCUSDT` into
+    /// `OrderBookEvent` and `execution.fast.linear` into `FastExecEvent`. Trade stream replies
+    /// carry no topic and are recognised by their `order.*` op.
+    pub fn from_value(value: Value) -> Self {
+        fn decode<T: serde::de::DeserializeOwned>(
+            value: Value,
+            variant: fn(T) -> WebsocketEvents,
+        ) -> WebsocketEvents {
+            match T::deserialize(&value) {
+                Ok(event) => variant(event),
+                Err(err) => {
+                    debug!("Could not decode {} frame: {err}", value["topic"]);
+                    WebsocketEvents::Unknown(value)
+                }
+            }
+        }
+        let topic = value
+            .get("topic")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let mut parts = topic.split('.');
+        match (parts.next().unwrap_or_default(), parts.next()) {
+            ("orderbook", _) => decode(value, WebsocketEvents::OrderBookEvent),
+            ("publicTrade", _) => decode(value, WebsocketEvents::TradeEvent),
+            ("tickers", _) => decode(value, WebsocketEvents::TickerEvent),
+            ("liquidation", _) => decode(value, WebsocketEvents::LiquidationEvent),
+            ("allLiquidation", _) => decode(value, WebsocketEvents::AllLiquidationEvent),
+            ("insurance", _) => decode(value, WebsocketEvents::InsuranceEvent),
+            ("kline", _) => decode(value, WebsocketEvents::KlineEvent),
+            ("kline_lt", _) => decode(value, WebsocketEvents::LtKlineEvent),
+            ("position", _) => decode(value, WebsocketEvents::PositionEvent),
+            ("execution", Some("fast")) => decode(value, WebsocketEvents::FastExecEvent),
+            ("execution", _) => decode(value, WebsocketEvents::ExecutionEvent),
+            ("order", _) => decode(value, WebsocketEvents::OrderEvent),
+            ("wallet", _) => decode(value, WebsocketEvents::Wallet),
+            ("greeks", _) => decode(value, WebsocketEvents::GreeksEvent),
+            ("", _)
+                if value
+                    .get("op")
+                    .and_then(Value::as_str)
+                    .is_some_and(|op| op.starts_with("order.")) =>
+            {
+                decode(value, WebsocketEvents::TradeStream)
+            }
+            _ => WebsocketEvents::Unknown(value),
+        }
+    }
+
+    /// The topic the event was pushed on, e.g. `tickers.BTCUSDT`. Trade stream replies have none.
+    pub fn topic(&self) -> Option<&str> {
+        match self {
+            WebsocketEvents::OrderBookEvent(event) => Some(&event.topic),
+            WebsocketEvents::TradeEvent(event) => Some(&event.topic),
+            WebsocketEvents::TickerEvent(event) => Some(&event.topic),
+            WebsocketEvents::LiquidationEvent(event) => Some(&event.topic),
+            WebsocketEvents::AllLiquidationEvent(event) => Some(&event.topic),
+            WebsocketEvents::InsuranceEvent(event) => Some(&event.topic),
+            WebsocketEvents::KlineEvent(event) => Some(&event.topic),
+            WebsocketEvents::LtKlineEvent(event) => Some(&event.topic),
+            WebsocketEvents::PositionEvent(event) => Some(&event.topic),
+            WebsocketEvents::ExecutionEvent(event) => Some(&event.topic),
+            WebsocketEvents::OrderEvent(event) => Some(&event.topic),
+            WebsocketEvents::Wallet(event) => Some(&event.topic),
+            WebsocketEvents::FastExecEvent(event) => Some(&event.topic),
+            WebsocketEvents::GreeksEvent(event) => Some(&event.topic),
+            WebsocketEvents::TradeStream(_) => None,
+            WebsocketEvents::Unknown(value) => value.get("topic").and_then(Value::as_str),
+        }
+    }
+
+    /// When Bybit generated the event, in milliseconds: `ts` on public topics, `creationTime` on
+    /// private ones and the `Timenow` header on trade stream replies.
+    pub fn timestamp(&self) -> Option<u64> {
+        match self {
+            WebsocketEvents::OrderBookEvent(event) => Some(event.timestamp),
+            WebsocketEvents::TradeEvent(event) => Some(event.timestamp),
+            WebsocketEvents::TickerEvent(event) => Some(event.ts),
+            WebsocketEvents::LiquidationEvent(event) => Some(event.ts),
+            WebsocketEvents::AllLiquidationEvent(event) => Some(event.ts),
+            WebsocketEvents::InsuranceEvent(event) => Some(event.ts),
+            WebsocketEvents::KlineEvent(event) => Some(event.timestamp),
+            WebsocketEvents::LtKlineEvent(event) => Some(event.timestamp),
+            WebsocketEvents::PositionEvent(event) => Some(event.creation_time),
+            WebsocketEvents::ExecutionEvent(event) => Some(event.creation_time),
+            WebsocketEvents::OrderEvent(event) => Some(event.creation_time),
+            WebsocketEvents::Wallet(event) => Some(event.creation_time),
+            WebsocketEvents::FastExecEvent(event) => Some(event.creation_time),
+            WebsocketEvents::GreeksEvent(event) => Some(event.creation_time),
+            WebsocketEvents::TradeStream(event) => event.header.timenow.parse().ok(),
+            WebsocketEvents::Unknown(value) => value
+                .get("ts")
+                .or_else(|| value.get("creationTime"))
+                .and_then(Value::as_u64),
+        }
+    }
+
+    /// `timestamp` as a UTC date time.
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        let millis = i64::try_from(self.timestamp()?).ok()?;
+        Utc.timestamp_millis_opt(millis).single()
+    }
+}
+
+impl<'de> Deserialize<'de> for WebsocketEvents {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(WebsocketEvents::from_value)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Tickers {
+    Linear(LinearTickerData),
+    Spot(SpotTickerData),
+    Option(OptionTickerData),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum PongResponse {
+    PublicPong(PongData),
+    PrivatePong(PongData),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PongData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ret_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    /// Not sent on private stream pongs.
+    #[serde(default)]
+    pub ret_msg: String,
+    pub conn_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub req_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<String>>,
+    pub op: String,
+}
+
+impl PongData {
+    /// The server time in milliseconds that private stream pongs carry in `args`.
+    pub fn server_time(&self) -> Option<u64> {
+        self.args.as_ref()?.first()?.parse().ok()
+    }
+}
+
+unsafe impl Send for PongData {}
+unsafe impl Sync for PongData {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeStreamEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub req_id: Option<String>,
+    pub ret_code: i32,
+    pub ret_msg: String,
+    pub op: String,
+    pub data: OrderStatus,
+    pub header: Header,
+    pub conn_id: String,
+}
+
+unsafe impl Send for TradeStreamEvent {}
+unsafe impl Sync for TradeStreamEvent {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBookUpdate {
+    #[serde(rename = "topic")]
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub kind: OrderBookKind,
+    #[serde(rename = "ts")]
+    pub timestamp: u64,
+    pub data: WsOrderBook,
+    /// Matching engine time of the update, in milliseconds.
+    pub cts: u64,
+}
+
+unsafe impl Send for OrderBookUpdate {}
+unsafe impl Sync for OrderBookUpdate {}
+
+impl OrderBookUpdate {
+    pub fn is_snapshot(&self) -> bool {
+        self.kind == OrderBookKind::Snapshot
+    }
+
+    /// Cross sequence, comparable across the order book depths of one symbol.
+    pub fn seq(&self) -> u64 {
+        self.data.seq
+    }
+}
+
+/// Whether an order book message replaces the book or updates some of its levels.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderBookKind {
+    Snapshot,
+    Delta,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WsOrderBook {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "a")]
+    pub asks: Vec<Ask>,
+    #[serde(rename = "b")]
+    pub bids: Vec<Bid>,
+    #[serde(rename = "u")]
+    pub update_id: u64,
+    pub seq: u64,
+}
+
+unsafe impl Send for WsOrderBook {}
+unsafe impl Sync for WsOrderBook {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TradeUpdate {
@@ -3376,6 +5386,7 @@ pub struct WsTrade {
     pub tick_direction: String,
     #[serde(rename = "i")]
     pub id: String,
+    /// Carries the `BT` flag, which marks block trades.
     #[serde(rename = "BT")]
     pub buyer_is_maker: bool,
 }
@@ -3389,6 +5400,8 @@ pub struct WsTicker {
     #[serde(rename = "type")]
     pub event_type: String,
     pub data: Tickers,
+    /// Cross sequence; not sent on the option ticker topic.
+    #[serde(default)]
     pub cs: u64,
     pub ts: u64,
 }
@@ -3467,6 +5480,98 @@ pub struct SpotTickerData {
 unsafe impl Send for SpotTickerData {}
 unsafe impl Sync for SpotTickerData {}
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OptionTickerData {
+    #[serde(rename = "symbol")]
+    pub symbol: String,
+    #[serde(rename = "bidPrice")]
+    pub bid_price: String,
+    #[serde(rename = "bidSize")]
+    pub bid_size: String,
+    #[serde(rename = "bidIv")]
+    pub bid_iv: String,
+    #[serde(rename = "askPrice")]
+    pub ask_price: String,
+    #[serde(rename = "askSize")]
+    pub ask_size: String,
+    #[serde(rename = "askIv")]
+    pub ask_iv: String,
+    #[serde(rename = "lastPrice")]
+    pub last_price: String,
+    #[serde(rename = "highPrice24h")]
+    pub high_price_24h: String,
+    #[serde(rename = "lowPrice24h")]
+    pub low_price_24h: String,
+    #[serde(rename = "markPrice")]
+    pub mark_price: String,
+    #[serde(rename = "indexPrice")]
+    pub index_price: String,
+    #[serde(rename = "markPriceIv")]
+    pub mark_price_iv: String,
+    #[serde(rename = "underlyingPrice")]
+    pub underlying_price: String,
+    #[serde(rename = "openInterest")]
+    pub open_interest: String,
+    #[serde(rename = "turnover24h")]
+    pub turnover_24h: String,
+    #[serde(rename = "volume24h")]
+    pub volume_24h: String,
+    #[serde(rename = "totalVolume")]
+    pub total_volume: String,
+    #[serde(rename = "totalTurnover")]
+    pub total_turnover: String,
+    #[serde(rename = "delta")]
+    pub delta: String,
+    #[serde(rename = "gamma")]
+    pub gamma: String,
+    #[serde(rename = "vega")]
+    pub vega: String,
+    #[serde(rename = "theta")]
+    pub theta: String,
+    #[serde(rename = "predictedDeliveryPrice")]
+    pub predicted_delivery_price: String,
+    #[serde(rename = "change24h")]
+    pub change_24h: String,
+}
+
+unsafe impl Send for OptionTickerData {}
+unsafe impl Sync for OptionTickerData {}
+
+impl OptionTickerData {
+    /// Parses the greeks and implied volatilities of this ticker, stamped with `ts`.
+    pub fn greeks(&self, ts: u64) -> Result<Greeks, BybitError> {
+        Ok(Greeks {
+            symbol: self.symbol.clone(),
+            delta: self.delta.parse()?,
+            gamma: self.gamma.parse()?,
+            vega: self.vega.parse()?,
+            theta: self.theta.parse()?,
+            mark_price_iv: self.mark_price_iv.parse()?,
+            bid_iv: self.bid_iv.parse()?,
+            ask_iv: self.ask_iv.parse()?,
+            mark_price: self.mark_price.parse()?,
+            underlying_price: self.underlying_price.parse()?,
+            ts,
+        })
+    }
+}
+
+/// The greeks of one option contract, as pushed on its ticker topic.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Greeks {
+    pub symbol: String,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub mark_price_iv: f64,
+    pub bid_iv: f64,
+    pub ask_iv: f64,
+    pub mark_price: f64,
+    pub underlying_price: f64,
+    pub ts: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Liquidation {
     #[serde(rename = "topic")]
@@ -3482,17 +5587,57 @@ pub struct Liquidation {
 unsafe impl Send for Liquidation {}
 unsafe impl Sync for Liquidation {}
 
+/// A message on the `allLiquidation.{symbol}` topic, which batches every liquidation
+/// using the short `T`/`s`/`S`/`v`/`p` field names.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AllLiquidation {
+    #[serde(rename = "topic")]
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(rename = "ts")]
+    pub ts: u64,
+    #[serde(rename = "data")]
+    pub data: Vec<LiquidationData>,
+}
+
+unsafe impl Send for AllLiquidation {}
+unsafe impl Sync for AllLiquidation {}
+
+/// A message on the `insurance.{coin}` topic. Bybit pushes one entry per insurance pool
+/// whose balance changed; a pool shared by several contracts lists all of them in `symbols`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InsuranceEvent {
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub ts: u64,
+    pub data: Vec<InsuranceData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InsuranceData {
+    pub coin: String,
+    /// Comma separated contracts sharing this pool.
+    pub symbols: String,
+    #[serde(with = "string_to_float")]
+    pub balance: f64,
+    #[serde(with = "string_to_u64")]
+    pub update_time: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LiquidationData {
-    #[serde(rename = "updatedTime")]
+    #[serde(rename = "updatedTime", alias = "T")]
     pub updated_time: u64,
-    #[serde(rename = "symbol")]
+    #[serde(rename = "symbol", alias = "s")]
     pub symbol: String,
-    #[serde(rename = "side")]
+    #[serde(rename = "side", alias = "S")]
     pub side: String,
-    #[serde(with = "string_to_float")]
+    #[serde(alias = "v", with = "string_to_float")]
     pub size: f64,
-    #[serde(with = "string_to_float")]
+    #[serde(alias = "p", with = "string_to_float")]
     pub price: f64,
 }
 
@@ -3512,9 +5657,23 @@ pub struct WsKline {
 unsafe impl Send for WsKline {}
 unsafe impl Sync for WsKline {}
 
+impl WsKline {
+    /// The candles in this message that have closed. See `KlineData::confirm`.
+    pub fn closed(&self) -> impl Iterator<Item = &KlineData> {
+        self.data.iter().filter(|kline| kline.confirm)
+    }
+}
+
+/// One candle from the `kline.{interval}.{symbol}` topic.
+///
+/// Bybit pushes the candle that is still forming several times a second, each time with
+/// the latest `close`, `high`, `low` and `volume`, and `confirm` set to false. The final
+/// message for a candle has `confirm` set to true; only that one holds the closed bar.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct KlineData {
+    /// Start of the candle, in milliseconds.
     pub start: u64,
+    /// End of the candle, in milliseconds.
     pub end: u64,
     pub interval: String,
     pub open: String,
@@ -3523,13 +5682,47 @@ pub struct KlineData {
     pub low: String,
     pub volume: String,
     pub turnover: String,
+    /// True once the candle has closed; false for updates to the open candle.
     pub confirm: bool,
+    /// When this update was generated, in milliseconds.
     pub timestamp: u64,
 }
 
 unsafe impl Send for KlineData {}
 unsafe impl Sync for KlineData {}
 
+/// A message from the `kline_lt.{interval}.{symbol}` topic.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WsLtKline {
+    pub topic: String,
+    pub data: Vec<LtKlineData>,
+    #[serde(rename = "ts")]
+    pub timestamp: u64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+impl WsLtKline {
+    /// The candles in this message that have closed.
+    pub fn closed(&self) -> impl Iterator<Item = &LtKlineData> {
+        self.data.iter().filter(|kline| kline.confirm)
+    }
+}
+
+/// One leveraged token NAV candle. Unlike `KlineData` it carries no volume or turnover.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LtKlineData {
+    pub start: u64,
+    pub end: u64,
+    pub interval: String,
+    pub open: String,
+    pub close: String,
+    pub high: String,
+    pub low: String,
+    pub confirm: bool,
+    pub timestamp: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PositionEvent {
     pub id: String,
@@ -3712,6 +5905,8 @@ pub struct FastExecData {
     #[serde(rename = "orderLinkId")]
     pub order_link_id: String,
     pub side: String,
+    #[serde(rename = "isMaker", default)]
+    pub is_maker: bool,
     #[serde(rename = "execTime")]
     pub exec_time: String,
     pub seq: u64,
@@ -3720,23 +5915,47 @@ pub struct FastExecData {
 unsafe impl Send for FastExecData {}
 unsafe impl Sync for  FastExecData {}
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GreeksEvent {
+    pub id: String,
+    pub topic: String,
+    #[serde(rename = "creationTime")]
+    pub creation_time: u64,
+    pub data: Vec<PortfolioGreeks>,
+}
+
+/// The account's total greeks over all option positions of one base coin.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioGreeks {
+    pub base_coin: String,
+    #[serde(with = "string_to_float")]
+    pub total_delta: f64,
+    #[serde(with = "string_to_float")]
+    pub total_gamma: f64,
+    #[serde(with = "string_to_float")]
+    pub total_vega: f64,
+    #[serde(with = "string_to_float")]
+    pub total_theta: f64,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrderData {
     pub symbol: String,
     #[serde(rename = "orderId")]
     pub order_id: String,
-    pub side: String,
+    pub side: Side,
     #[serde(rename = "orderType")]
-    pub order_type: String,
+    pub order_type: OrderType,
     #[serde(rename = "cancelType")]
     pub cancel_type: String,
     pub price: String,
-    pub qty: String,
+    #[serde(with = "string_to_float")]
+    pub qty: f64,
     #[serde(rename = "orderIv")]
     pub order_iv: String,
     #[serde(rename = "timeInForce")]
-    pub time_in_force: String,
+    pub time_in_force: TimeInForce,
     #[serde(rename = "orderStatus")]
     pub order_status: String,
     #[serde(rename = "orderLinkId")]
@@ -3745,16 +5964,18 @@ pub struct OrderData {
     pub last_price_on_created: String,
     #[serde(rename = "reduceOnly")]
     pub reduce_only: bool,
-    #[serde(rename = "leavesQty")]
-    pub leaves_qty: String,
+    /// Empty for options that are no longer open.
+    #[serde(rename = "leavesQty", with = "string_to_float_optional", default)]
+    pub leaves_qty: Option<f64>,
     #[serde(rename = "leavesValue")]
     pub leaves_value: String,
-    #[serde(rename = "cumExecQty")]
-    pub cum_exec_qty: String,
-    #[serde(rename = "cumExecValue")]
-    pub cum_exec_value: String,
-    #[serde(rename = "avgPrice")]
-    pub avg_price: String,
+    #[serde(rename = "cumExecQty", with = "string_to_float_optional", default)]
+    pub cum_exec_qty: Option<f64>,
+    #[serde(rename = "cumExecValue", with = "string_to_float_optional", default)]
+    pub cum_exec_value: Option<f64>,
+    /// Empty, or `0` for some categories, until the order fills.
+    #[serde(rename = "avgPrice", with = "string_to_float_optional", default)]
+    pub avg_price: Option<f64>,
     #[serde(rename = "blockTradeId")]
     pub block_trade_id: String,
     #[serde(rename = "positionIdx")]
@@ -3804,6 +6025,24 @@ pub struct OrderData {
     pub fee_currency: String,
 }
 
+impl OrderData {
+    /// Parses `order_status`, or returns `None` for a status this crate does not know.
+    pub fn state(&self) -> Option<OrderState> {
+        from_value(Value::String(self.order_status.clone())).ok()
+    }
+
+    /// Whether Bybit reports the whole `qty` as executed.
+    pub fn is_fully_filled(&self) -> bool {
+        self.state() == Some(OrderState::Filled)
+    }
+
+    /// Executed share of `qty`, from `0.0` to `1.0`. Meaningless for spot market orders whose
+    /// `qty` is given in the quote coin.
+    pub fn fill_ratio(&self) -> f64 {
+        fill_ratio(self.cum_exec_qty.unwrap_or(0.0), self.qty)
+    }
+}
+
 unsafe impl Send for OrderData {}
 unsafe impl Sync for OrderData {}
 
@@ -3830,119 +6069,119 @@ pub struct WalletEvent {
 unsafe impl Send for WalletEvent {}
 unsafe impl Sync for WalletEvent {}
 
+/// A wallet from `get_wallet_balance` or the `wallet` topic.
+///
+/// Unified (`UNIFIED`) accounts fill in the account-wide totals and margin rates. Classic
+/// (`CONTRACT`, `SPOT`) accounts leave them empty or omit them, so they are `None` there.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WalletData {
-    #[serde(rename = "accountIMRate")]
-    pub account_im_rate: String,
-    #[serde(rename = "accountMMRate")]
-    pub account_mm_rate: String,
-    #[serde(rename = "totalEquity")]
-    pub total_equity: String,
-    #[serde(rename = "totalWalletBalance")]
-    pub total_wallet_balance: String,
-    #[serde(rename = "totalMarginBalance")]
-    pub total_margin_balance: String,
-    #[serde(rename = "totalAvailableBalance")]
-    pub total_available_balance: String,
-    #[serde(rename = "totalPerpUPL")]
-    pub total_perp_upl: String,
-    #[serde(rename = "totalInitialMargin")]
-    pub total_initial_margin: String,
-    #[serde(rename = "totalMaintenanceMargin")]
-    pub total_maintenance_margin: String,
-    #[serde(rename = "coin")]
+    #[serde(rename = "accountIMRate", with = "string_to_float_optional", default)]
+    pub account_im_rate: Option<f64>,
+    #[serde(rename = "accountMMRate", with = "string_to_float_optional", default)]
+    pub account_mm_rate: Option<f64>,
+    #[serde(rename = "totalEquity", with = "string_to_float_optional", default)]
+    pub total_equity: Option<f64>,
+    #[serde(
+        rename = "totalWalletBalance",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub total_wallet_balance: Option<f64>,
+    #[serde(
+        rename = "totalMarginBalance",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub total_margin_balance: Option<f64>,
+    #[serde(
+        rename = "totalAvailableBalance",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub total_available_balance: Option<f64>,
+    #[serde(rename = "totalPerpUPL", with = "string_to_float_optional", default)]
+    pub total_perp_upl: Option<f64>,
+    #[serde(
+        rename = "totalInitialMargin",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub total_initial_margin: Option<f64>,
+    #[serde(
+        rename = "totalMaintenanceMargin",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub total_maintenance_margin: Option<f64>,
+    #[serde(rename = "coin", default)]
     pub coin: Vec<CoinData>,
-    #[serde(rename = "accountLTV")]
-    pub account_ltv: String,
+    #[serde(rename = "accountLTV", with = "string_to_float_optional", default)]
+    pub account_ltv: Option<f64>,
     #[serde(rename = "accountType", skip_serializing_if = "Option::is_none")]
     pub account_type: Option<String>,
 }
+
+impl WalletData {
+    /// Whether this is a unified trading account wallet.
+    pub fn is_unified(&self) -> bool {
+        self.account_type.as_deref() == Some("UNIFIED")
+    }
+}
+
 unsafe impl Send for WalletData {}
 unsafe impl Sync for WalletData {}
+/// One coin of a `WalletData`. Fields that do not apply to the account type, e.g. `free` outside
+/// classic spot accounts or the borrowing fields outside unified ones, are `None`.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CoinData {
     #[serde(rename = "coin")]
     pub coin: String,
-    #[serde(rename = "equity")]
-    pub equity: String,
-    #[serde(rename = "usdValue")]
-    pub usd_value: String,
-    #[serde(rename = "walletBalance")]
-    pub wallet_balance: String,
-    #[serde(rename = "availableToWithdraw")]
-    pub available_to_withdraw: String,
-    #[serde(rename = "availableToBorrow")]
-    pub available_to_borrow: String,
-    #[serde(rename = "borrowAmount")]
-    pub borrow_amount: String,
-    #[serde(rename = "accruedInterest")]
-    pub accrued_interest: String,
-    #[serde(rename = "totalOrderIM")]
-    pub total_order_im: String,
-    #[serde(rename = "totalPositionIM")]
-    pub total_position_im: String,
-    #[serde(rename = "totalPositionMM")]
-    pub total_position_mm: String,
-    #[serde(rename = "unrealisedPnl")]
-    pub unrealised_pnl: String,
-    #[serde(rename = "cumRealisedPnl")]
-    pub cum_realised_pnl: String,
-    pub bonus: String,
-    #[serde(rename = "collateralSwitch")]
-    pub collateral_switch: bool,
-    #[serde(rename = "marginCollateral")]
-    pub margin_collateral: bool,
-    #[serde(rename = "locked")]
-    pub locked: String,
-    #[serde(rename = "spotHedgingQty")]
-    pub spot_hedging_qty: String,
+    #[serde(rename = "equity", with = "string_to_float_optional", default)]
+    pub equity: Option<f64>,
+    #[serde(rename = "usdValue", with = "string_to_float_optional", default)]
+    pub usd_value: Option<f64>,
+    #[serde(rename = "walletBalance", with = "string_to_float_optional", default)]
+    pub wallet_balance: Option<f64>,
+    #[serde(
+        rename = "availableToWithdraw",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub available_to_withdraw: Option<f64>,
+    #[serde(
+        rename = "availableToBorrow",
+        with = "string_to_float_optional",
+        default
+    )]
+    pub available_to_borrow: Option<f64>,
+    #[serde(rename = "borrowAmount", with = "string_to_float_optional", default)]
+    pub borrow_amount: Option<f64>,
+    #[serde(rename = "accruedInterest", with = "string_to_float_optional", default)]
+    pub accrued_interest: Option<f64>,
+    #[serde(rename = "totalOrderIM", with = "string_to_float_optional", default)]
+    pub total_order_im: Option<f64>,
+    #[serde(rename = "totalPositionIM", with = "string_to_float_optional", default)]
+    pub total_position_im: Option<f64>,
+    #[serde(rename = "totalPositionMM", with = "string_to_float_optional", default)]
+    pub total_position_mm: Option<f64>,
+    #[serde(rename = "unrealisedPnl", with = "string_to_float_optional", default)]
+    pub unrealised_pnl: Option<f64>,
+    #[serde(rename = "cumRealisedPnl", with = "string_to_float_optional", default)]
+    pub cum_realised_pnl: Option<f64>,
+    #[serde(with = "string_to_float_optional", default)]
+    pub bonus: Option<f64>,
+    #[serde(rename = "collateralSwitch", default)]
+    pub collateral_switch: Option<bool>,
+    #[serde(rename = "marginCollateral", default)]
+    pub margin_collateral: Option<bool>,
+    /// Available balance of a classic spot account.
+    #[serde(rename = "free", with = "string_to_float_optional", default)]
+    pub free: Option<f64>,
+    #[serde(rename = "locked", with = "string_to_float_optional", default)]
+    pub locked: Option<f64>,
+    #[serde(rename = "spotHedgingQty", with = "string_to_float_optional", default)]
+    pub spot_hedging_qty: Option<f64>,
 }
 
 unsafe impl Send for CoinData {}
 unsafe impl Sync for CoinData {}
-
-mod string_to_u64 {
-    use std::default;
-
-    use serde::{self, Deserialize, Deserializer, Serializer};
-
-    // Serialize a u64 as a string.
-    pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let s = value.to_string();
-        serializer.serialize_str(&s)
-    }
-
-    // Deserialize a string to a u64.
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        s.parse::<u64>().map_err(serde::de::Error::custom)
-    }
-}
-
-mod string_to_float {
-    use serde::{self, Deserialize, Deserializer, Serializer};
-
-    // Serialize a u64 as a string.
-    pub fn serialize<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let s = value.to_string();
-        serializer.serialize_str(&s)
-    }
-
-    // Deserialize a string as an f64.
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        s.parse::<f64>().map_err(serde::de::Error::custom)
-    }
-}
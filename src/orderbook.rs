@@ -0,0 +1,152 @@
+use crate::errors::BybitError;
+use crate::model::{Ask, Bid, OrderBook, OrderBookUpdate};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Price key for the book levels. Prices arrive as finite decimals, so
+/// `f64::total_cmp` gives a total order that is safe to use in a `BTreeMap`.
+#[derive(Clone, Copy, Debug)]
+pub struct Price(pub f64);
+
+impl PartialEq for Price {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Price {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// A local copy of one symbol's order book, kept in sync from the
+/// `orderbook.{depth}.{symbol}` websocket topic.
+///
+/// Bybit sends a `snapshot` first and `delta` messages afterwards. A delta level
+/// with a quantity of zero removes that price. Each message carries an update id
+/// (`u`) that must increase by exactly one; a gap means a message was lost and the
+/// book must be rebuilt by resubscribing.
+#[derive(Clone, Debug, Default)]
+pub struct LocalOrderBook {
+    pub symbol: String,
+    pub bids: BTreeMap<Price, f64>,
+    pub asks: BTreeMap<Price, f64>,
+    pub update_id: u64,
+    pub seq: u64,
+    pub timestamp: u64,
+    initialized: bool,
+}
+
+impl LocalOrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a book from a REST `get_depth` snapshot. Websocket deltas whose update
+    /// id follows the snapshot's `u` can then be applied on top of it.
+    pub fn from_snapshot(snapshot: &OrderBook) -> Self {
+        let mut book = Self {
+            symbol: snapshot.symbol.clone(),
+            update_id: snapshot.update_id,
+            seq: snapshot.seq,
+            timestamp: snapshot.timestamp,
+            initialized: true,
+            ..Self::default()
+        };
+        for bid in &snapshot.bids {
+            Self::apply_level(&mut book.bids, bid.price, bid.qty);
+        }
+        for ask in &snapshot.asks {
+            Self::apply_level(&mut book.asks, ask.price, ask.qty);
+        }
+        book
+    }
+
+    /// Applies a websocket order book message.
+    ///
+    /// Returns `BybitError::OrderBookSequenceGap` when a delta does not follow the
+    /// previous update id, or when a delta arrives before any snapshot. The book is
+    /// left untouched in that case.
+    pub fn apply(&mut self, event: &OrderBookUpdate) -> Result<(), BybitError> {
+        let data = &event.data;
+        // Bybit resends a snapshot with `u = 1` after a service restart.
+        if event.is_snapshot() || data.update_id == 1 {
+            self.bids.clear();
+            self.asks.clear();
+            self.symbol = data.symbol.clone();
+            self.initialized = true;
+        } else {
+            let expected = self.update_id + 1;
+            if !self.initialized || data.update_id != expected {
+                return Err(BybitError::OrderBookSequenceGap {
+                    expected,
+                    received: data.update_id,
+                });
+            }
+        }
+        for bid in &data.bids {
+            Self::apply_level(&mut self.bids, bid.price, bid.qty);
+        }
+        for ask in &data.asks {
+            Self::apply_level(&mut self.asks, ask.price, ask.qty);
+        }
+        self.update_id = data.update_id;
+        self.seq = data.seq;
+        self.timestamp = event.timestamp;
+        Ok(())
+    }
+
+    fn apply_level(side: &mut BTreeMap<Price, f64>, price: f64, qty: f64) {
+        if qty == 0.0 {
+            side.remove(&Price(price));
+        } else {
+            side.insert(Price(price), qty);
+        }
+    }
+
+    /// Clears all levels; the next message must be a snapshot.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn best_bid(&self) -> Option<Bid> {
+        self.bids
+            .iter()
+            .next_back()
+            .map(|(price, qty)| Bid::new(price.0, *qty))
+    }
+
+    pub fn best_ask(&self) -> Option<Ask> {
+        self.asks
+            .iter()
+            .next()
+            .map(|(price, qty)| Ask::new(price.0, *qty))
+    }
+
+    /// Returns the top `n` levels of each side, best price first.
+    pub fn depth(&self, n: usize) -> (Vec<Bid>, Vec<Ask>) {
+        let bids = self
+            .bids
+            .iter()
+            .rev()
+            .take(n)
+            .map(|(price, qty)| Bid::new(price.0, *qty))
+            .collect();
+        let asks = self
+            .asks
+            .iter()
+            .take(n)
+            .map(|(price, qty)| Ask::new(price.0, *qty))
+            .collect();
+        (bids, asks)
+    }
+}
@@ -1,20 +1,33 @@
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 use serde_json::{json, Value};
 
-use crate::api::{Position, API};
-use crate::client::Client;
+use crate::api::{Market, Position, API};
+use crate::client::{paginate_all, Client, MAX_PAGES};
 use crate::errors::BybitError;
 use crate::model::{
-    AddMarginRequest, AddMarginResponse, AddReduceMarginRequest, AddReduceMarginResponse,
-    ChangeMarginRequest, ChangeMarginResponse, ClosedPnlRequest,
-    ClosedPnlResponse, InfoResponse, LeverageRequest, LeverageResponse,
-    MarginModeRequest, MarginModeResponse, MoveHistoryRequest, MoveHistoryResponse,
-    MovePositionRequest, MovePositionResponse, PositionRequest, SetRiskLimit, SetRiskLimitResponse, TradingStopRequest,
-    TradingStopResponse,
+    AddMarginRequest, AddMarginResponse, AddReduceMarginRequest, AddReduceMarginResponse, Category,
+    ChangeMarginRequest, ChangeMarginResponse, ClosedPnlItem, ClosedPnlRequest, ClosedPnlResponse,
+    Empty, InfoResponse, LeverageRequest, LeverageResponse, MarginModeRequest, MarginModeResponse,
+    MoveHistoryRequest, MoveHistoryResponse, MovePositionRequest, MovePositionResponse,
+    PositionInfo, PositionRequest, RiskLimit, RiskLimitResponse, SetRiskLimit,
+    SetRiskLimitResponse, SetRiskLimitResult, TradingStopRequest, TradingStopResponse,
 };
 use crate::util::{build_json_request, build_request, date_to_milliseconds};
 
+/// retCode Bybit returns when `set_leverage` would not change the current leverage.
+pub const LEVERAGE_NOT_MODIFIED: i32 = 110043;
+
+/// retCode Bybit returns when `set_position_mode` would not change the position mode.
+pub const POSITION_MODE_NOT_MODIFIED: i32 = 110025;
+
+/// retCode Bybit returns when `set_margin_mode` would not change the margin mode.
+pub const MARGIN_MODE_NOT_MODIFIED: i32 = 110026;
+
+/// retCode Bybit returns when `set_risk_limit` would not change the risk limit.
+pub const RISK_LIMIT_NOT_MODIFIED: i32 = 110075;
+
 #[derive(Clone)]
 pub struct PositionManager {
     pub client: Client,
@@ -63,6 +76,9 @@ impl PositionManager {
         if let Some(v) = req.limit {
             parameters.insert("limit".into(), v.to_string());
         }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
         let request = build_request(&parameters);
         let response: InfoResponse = self
             .client
@@ -75,15 +91,34 @@ impl PositionManager {
         Ok(response)
     }
 
+    /// Retrieves every position matching `req`, following `nextPageCursor` until the last page.
+    pub async fn get_all_positions<'a>(
+        &self,
+        req: PositionRequest<'a>,
+    ) -> Result<Vec<PositionInfo>, BybitError> {
+        paginate_all(MAX_PAGES, |cursor| {
+            let page = PositionRequest {
+                cursor: cursor.map(Cow::Owned),
+                ..req.clone()
+            };
+            async move {
+                let result = self.get_info(page).await?.result;
+                Ok((result.list, result.next_page_cursor))
+            }
+        })
+        .await
+    }
+
     // Sets the leverage for a given symbol.
     ///
     /// # Arguments
     ///
-    /// * `req` - The leverage request containing category, symbol, and leverage.
+    /// * `req` - The leverage request containing category, symbol, and buy/sell leverage.
     ///
     /// # Returns
     ///
-    /// A result containing the leverage response.
+    /// A result containing the leverage response. Bybit answers with retCode `110043` when the
+    /// leverage is already set to the requested value; that is returned as a success.
     pub async fn set_leverage<'a>(
         &self,
         req: LeverageRequest<'a>,
@@ -91,18 +126,29 @@ impl PositionManager {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         parameters.insert("category".into(), req.category.as_str().into());
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("buyLeverage".into(), req.leverage.to_string());
-        parameters.insert("sellLeverage".into(), req.leverage.to_string());
+        parameters.insert("buyLeverage".into(), req.buy_leverage.to_string());
+        parameters.insert("sellLeverage".into(), req.sell_leverage.to_string());
         let request = build_json_request(&parameters);
-        let response: LeverageResponse = self
+        let response = self
             .client
             .post_signed(
                 API::Position(Position::SetLeverage),
                 self.recv_window.into(),
                 Some(request),
             )
-            .await?;
-        Ok(response)
+            .await;
+        match response {
+            Err(BybitError::BybitError(error)) if error.code == LEVERAGE_NOT_MODIFIED => {
+                Ok(LeverageResponse {
+                    ret_code: error.code,
+                    ret_msg: error.msg,
+                    result: Empty {},
+                    ret_ext_info: Empty {},
+                    time: 0,
+                })
+            }
+            response => response,
+        }
     }
 
     /// Set the margin mode.
@@ -113,7 +159,9 @@ impl PositionManager {
     ///
     /// # Returns
     ///
-    /// * Result<ChangeMarginResponse> - The result of setting the margin mode.
+    /// * Result<ChangeMarginResponse> - The result of setting the margin mode. Bybit answers
+    ///   with retCode `110026` when the symbol already uses the requested mode; that is
+    ///   returned as a success.
     pub async fn set_margin_mode<'a>(
         &self,
         req: ChangeMarginRequest<'a>,
@@ -121,17 +169,30 @@ impl PositionManager {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
         parameters.insert("category".into(), req.category.as_str().into());
         parameters.insert("symbol".into(), req.symbol.into());
-        parameters.insert("tradeMode".into(), req.trade_mode.into());
+        parameters.insert("tradeMode".into(), req.trade_mode.as_i8().into());
+        parameters.insert("buyLeverage".into(), req.buy_leverage.to_string().into());
+        parameters.insert("sellLeverage".into(), req.sell_leverage.to_string().into());
         let request = build_json_request(&parameters);
-        let response: ChangeMarginResponse = self
+        let response = self
             .client
             .post_signed(
                 API::Position(Position::SwitchIsolated),
                 self.recv_window.into(),
                 Some(request),
             )
-            .await?;
-        Ok(response)
+            .await;
+        match response {
+            Err(BybitError::BybitError(error)) if error.code == MARGIN_MODE_NOT_MODIFIED => {
+                Ok(ChangeMarginResponse {
+                    ret_code: error.code,
+                    ret_msg: error.msg,
+                    result: Empty {},
+                    ret_ext_info: Empty {},
+                    time: 0,
+                })
+            }
+            response => response,
+        }
     }
 
     /// Set the position mode.
@@ -142,7 +203,9 @@ impl PositionManager {
     ///
     /// # Returns
     ///
-    /// * Result<MarginModeResponse> - The result of setting the position mode.
+    /// * Result<MarginModeResponse> - The result of setting the position mode. Bybit answers
+    ///   with retCode `110025` when the position mode is already the requested one; that is
+    ///   returned as a success.
     pub async fn set_position_mode<'a>(
         &self,
         req: MarginModeRequest<'a>,
@@ -155,17 +218,48 @@ impl PositionManager {
         if let Some(v) = req.coin {
             parameters.insert("coin".into(), v.into());
         }
-        parameters.insert("mode".into(), req.mode.into());
+        parameters.insert("mode".into(), req.mode.as_i8().into());
         let request = build_json_request(&parameters);
-        let response: MarginModeResponse = self
+        let response = self
             .client
             .post_signed(
                 API::Position(Position::SwitchMode),
                 self.recv_window.into(),
                 Some(request),
             )
+            .await;
+        match response {
+            Err(BybitError::BybitError(error)) if error.code == POSITION_MODE_NOT_MODIFIED => {
+                Ok(MarginModeResponse {
+                    ret_code: error.code,
+                    ret_msg: error.msg,
+                    result: Empty {},
+                    ret_ext_info: Empty {},
+                    time: 0,
+                })
+            }
+            response => response,
+        }
+    }
+
+    /// Retrieves the risk limit tiers of `symbol`, lowest first.
+    ///
+    /// Each tier caps the position value (`risk_limit_value`) and sets the margin rates and
+    /// the highest leverage allowed up to it. Pass a tier's `id` to `set_risk_limit`.
+    pub async fn get_risk_limit(
+        &self,
+        category: Category,
+        symbol: &str,
+    ) -> Result<Vec<RiskLimit>, BybitError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), category.as_str().into());
+        parameters.insert("symbol".into(), symbol.into());
+        let request = build_request(&parameters);
+        let response: RiskLimitResponse = self
+            .client
+            .get(API::Market(Market::RiskLimit), Some(request))
             .await?;
-        Ok(response)
+        Ok(response.result.list)
     }
 
     /// Set the risk limit.
@@ -176,35 +270,59 @@ impl PositionManager {
     ///
     /// # Returns
     ///
-    /// * Result<SetRiskLimitResult> - The result of setting the risk limit.
+    /// * Result<SetRiskLimitResult> - The result of setting the risk limit. Bybit answers with
+    ///   retCode `110075` when the tier is already in use; that is returned as a success, with
+    ///   the tier's value looked up through `get_risk_limit`.
     pub async fn set_risk_limit<'a>(
         &self,
         req: SetRiskLimit<'a>,
     ) -> Result<SetRiskLimitResponse, BybitError> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
         parameters.insert("category".into(), req.category.as_str().into());
-        parameters.insert("symbol".into(), req.symbol.into());
+        parameters.insert("symbol".into(), req.symbol.clone().into());
         parameters.insert("riskId".into(), req.risk_id.into());
         if let Some(v) = req.position_idx {
             parameters.insert("positionIdx".into(), v.into());
         }
         let request = build_json_request(&parameters);
-        let response: SetRiskLimitResponse = self
+        let response = self
             .client
             .post_signed(
                 API::Position(Position::SetRiskLimit),
                 self.recv_window.into(),
                 Some(request),
             )
-            .await?;
-        Ok(response)
+            .await;
+        match response {
+            Err(BybitError::BybitError(error)) if error.code == RISK_LIMIT_NOT_MODIFIED => {
+                let tiers = self.get_risk_limit(req.category, &req.symbol).await?;
+                let risk_limit_value = tiers
+                    .iter()
+                    .find(|tier| tier.id == req.risk_id as u64)
+                    .map(|tier| tier.risk_limit_value as u64)
+                    .unwrap_or_default();
+                Ok(SetRiskLimitResponse {
+                    ret_code: error.code,
+                    ret_msg: error.msg,
+                    result: SetRiskLimitResult {
+                        risk_id: req.risk_id.into(),
+                        risk_limit_value,
+                        category: req.category.as_str().to_string(),
+                    },
+                    ret_ext_info: Empty {},
+                    time: 0,
+                })
+            }
+            response => response,
+        }
     }
 
     /// Set the trading stop.
     ///
     /// # Arguments
     ///
-    /// * `req` - The TradingStopRequest containing the necessary information.
+    /// * `req` - The TradingStopRequest containing the necessary information. Levels left
+    ///   at `None` are not sent and stay as they are; `StopLevel::Clear` removes them.
     ///
     /// # Returns
     ///
@@ -213,14 +331,32 @@ impl PositionManager {
         &self,
         req: TradingStopRequest<'a>,
     ) -> Result<TradingStopResponse, BybitError> {
+        let parameters = Self::build_trading_stop(req);
+        let request = build_json_request(&parameters);
+        let response: TradingStopResponse = self
+            .client
+            .post_signed(
+                API::Position(Position::SetTradingStop),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// Builds the `set_trading_stop` body. Only the fields that are set are included.
+    pub fn build_trading_stop<'a>(req: TradingStopRequest<'a>) -> BTreeMap<String, Value> {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
         parameters.insert("category".into(), req.category.as_str().into());
         parameters.insert("symbol".into(), req.symbol.into());
         if let Some(v) = req.take_profit {
-            parameters.insert("takeProfit".into(), v.into());
+            parameters.insert("takeProfit".into(), v.as_param().into());
         }
         if let Some(v) = req.stop_loss {
-            parameters.insert("stopLoss".into(), v.into());
+            parameters.insert("stopLoss".into(), v.as_param().into());
+        }
+        if let Some(v) = req.trailing_stop {
+            parameters.insert("trailingStop".into(), v.as_param().into());
         }
         if let Some(v) = req.tp_trigger_by {
             parameters.insert("tpTriggerBy".into(), v.into());
@@ -238,28 +374,19 @@ impl PositionManager {
             parameters.insert("slOrderType".into(), v.as_str().into());
         }
         if let Some(v) = req.tp_size {
-            parameters.insert("tpSize".into(), v.into());
+            parameters.insert("tpSize".into(), v.to_string().into());
         }
         if let Some(v) = req.sl_size {
-            parameters.insert("slSize".into(), v.into());
+            parameters.insert("slSize".into(), v.to_string().into());
         }
         if let Some(v) = req.tp_limit_price {
-            parameters.insert("tpLimitPrice".into(), v.into());
+            parameters.insert("tpLimitPrice".into(), v.to_string().into());
         }
         if let Some(v) = req.sl_limit_price {
-            parameters.insert("slLimitPrice".into(), v.into());
+            parameters.insert("slLimitPrice".into(), v.to_string().into());
         }
         parameters.insert("positionIdx".into(), req.position_idx.into());
-        let request = build_json_request(&parameters);
-        let response: TradingStopResponse = self
-            .client
-            .post_signed(
-                API::Position(Position::SetTradingStop),
-                self.recv_window.into(),
-                Some(request),
-            )
-            .await?;
-        Ok(response)
+        parameters
     }
 
     pub async fn set_add_margin<'a>(
@@ -312,6 +439,11 @@ impl PositionManager {
         Ok(response)
     }
 
+    /// Retrieves one page of closed position PnL, newest first.
+    ///
+    /// Bybit serves at most seven days between `start_time` and `end_time`, and the last
+    /// seven days when neither is set. Set `cursor` to the previous page's
+    /// `nextPageCursor` to continue, or use `get_all_closed_pnl`.
     pub async fn get_closed_pnl<'a>(
         &self,
         req: ClosedPnlRequest<'a>,
@@ -325,18 +457,21 @@ impl PositionManager {
         if let Some(start_str) = req.start_time.as_ref().map(|s| s.as_ref()) {
             let start_millis = date_to_milliseconds(start_str);
             parameters
-                .entry("end".to_owned())
+                .entry("startTime".to_owned())
                 .or_insert_with(|| start_millis.to_string().into());
         }
         if let Some(end_str) = req.end_time.as_ref().map(|s| s.as_ref()) {
             let end_millis = date_to_milliseconds(end_str);
             parameters
-                .entry("end".to_owned())
+                .entry("endTime".to_owned())
                 .or_insert_with(|| end_millis.to_string().into());
         }
         if let Some(v) = req.limit {
             parameters.insert("limit".into(), v.into());
         }
+        if let Some(v) = req.cursor {
+            parameters.insert("cursor".into(), v.into());
+        }
         let request = build_request(&parameters);
         let response: ClosedPnlResponse = self
             .client
@@ -349,6 +484,31 @@ impl PositionManager {
         Ok(response)
     }
 
+    /// Retrieves every closed PnL item in the window of `req`, following
+    /// `nextPageCursor` until the last page.
+    pub async fn get_all_closed_pnl<'a>(
+        &self,
+        req: ClosedPnlRequest<'a>,
+    ) -> Result<Vec<ClosedPnlItem>, BybitError> {
+        paginate_all(MAX_PAGES, |cursor| {
+            let page = ClosedPnlRequest {
+                cursor: cursor.map(Cow::Owned),
+                ..req.clone()
+            };
+            async move { Ok(self.get_closed_pnl(page).await?.result.into_page()) }
+        })
+        .await
+    }
+
+    /// Sums `closed_pnl` over every item in the window of `req`.
+    pub async fn get_total_closed_pnl<'a>(
+        &self,
+        req: ClosedPnlRequest<'a>,
+    ) -> Result<f64, BybitError> {
+        let items = self.get_all_closed_pnl(req).await?;
+        Ok(items.iter().map(|item| item.closed_pnl).sum())
+    }
+
     pub async fn move_position<'a>(
         &self,
         req: MovePositionRequest<'a>,
@@ -0,0 +1,187 @@
+use crate::api::API;
+use crate::config::RateLimitConfig;
+use crate::errors::BybitError;
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Bybit applies its REST limits per group of endpoints rather than globally.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndpointGroup {
+    Order,
+    Position,
+    Account,
+    Market,
+}
+
+impl From<&API> for EndpointGroup {
+    fn from(endpoint: &API) -> Self {
+        match endpoint {
+            API::Market(_) => EndpointGroup::Market,
+            API::Trade(_) => EndpointGroup::Order,
+            API::Position(_) => EndpointGroup::Position,
+            API::Account(_) | API::Asset(_) | API::SpotLeverage(_) | API::SpotMargin(_) => {
+                EndpointGroup::Account
+            }
+        }
+    }
+}
+
+/// The request budget Bybit reported in the `X-Bapi-Limit*` headers of a REST response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub group: EndpointGroup,
+    /// Requests allowed per window (`X-Bapi-Limit`).
+    pub limit: u32,
+    /// Requests left in the current window (`X-Bapi-Limit-Status`).
+    pub remaining: u32,
+    /// When the window starts over (`X-Bapi-Limit-Reset-Timestamp`).
+    pub reset: DateTime<Utc>,
+}
+
+impl RateLimitStatus {
+    /// Reads the headers of a response from an endpoint of `group`. Returns `None` unless all
+    /// three are present and valid; Bybit leaves them out on some public endpoints.
+    pub fn from_headers(group: EndpointGroup, headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<i64>().ok())
+        };
+        Some(Self {
+            group,
+            limit: u32::try_from(header("X-Bapi-Limit")?).ok()?,
+            remaining: u32::try_from(header("X-Bapi-Limit-Status")?).ok()?,
+            reset: Utc
+                .timestamp_millis_opt(header("X-Bapi-Limit-Reset-Timestamp")?)
+                .single()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(rate: u32) -> Self {
+        let rate = f64::from(rate.max(1));
+        Self {
+            capacity: rate,
+            tokens: rate,
+            refill_per_sec: rate,
+            last_refill: Instant::now(),
+            blocked_until: None,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        if let Some(until) = self.blocked_until {
+            if now < until {
+                return Err(until - now);
+            }
+            self.blocked_until = None;
+            self.last_refill = now;
+        }
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+/// Token bucket rate limiter shared by all clones of a `Client`.
+///
+/// Each endpoint group has its own bucket. The `X-Bapi-Limit-Status` and
+/// `X-Bapi-Limit-Reset-Timestamp` headers of each response are fed back through
+/// `observe`, so the local budget never runs ahead of what Bybit reports.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<[Bucket; 4]>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let buckets = [
+            Bucket::new(config.order),
+            Bucket::new(config.position),
+            Bucket::new(config.account),
+            Bucket::new(config.market),
+        ];
+        Self {
+            config,
+            buckets: Mutex::new(buckets),
+        }
+    }
+
+    fn index(group: EndpointGroup) -> usize {
+        match group {
+            EndpointGroup::Order => 0,
+            EndpointGroup::Position => 1,
+            EndpointGroup::Account => 2,
+            EndpointGroup::Market => 3,
+        }
+    }
+
+    /// Takes a request slot without waiting. On failure the error is
+    /// `BybitError::RateLimited` with the time until a slot frees up.
+    pub fn try_acquire(&self, group: EndpointGroup) -> Result<(), BybitError> {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets[Self::index(group)]
+            .take(Instant::now())
+            .map_err(|retry_after| BybitError::RateLimited { retry_after })
+    }
+
+    /// Takes a request slot, waiting for one if the config says to block.
+    pub async fn acquire(&self, group: EndpointGroup) -> Result<(), BybitError> {
+        loop {
+            match self.try_acquire(group) {
+                Err(BybitError::RateLimited { retry_after }) if self.config.block => {
+                    tokio::time::sleep(retry_after).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Adjusts a bucket to the limit state Bybit reported for the last request.
+    ///
+    /// `remaining` is the `X-Bapi-Limit-Status` header. When it reaches zero and
+    /// `reset_timestamp` (`X-Bapi-Limit-Reset-Timestamp`, in milliseconds) is
+    /// known, the group is held until that time.
+    pub fn observe(&self, group: EndpointGroup, remaining: u32, reset_timestamp: Option<u64>) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = &mut buckets[Self::index(group)];
+        let now = Instant::now();
+        bucket.refill(now);
+        bucket.tokens = bucket.tokens.min(f64::from(remaining));
+        if remaining == 0 {
+            if let Some(reset) = reset_timestamp {
+                let wait = reset.saturating_sub(crate::util::get_timestamp());
+                bucket.blocked_until = Some(now + Duration::from_millis(wait));
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::api::{Public, WebsocketAPI};
+use crate::config::ReconnectConfig;
+use crate::errors::BybitError;
+use crate::model::{Category, Subscription, WebsocketEvents};
+use crate::ws::{Stream, StreamHandle, ACK_TIMEOUT};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+
+/// Bybit rejects a public connection whose subscribed `args` exceed 21,000 characters in total.
+pub const MAX_ARGS_LENGTH: usize = 21_000;
+
+type Subscribers = Arc<Mutex<HashMap<String, Vec<mpsc::UnboundedSender<WebsocketEvents>>>>>;
+
+/// The connections of one endpoint are pooled together.
+#[derive(Clone, Copy)]
+enum Pool {
+    Public(Category),
+    Private,
+}
+
+impl Pool {
+    fn key(self) -> &'static str {
+        match self {
+            Pool::Public(Category::Linear) => "linear",
+            Pool::Public(Category::Inverse) => "inverse",
+            Pool::Public(Category::Spot) => "spot",
+            Pool::Public(Category::Option) => "option",
+            Pool::Private => "private",
+        }
+    }
+
+    fn endpoint(self) -> WebsocketAPI {
+        match self {
+            Pool::Public(Category::Linear) => WebsocketAPI::Public(Public::Linear),
+            Pool::Public(Category::Inverse) => WebsocketAPI::Public(Public::Inverse),
+            Pool::Public(Category::Spot) => WebsocketAPI::Public(Public::Spot),
+            Pool::Public(Category::Option) => WebsocketAPI::Public(Public::Option),
+            Pool::Private => WebsocketAPI::Private,
+        }
+    }
+}
+
+struct Connection {
+    handle: StreamHandle,
+    subscribers: Subscribers,
+}
+
+impl Connection {
+    fn args_length(&self) -> usize {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .keys()
+            .map(String::len)
+            .sum()
+    }
+}
+
+/// Shares a pool of websocket connections between any number of topic subscribers.
+///
+/// Public topics are spread over as many connections per category as Bybit's `args` length
+/// limit requires, private topics share a single authenticated connection. Every event is
+/// forwarded to the receivers subscribed to its topic. Dropped connections are re-established
+/// with the manager's `ReconnectConfig` and resubscribe to the topics they held.
+///
+/// A topic is unsubscribed once all of its receivers are dropped, when its next event arrives.
+pub struct StreamManager {
+    stream: Stream,
+    reconnect: ReconnectConfig,
+    ping_interval: Option<u64>,
+    max_args_length: usize,
+    pools: tokio::sync::Mutex<HashMap<&'static str, Vec<Connection>>>,
+}
+
+impl StreamManager {
+    pub fn new(stream: Stream) -> Self {
+        Self {
+            stream,
+            reconnect: ReconnectConfig::default(),
+            ping_interval: None,
+            max_args_length: MAX_ARGS_LENGTH,
+            pools: tokio::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Applies to connections opened after the call.
+    pub fn with_reconnect(self, reconnect: ReconnectConfig) -> Self {
+        Self { reconnect, ..self }
+    }
+
+    pub fn with_ping_interval(self, ping_interval: u64) -> Self {
+        Self {
+            ping_interval: Some(ping_interval),
+            ..self
+        }
+    }
+
+    /// Lowers the total `args` length a public connection may hold before the next topic is
+    /// subscribed on a new connection. Values above `MAX_ARGS_LENGTH` are capped.
+    pub fn with_max_args_length(self, max_args_length: usize) -> Self {
+        Self {
+            max_args_length: max_args_length.min(MAX_ARGS_LENGTH),
+            ..self
+        }
+    }
+
+    /// Subscribes to a public `topic`, e.g. `orderbook.50.BTCUSDT`, of `category`.
+    ///
+    /// Resolves once Bybit acknowledges the topic, or immediately when it is already
+    /// subscribed for another receiver.
+    pub async fn subscribe(
+        &self,
+        category: Category,
+        topic: &str,
+    ) -> Result<mpsc::UnboundedReceiver<WebsocketEvents>, BybitError> {
+        self.subscribe_on(Pool::Public(category), topic).await
+    }
+
+    /// Subscribes to a private `topic`, e.g. `order` or `position.linear`. The stream must have
+    /// been created with API keys.
+    pub async fn subscribe_private(
+        &self,
+        topic: &str,
+    ) -> Result<mpsc::UnboundedReceiver<WebsocketEvents>, BybitError> {
+        self.subscribe_on(Pool::Private, topic).await
+    }
+
+    async fn subscribe_on(
+        &self,
+        pool: Pool,
+        topic: &str,
+    ) -> Result<mpsc::UnboundedReceiver<WebsocketEvents>, BybitError> {
+        if topic.len() > self.max_args_length {
+            return Err(BybitError::InvalidParams(format!(
+                "topic {topic} is longer than the {} characters a connection can hold",
+                self.max_args_length
+            )));
+        }
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut pools = self.pools.lock().await;
+        let connections = pools.entry(pool.key()).or_default();
+        connections.retain(|connection| !connection.handle.is_finished());
+        for connection in connections.iter() {
+            if let Some(senders) = connection.subscribers.lock().unwrap().get_mut(topic) {
+                senders.push(sender);
+                return Ok(receiver);
+            }
+        }
+        let free = connections.iter().position(|connection| {
+            matches!(pool, Pool::Private)
+                || connection.args_length() + topic.len() <= self.max_args_length
+        });
+        let connection = match free {
+            Some(index) => &connections[index],
+            None => {
+                connections.push(self.connect(pool));
+                connections.last().unwrap()
+            }
+        };
+        // Registered before subscribing so that the first snapshot is not missed.
+        connection
+            .subscribers
+            .lock()
+            .unwrap()
+            .insert(topic.to_string(), vec![sender]);
+        if let Err(e) = connection
+            .handle
+            .stream()
+            .ws_subscribe_more(vec![topic])
+            .await
+        {
+            connection.subscribers.lock().unwrap().remove(topic);
+            return Err(e);
+        }
+        Ok(receiver)
+    }
+
+    /// Opens a connection without topics; `subscribe_on` adds them once it is up.
+    fn connect(&self, pool: Pool) -> Connection {
+        let subscribers = Subscribers::default();
+        let routes = subscribers.clone();
+        let ping_interval = self.ping_interval;
+        let reconnect = self.reconnect.clone();
+        let handle = self.stream.spawn(move |stream| {
+            let state = stream.register_subscription(&Subscription::default());
+            async move {
+                let commands = stream.clone();
+                stream
+                    .run_subscription(
+                        pool.endpoint(),
+                        state,
+                        ping_interval,
+                        Some(reconnect),
+                        move |event| {
+                            Self::dispatch(&routes, &commands, event);
+                            Ok(())
+                        },
+                    )
+                    .await
+            }
+        });
+        Connection {
+            handle,
+            subscribers,
+        }
+    }
+
+    fn dispatch(subscribers: &Subscribers, stream: &Stream, event: WebsocketEvents) {
+        let Some(topic) = event.topic() else { return };
+        let mut subscribers = subscribers.lock().unwrap();
+        // Some pushes only name the topic family of a categorised subscription.
+        let key = match subscribers.contains_key(topic) {
+            true => topic.to_string(),
+            false => match subscribers.keys().find(|key| {
+                key.strip_prefix(topic)
+                    .is_some_and(|rest| rest.starts_with('.'))
+            }) {
+                Some(key) => key.clone(),
+                None => return,
+            },
+        };
+        let Some(senders) = subscribers.get_mut(&key) else {
+            return;
+        };
+        senders.retain(|sender| sender.send(event.clone()).is_ok());
+        if senders.is_empty() {
+            subscribers.remove(&key);
+            let stream = stream.clone();
+            tokio::spawn(async move {
+                if let Err(e) = stream.ws_unsubscribe(vec![&key]).await {
+                    warn!("Failed to unsubscribe from {key}: {e}");
+                }
+            });
+        }
+    }
+
+    /// Number of open connections, public and private.
+    pub async fn connection_count(&self) -> usize {
+        let pools = self.pools.lock().await;
+        pools
+            .values()
+            .flatten()
+            .filter(|connection| !connection.handle.is_finished())
+            .count()
+    }
+
+    /// Closes every connection and waits for them to finish. All receivers end afterwards.
+    pub async fn shutdown(self) -> Result<(), BybitError> {
+        let mut result = Ok(());
+        for connection in self.pools.into_inner().into_values().flatten() {
+            let _ = tokio::time::timeout(
+                Duration::from_secs(ACK_TIMEOUT),
+                connection.handle.stream().close(),
+            )
+            .await;
+            if let Err(e) = connection.handle.stop().await {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
@@ -1,16 +1,67 @@
 use serde_json::{json, Value};
 
-use crate::api::{Trade, API};
-use crate::client::Client;
-use crate::errors::BybitError;
+use crate::api::{SpotLeverage, Trade, API};
+use crate::client::{paginate_all, Client, MAX_PAGES};
+use crate::errors::{BybitContentError, BybitError};
 use crate::model::{
-    AmendOrderRequest, AmendOrderResponse, BatchAmendRequest, BatchAmendResponse, BatchCancelRequest, BatchCancelResponse, BatchPlaceRequest, BatchPlaceResponse, CancelOrderRequest, CancelOrderResponse, CancelallRequest, CancelallResponse, Category, OpenOrdersRequest, OpenOrdersResponse, OrderHistoryRequest, OrderHistoryResponse, OrderRequest, OrderResponse, OrderType, RequestType, Side, TradeHistoryRequest, TradeHistoryResponse
+    AmendOrderRequest, AmendOrderResponse, BatchAmendRequest, BatchAmendResponse,
+    BatchCancelRequest, BatchCancelResponse, BatchPlaceRequest, BatchPlaceResponse,
+    CancelOrderRequest, CancelOrderResponse, CancelRequest, CancelallRequest, CancelallResponse,
+    Category, DcpProduct, DcpResponse, InstrumentInfo, LeverageTokenPurchase,
+    LeverageTokenPurchaseResponse, LeverageTokenRedemption, LeverageTokenRedemptionResponse,
+    OpenOrdersRequest, OpenOrdersResponse, OrderHistoryRequest, OrderHistoryResponse, OrderRequest,
+    OrderResponse, OrderType, Orders, PositionIdx, RequestType, Side, TradeHistory,
+    TradeHistoryRequest, TradeHistoryResponse,
+};
+use crate::util::{
+    build_json_request, build_request, date_to_milliseconds, generate_order_link_id,
 };
-use crate::util::{build_json_request, build_request, date_to_milliseconds, generate_random_uid};
 
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
+/// Most orders Bybit accepts in one batch create/amend/cancel request, in any category.
+pub const MAX_BATCH_ORDERS: usize = 10;
+
+/// `retCode` for an order whose `orderLinkId` is already in use.
+pub const DUPLICATE_ORDER_LINK_ID: i32 = 110072;
+
+/// Whether `error` rejects an order for reusing an `orderLinkId`. Some order paths report
+/// this as a generic parameter error (10001) with a message naming the duplicate id.
+pub fn is_duplicate_order_link_id(error: &BybitContentError) -> bool {
+    error.code == DUPLICATE_ORDER_LINK_ID
+        || (error.code == 10001 && error.msg.to_lowercase().contains("duplicate"))
+}
+
+/// The outcome of `Trader::place_custom_order`.
+#[derive(Debug, Clone)]
+pub enum PlacedOrder {
+    /// Bybit accepted the order.
+    New(OrderResponse),
+    /// An order with `order_link_id` already existed, so this request was deduplicated.
+    /// `order` is that order, or `None` if it is no longer among the open and recent orders.
+    Duplicate {
+        order_link_id: String,
+        order: Option<Box<Orders>>,
+    },
+}
+
+impl PlacedOrder {
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, PlacedOrder::Duplicate { .. })
+    }
+
+    pub fn order_link_id(&self) -> &str {
+        match self {
+            PlacedOrder::New(response) => &response.result.order_link_id,
+            PlacedOrder::Duplicate { order_link_id, .. } => order_link_id,
+        }
+    }
+}
+
+/// Disconnect cancel all windows Bybit accepts, in seconds.
+pub const DCP_TIME_WINDOW: std::ops::RangeInclusive<u32> = 3..=300;
+
 #[derive(Clone)]
 pub struct Trader {
     pub client: Client,
@@ -68,23 +119,72 @@ pub enum Action<'a> {
 
 
 impl Trader {
+    /// Places an order, safely retried under the client's `RetryConfig`.
+    ///
+    /// An `orderLinkId` is generated when the request has none, and every attempt reuses it.
+    /// If Bybit reports that the id already exists, usually because an earlier attempt reached
+    /// it before the connection failed, the existing order is looked up and returned as
+    /// `PlacedOrder::Duplicate` instead of an error.
     pub async fn place_custom_order<'a>(
         &self,
-        req: OrderRequest<'a>,
-    ) -> Result<OrderResponse, BybitError> {
-        let action = Action::Order(req, false);
-        let parameters = Self::build_orders(action);
-
+        mut req: OrderRequest<'a>,
+    ) -> Result<PlacedOrder, BybitError> {
+        let order_link_id = req
+            .order_link_id
+            .get_or_insert_with(|| generate_order_link_id("").into())
+            .to_string();
+        let (category, symbol) = (req.category, req.symbol.to_string());
+        let parameters = Self::build_orders(Action::Order(req, false));
         let request = build_json_request(&parameters);
-        let response: OrderResponse = self
+        let placed = self
             .client
-            .post_signed(
+            .post_signed_idempotent(
                 API::Trade(Trade::Place),
                 self.recv_window.into(),
                 Some(request),
             )
-            .await?;
-        Ok(response)
+            .await;
+        match placed {
+            Ok(response) => Ok(PlacedOrder::New(response)),
+            Err(BybitError::BybitError(error)) if is_duplicate_order_link_id(&error) => {
+                let lookup = OpenOrdersRequest {
+                    category,
+                    symbol: Some(Cow::Owned(symbol)),
+                    order_link_id: Some(Cow::Borrowed(&order_link_id)),
+                    ..OpenOrdersRequest::default()
+                };
+                let order = self
+                    .get_open_orders(lookup)
+                    .await?
+                    .result
+                    .list
+                    .pop()
+                    .map(Box::new);
+                Ok(PlacedOrder::Duplicate {
+                    order_link_id,
+                    order,
+                })
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Same as `place_custom_order`, after checking `req` against the filters of `instrument`
+    /// with `InstrumentInfo::validate_order`. A violated filter fails with `InvalidParams`
+    /// without sending anything.
+    pub async fn place_validated_order<'a>(
+        &self,
+        req: OrderRequest<'a>,
+        instrument: &InstrumentInfo,
+    ) -> Result<PlacedOrder, BybitError> {
+        if req.symbol != instrument.symbol {
+            return Err(BybitError::InvalidParams(format!(
+                "order for {} validated against instrument {}",
+                req.symbol, instrument.symbol
+            )));
+        }
+        instrument.validate_order(req.price, req.qty)?;
+        self.place_custom_order(req).await
     }
 
     pub async fn place_futures_limit_order(
@@ -94,7 +194,7 @@ impl Trader {
         side: Side,
         qty: f64,
         price: f64,
-        mode: u8,
+        mode: PositionIdx,
     ) -> Result<OrderResponse, BybitError> {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         let req = OrderRequest {
@@ -104,7 +204,7 @@ impl Trader {
             qty,
             order_type: OrderType::Limit,
             position_idx: Some(mode),
-            order_link_id: Some(generate_random_uid(36).into()),
+            order_link_id: Some(generate_order_link_id("").into()),
             price: Some(price),
             ..Default::default()
         };
@@ -117,12 +217,7 @@ impl Trader {
         }
         parameters.insert("qty".into(), req.qty.to_string());
         if let Some(v) = req.position_idx {
-            match v {
-                0 | 1 | 2 => {
-                    parameters.insert("positionIdx".into(), v.to_string());
-                }
-                _ => return Err(BybitError::from("Invalid position index".to_string())),
-            }
+            parameters.insert("positionIdx".into(), v.as_u8().to_string());
         }
         if let Some(v) = req.price {
             parameters.insert("price".into(), v.to_string());
@@ -131,7 +226,7 @@ impl Trader {
         let request = build_json_request(&parameters);
         let response: OrderResponse = self
             .client
-            .post_signed(
+            .post_signed_idempotent(
                 API::Trade(Trade::Place),
                 self.recv_window.into(),
                 Some(request),
@@ -144,6 +239,11 @@ impl Trader {
         &self,
         req: AmendOrderRequest<'a>,
     ) -> Result<AmendOrderResponse, BybitError> {
+        if req.order_id.is_none() && req.order_link_id.is_none() {
+            return Err(BybitError::InvalidParams(
+                "Either order_id or order_link_id is required".to_string(),
+            ));
+        }
         let action = Action::Amend(req, false);
         let parameters = Self::build_orders(action);
         let request = build_json_request(&parameters);
@@ -161,6 +261,11 @@ impl Trader {
         &self,
         req: CancelOrderRequest<'a>,
     ) -> Result<CancelOrderResponse, BybitError> {
+        if req.order_id.is_none() && req.order_link_id.is_none() {
+            return Err(BybitError::InvalidParams(
+                "Either order_id or order_link_id is required".to_string(),
+            ));
+        }
         let action = Action::Cancel(req, false);
         let parameters = Self::build_orders(action);
         let request = build_json_request(&parameters);
@@ -174,14 +279,30 @@ impl Trader {
             .await?;
         Ok(response)
     }
+    /// Retrieves one page of live orders.
+    ///
+    /// Derivatives need at least one of `symbol`, `base_coin` or `settle_coin`; a request
+    /// without any of them is rejected with `BybitError::InvalidParams` before sending.
+    /// Set `cursor` to continue from a previous page, or use `get_all_open_orders`.
     pub async fn get_open_orders<'a>(
         &self,
         req: OpenOrdersRequest<'a>,
     ) -> Result<OpenOrdersResponse, BybitError> {
+        if matches!(req.category, Category::Linear | Category::Inverse)
+            && req.symbol.is_none()
+            && req.base_coin.is_none()
+            && req.settle_coin.is_none()
+        {
+            return Err(BybitError::InvalidParams(
+                "open orders for derivatives need a symbol, base coin or settle coin".into(),
+            ));
+        }
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
 
         parameters.insert("category".into(), req.category.as_str().into());
-        parameters.insert("symbol".into(), req.symbol.into());
+        if let Some(symbol) = req.symbol {
+            parameters.insert("symbol".into(), symbol.into());
+        }
 
         if let Some(base_coin) = req.base_coin {
             parameters.insert("baseCoin".into(), base_coin.into());
@@ -206,22 +327,48 @@ impl Trader {
         if let Some(limit) = req.limit {
             parameters.insert("limit".into(), limit.to_string().into());
         }
+        if let Some(cursor) = req.cursor {
+            parameters.insert("cursor".into(), cursor.into());
+        }
 
         let request = build_request(&parameters);
         let response: OpenOrdersResponse = self
             .client
-            .get_signed(API::Trade(Trade::OpenOrders), 5000, Some(request))
+            .get_signed(
+                API::Trade(Trade::OpenOrders),
+                self.recv_window.into(),
+                Some(request),
+            )
             .await?;
 
         Ok(response)
     }
+
+    /// Retrieves every live order matching `req`, following `nextPageCursor` until the
+    /// last page. Useful on startup to learn which orders are already working.
+    pub async fn get_all_open_orders<'a>(
+        &self,
+        req: OpenOrdersRequest<'a>,
+    ) -> Result<Vec<Orders>, BybitError> {
+        paginate_all(MAX_PAGES, |cursor| {
+            let page = OpenOrdersRequest {
+                cursor: cursor.map(Cow::Owned),
+                ..req.clone()
+            };
+            async move { Ok(self.get_open_orders(page).await?.result.into_page()) }
+        })
+        .await
+    }
     pub async fn cancel_all_orders<'a>(
         &self,
         req: CancelallRequest<'a>,
     ) -> Result<CancelallResponse, BybitError> {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         parameters.insert("category".into(), req.category.as_str().into());
-        parameters.insert("symbol".into(), req.symbol.into());
+        // An empty symbol cancels across the whole `base_coin`/`settle_coin` instead.
+        if !req.symbol.is_empty() {
+            parameters.insert("symbol".into(), req.symbol.into());
+        }
         if let Some(base_coin) = req.base_coin {
             parameters.insert("baseCoin".into(), base_coin.into());
         }
@@ -250,9 +397,11 @@ impl Trader {
     ///
     /// # Arguments
     /// * `req` - An instance of `OrderHistoryRequest` containing the request parameters.
+    ///   Set `cursor` to the previous page's `nextPageCursor` to continue a listing.
     ///
     /// # Returns
     /// A `Result` wrapping `OrderHistory` which contains the historical orders' data.
+    /// `OrderHistory::into_page` splits it into the orders and the next cursor.
     /// If the operation fails, it returns an error.
     ///
     pub async fn get_order_history<'a>(
@@ -273,8 +422,9 @@ impl Trader {
             .map(|order_link_id| parameters.insert("orderLinkId".into(), order_link_id.into()));
         req.order_filter
             .map(|order_filter| parameters.insert("orderFilter".into(), order_filter.into()));
-        req.order_status
-            .map(|order_status| parameters.insert("orderStatus".into(), order_status.into()));
+        req.order_status.map(|order_status| {
+            parameters.insert("orderStatus".into(), order_status.as_str().into())
+        });
         req.start_time
             .and_then(|start_time| Some(date_to_milliseconds(start_time.as_ref())))
             .map(|start_millis| parameters.insert("startTime".into(), start_millis.to_string()));
@@ -283,6 +433,8 @@ impl Trader {
             .map(|end_millis| parameters.insert("endTime".into(), end_millis.to_string()));
         req.limit
             .map(|limit| parameters.insert("limit".into(), limit.to_string()));
+        req.cursor
+            .map(|cursor| parameters.insert("cursor".into(), cursor.into()));
 
         let request = build_request(&parameters);
         let response: OrderHistoryResponse = self
@@ -295,6 +447,11 @@ impl Trader {
             .await?;
         Ok(response)
     }
+    /// Retrieves one page of executions (`/v5/execution/list`), newest first.
+    ///
+    /// Bybit serves at most seven days between `start_time` and `end_time`, and the last
+    /// 24 hours when neither is set. Set `cursor` to the previous page's
+    /// `nextPageCursor` to continue, or use `get_all_trade_history`.
     pub async fn get_trade_history<'a>(
         &self,
         req: TradeHistoryRequest<'a>,
@@ -319,6 +476,8 @@ impl Trader {
             .map(|limit| parameters.insert("limit".into(), limit.to_string()));
         req.exec_type
             .map(|exec_type| parameters.insert("execType".into(), exec_type.into()));
+        req.cursor
+            .map(|cursor| parameters.insert("cursor".into(), cursor.into()));
         let request = build_request(&parameters);
         let response: TradeHistoryResponse = self
             .client
@@ -330,19 +489,29 @@ impl Trader {
             .await?;
         Ok(response)
     }
+
+    /// Retrieves every execution in the window of `req`, following `nextPageCursor`
+    /// until the last page.
+    pub async fn get_all_trade_history<'a>(
+        &self,
+        req: TradeHistoryRequest<'a>,
+    ) -> Result<Vec<TradeHistory>, BybitError> {
+        paginate_all(MAX_PAGES, |cursor| {
+            let page = TradeHistoryRequest {
+                cursor: cursor.map(Cow::Owned),
+                ..req.clone()
+            };
+            async move { Ok(self.get_trade_history(page).await?.result.into_page()) }
+        })
+        .await
+    }
     pub async fn batch_place_order<'a>(
         &self,
         req: BatchPlaceRequest<'a>,
     ) -> Result<BatchPlaceResponse, BybitError> {
+        Self::check_batch_size(req.requests.len())?;
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        match req.category {
-            Category::Linear | Category::Inverse | Category::Option => {
-                parameters.insert("category".into(), req.category.as_str().into());
-            }
-            _ => {
-                println!("Invalid category");
-            }
-        }
+        parameters.insert("category".into(), req.category.as_str().into());
         let mut requests_array: Vec<Value> = Vec::new();
         for value in req.requests {
             let action = Action::Order(value, true);
@@ -367,15 +536,9 @@ impl Trader {
         &self,
         req: BatchAmendRequest<'a>,
     ) -> Result<BatchAmendResponse, BybitError> {
+        Self::check_batch_size(req.requests.len())?;
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        match req.category {
-            Category::Linear | Category::Inverse | Category::Option => {
-                parameters.insert("category".into(), req.category.as_str().into());
-            }
-            _ => {
-                println!("Invalid category");
-            }
-        }
+        parameters.insert("category".into(), req.category.as_str().into());
         let mut requests_array: Vec<Value> = Vec::new();
         for value in req.requests {
             let action = Action::Amend(value, true);
@@ -396,19 +559,35 @@ impl Trader {
         Ok(response)
     }
 
+    /// Cancels up to `MAX_BATCH_ORDERS` orders of one category in a single request.
+    ///
+    /// Bybit answers per order: see `BatchCancelResponse::results` for which cancels failed.
+    pub async fn cancel_batch<'a>(
+        &self,
+        category: Category,
+        cancels: Vec<CancelRequest<'a>>,
+    ) -> Result<BatchCancelResponse, BybitError> {
+        self.batch_cancel_order(BatchCancelRequest::from_cancels(category, cancels))
+            .await
+    }
+
     pub async fn batch_cancel_order<'a>(
         &self,
         req: BatchCancelRequest<'a>,
     ) -> Result<BatchCancelResponse, BybitError> {
-        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        match req.category {
-            Category::Linear | Category::Inverse | Category::Option => {
-                parameters.insert("category".into(), req.category.as_str().into());
-            }
-            _ => {
-                println!("Invalid category");
-            }
+        Self::check_batch_size(req.requests.len())?;
+        if let Some(i) = req
+            .requests
+            .iter()
+            .position(|r| r.order_id.is_none() && r.order_link_id.is_none())
+        {
+            return Err(BybitError::InvalidParams(format!(
+                "Cancel {} of the batch needs either order_id or order_link_id",
+                i
+            )));
         }
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("category".into(), req.category.as_str().into());
         let mut requests_array: Vec<Value> = Vec::new();
         for value in req.requests {
             let action = Action::Cancel(value, true);
@@ -428,13 +607,86 @@ impl Trader {
             .await?;
         Ok(response)
     }
-    pub async fn get_borrow_quota_spot(&self) {
-        // TODO: Implement this function
-        todo!("This function has not yet been implemented");
+    /// Sets the disconnect cancel all (DCP) window for `product`, in seconds.
+    ///
+    /// Once the private websocket subscribed to `Topic::dcp(product)` has been gone for
+    /// `time_window` seconds, Bybit cancels all open orders of that product. Bybit accepts
+    /// windows of 3 to 300 seconds; the setting applies to the whole account.
+    pub async fn set_dcp_options(
+        &self,
+        product: DcpProduct,
+        time_window: u32,
+    ) -> Result<DcpResponse, BybitError> {
+        if !DCP_TIME_WINDOW.contains(&time_window) {
+            return Err(BybitError::InvalidParams(format!(
+                "DCP time window of {}s is outside {:?}",
+                time_window, DCP_TIME_WINDOW
+            )));
+        }
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("product".into(), product.as_str().into());
+        parameters.insert("timeWindow".into(), time_window.into());
+        let request = build_json_request(&parameters);
+        self.client
+            .post_signed(
+                API::Trade(Trade::SetDisconnectCancelall),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await
+    }
+
+    /// Buys leveraged tokens `lt_coin` (e.g. `BTC3L`) for `amount` of its quote coin. The
+    /// returned status is usually `InProgress`; the tokens arrive once it completes.
+    pub async fn purchase_leverage_token(
+        &self,
+        lt_coin: &str,
+        amount: f64,
+    ) -> Result<LeverageTokenPurchase, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("ltCoin".into(), lt_coin.into());
+        parameters.insert("amount".into(), amount.to_string().into());
+        let request = build_json_request(&parameters);
+        let response: LeverageTokenPurchaseResponse = self
+            .client
+            .post_signed(
+                API::SpotLeverage(SpotLeverage::Purchase),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.result)
     }
-    pub async fn set_dcp_options(&self) {
-        // TODO: Implement this function
-        todo!("This function has not yet been implemented");
+
+    /// Redeems `quantity` leveraged tokens `lt_coin` for their net value.
+    pub async fn redeem_leverage_token(
+        &self,
+        lt_coin: &str,
+        quantity: f64,
+    ) -> Result<LeverageTokenRedemption, BybitError> {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        parameters.insert("ltCoin".into(), lt_coin.into());
+        parameters.insert("quantity".into(), quantity.to_string().into());
+        let request = build_json_request(&parameters);
+        let response: LeverageTokenRedemptionResponse = self
+            .client
+            .post_signed(
+                API::SpotLeverage(SpotLeverage::Redeem),
+                self.recv_window.into(),
+                Some(request),
+            )
+            .await?;
+        Ok(response.result)
+    }
+
+    fn check_batch_size(len: usize) -> Result<(), BybitError> {
+        if len > MAX_BATCH_ORDERS {
+            return Err(BybitError::InvalidParams(format!(
+                "Batch of {} orders exceeds the limit of {}",
+                len, MAX_BATCH_ORDERS
+            )));
+        }
+        Ok(())
     }
 
     pub fn build_orders<'a>(action: Action<'a>) -> BTreeMap<String, Value> {
@@ -481,21 +733,16 @@ impl Trader {
                     parameters.insert("orderIv".into(), iv.to_string().into());
                 }
                 if let Some(time_in_force) = req.time_in_force {
-                    parameters.insert("timeInForce".into(), time_in_force.into());
+                    parameters.insert("timeInForce".into(), time_in_force.as_str().into());
                 }
                 if let Some(v) = req.position_idx {
-                    match v {
-                        0 | 1 | 2 => {
-                            parameters.insert("positionIdx".into(), v.to_string().into());
-                        }
-                        _ => println!("Invalid position idx"),
-                    }
+                    parameters.insert("positionIdx".into(), v.into());
                 }
                 if let Some(order_link_id) = req.order_link_id {
                     parameters.insert("orderLinkId".into(), order_link_id.into());
                 } else {
-                    let uuid = generate_random_uid(36);
-                    parameters.insert("orderLinkId".into(), uuid.into());
+                    let order_link_id = generate_order_link_id("");
+                    parameters.insert("orderLinkId".into(), order_link_id.into());
                 }
                 if let Some(price) = req.take_profit {
                     parameters.insert("takeProfit".into(), price.to_string().into());
@@ -528,10 +775,10 @@ impl Trader {
                     parameters.insert("slTriggerPrice".into(), v.to_string().into());
                 }
                 if let Some(v) = req.tp_order_type {
-                    parameters.insert("tpOrderType".into(), v.into());
+                    parameters.insert("tpOrderType".into(), v.as_str().into());
                 }
                 if let Some(v) = req.sl_order_type {
-                    parameters.insert("slOrderType".into(), v.into());
+                    parameters.insert("slOrderType".into(), v.as_str().into());
                 }
             }
             Action::Amend(req, batch) => {
@@ -551,7 +798,9 @@ impl Trader {
                 if let Some(v) = req.trigger_price {
                     parameters.insert("triggerPrice".into(), v.to_string().into());
                 }
-                parameters.insert("qty".into(), req.qty.into());
+                if let Some(v) = req.qty {
+                    parameters.insert("qty".into(), v.to_string().into());
+                }
                 if let Some(v) = req.price {
                     parameters.insert("price".into(), v.to_string().into());
                 }
@@ -5,6 +5,7 @@ use serde::Serialize;
 
 use serde_json::Value;
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn build_request<T: ToString>(parameters: &BTreeMap<String, T>) -> String {
@@ -15,7 +16,7 @@ pub fn build_request<T: ToString>(parameters: &BTreeMap<String, T>) -> String {
         request.push_str(&value.to_string());
         request.push('&');
     }
-    request.truncate(request.len() - 1);
+    request.pop();
     request
 }
 
@@ -44,10 +45,250 @@ pub fn date_to_milliseconds(date_str: &str) -> u64 {
     datetime_utc.timestamp_millis() as u64
 }
 
+/// Longest `orderLinkId` Bybit accepts.
+pub const MAX_ORDER_LINK_ID_LEN: usize = 36;
+
+static UID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Random string of `length` characters from `[A-Za-z0-9]`.
+///
+/// Characters are drawn from `thread_rng`, a cryptographically secure generator
+/// seeded from the OS, so ids are not predictable. Short ids can still collide by
+/// chance; use [`generate_unique_uid`] where that matters.
 pub fn generate_random_uid(length: usize) -> String {
-    let mut uid = String::with_capacity(length);
-    for _ in 0..length {
-        uid.push(thread_rng().sample(Alphanumeric) as char);
-    }
+    thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// Random id with a process-wide counter appended, so no two calls in the same
+/// process return the same value. The counter is written in base 36, making the
+/// result `length` plus a few characters long.
+pub fn generate_unique_uid(length: usize) -> String {
+    let mut uid = generate_random_uid(length);
+    uid.push_str(&to_base36(UID_COUNTER.fetch_add(1, Ordering::Relaxed)));
     uid
 }
+
+/// `orderLinkId` that is valid for Bybit and unique within the process.
+///
+/// The id is the alphanumeric characters of `prefix`, then a counter, then random
+/// characters up to [`MAX_ORDER_LINK_ID_LEN`]. The prefix is cut to 16
+/// characters so at least 20 characters stay counter and randomness.
+pub fn generate_order_link_id(prefix: &str) -> String {
+    let mut id: String = prefix
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(16)
+        .collect();
+    id.push_str(&to_base36(UID_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let fill = MAX_ORDER_LINK_ID_LEN - id.len();
+    id.push_str(&generate_random_uid(fill));
+    id
+}
+
+fn to_base36(mut value: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(value % 36) as usize]);
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().map(|&d| d as char).collect()
+}
+
+/// Random (version 4) UUID, as Bybit requires for `transferId`.
+pub fn generate_uuid() -> String {
+    let mut bytes: [u8; 16] = thread_rng().gen();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Direction in which `round_to_tick` moves a value that is not on the tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    Floor,
+    Ceil,
+    Nearest,
+}
+
+/// Rounds `value` to a multiple of `tick`, e.g. a price to the instrument's `tickSize`. A
+/// `tick` of zero or less returns `value` unchanged.
+///
+/// Values that are a multiple of `tick` up to float noise, such as `0.1 + 0.2` for a tick of
+/// `0.1`, stay on that multiple in every mode, and the result is trimmed to the tick's decimals
+/// so that it prints as Bybit expects.
+pub fn round_to_tick(value: f64, tick: f64, mode: RoundingMode) -> f64 {
+    if tick <= 0.0 {
+        return value;
+    }
+    let ticks = value / tick;
+    let nearest = ticks.round();
+    let ticks = match mode {
+        _ if (ticks - nearest).abs() < 1e-9 => nearest,
+        RoundingMode::Floor => ticks.floor(),
+        RoundingMode::Ceil => ticks.ceil(),
+        RoundingMode::Nearest => nearest,
+    };
+    let decimals = tick.to_string().split('.').nth(1).map_or(0, |d| d.len()) as i32;
+    let factor = 10f64.powi(decimals);
+    (ticks * tick * factor).round() / factor
+}
+
+/// Rounds a quantity down to a multiple of `step`, so that an order never exceeds the amount
+/// asked for.
+pub fn round_to_step(qty: f64, step: f64) -> f64 {
+    round_to_tick(qty, step, RoundingMode::Floor)
+}
+
+/// Serde helpers for the numbers and timestamps Bybit sends as JSON strings.
+///
+/// Use them with `#[serde(with = "...")]`. The `_optional` variants map an empty
+/// string, which Bybit uses for absent values such as an unset `triggerPrice`, to
+/// `None`; pair them with `#[serde(default)]` so a missing field is `None` too.
+pub mod string_to_u64 {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize a u64 as a string.
+    pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = value.to_string();
+        serializer.serialize_str(&s)
+    }
+
+    // Deserialize a string to a u64.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<u64>().map_err(serde::de::Error::custom)
+    }
+}
+
+pub mod string_to_float {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize a f64 as a string.
+    pub fn serialize<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = value.to_string();
+        serializer.serialize_str(&s)
+    }
+
+    // Deserialize a string as an f64.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<f64>().map_err(serde::de::Error::custom)
+    }
+}
+
+pub mod string_to_u64_optional {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize None as an empty string.
+    pub fn serialize<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(v) => serializer.serialize_str(&v.to_string()),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    // Deserialize an empty string or null as None.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) if !s.is_empty() => {
+                s.parse::<u64>().map(Some).map_err(serde::de::Error::custom)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+pub mod string_to_float_optional {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize None as an empty string.
+    pub fn serialize<S>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(v) => serializer.serialize_str(&v.to_string()),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    // Deserialize an empty string or null as None.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) if !s.is_empty() => {
+                s.parse::<f64>().map(Some).map_err(serde::de::Error::custom)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+pub mod millis_to_datetime {
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    // Serialize a datetime as a millisecond timestamp string.
+    pub fn serialize<S>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.timestamp_millis().to_string())
+    }
+
+    // Deserialize a millisecond timestamp, sent either as a string or a number.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Millis {
+            Str(String),
+            Num(i64),
+        }
+        let millis = match Millis::deserialize(deserializer)? {
+            Millis::Str(s) => s.parse::<i64>().map_err(serde::de::Error::custom)?,
+            Millis::Num(n) => n,
+        };
+        Utc.timestamp_millis_opt(millis)
+            .single()
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp: {millis}")))
+    }
+}
@@ -1,34 +1,173 @@
 use crate::api::{Public, WebsocketAPI};
-use crate::client::Client;
-use crate::errors::BybitError;
+use crate::client::{Client, WebSocketConfig};
+use crate::config::ReconnectConfig;
+use crate::errors::{BybitContentError, BybitError};
 use crate::model::{
-    Category, ExecutionData, LiquidationData, OrderBookUpdate, OrderData, PongResponse,
-    PositionData, RequestType, Subscription, Tickers, WalletData, WebsocketEvents, WsKline,
-    WsTrade, FastExecData,
+    BatchPlaceRequest, Category, DcpProduct, Execution, ExecutionData, FastExecData, Greeks,
+    InsuranceData, Interval, LiquidationData, OrderBookUpdate, OrderData, OrderEvent, OrderRequest,
+    PongData, PongResponse, PortfolioGreeks, PositionData, PositionEvent, RequestType,
+    Subscription, SubscriptionOp, Tickers, Topic, TradeStreamEvent, TradeUpdate, WalletData,
+    WalletEvent, WebsocketEvents, WsKline, WsLtKline, WsTicker, WsTrade,
 };
-use crate::trade::build_ws_orders;
-use crate::util::{build_json_request, generate_random_uid, get_timestamp};
+use crate::trade::{build_ws_orders, Trader};
+use crate::util::{build_json_request, generate_unique_uid, get_timestamp};
 use futures::{SinkExt, StreamExt};
 use serde_json::{json, Value};
-use std::collections::BTreeMap;
-use std::time::Instant;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tokio::net::TcpStream;
-use tokio::sync::mpsc;
-use tokio::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
 use tokio_tungstenite::WebSocketStream;
 use tokio_tungstenite::{tungstenite::Message as WsMessage, MaybeTlsStream};
 
+/// Seconds between heartbeat pings; Bybit recommends pinging every 20 seconds.
+pub const DEFAULT_PING_INTERVAL: u64 = 20;
+
 #[derive(Clone)]
 pub struct Stream {
     pub client: Client,
+    /// The most recently started subscription on this stream (or any of its clones).
+    pub(crate) active: Arc<Mutex<ActiveSubscription>>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ActiveSubscription {
+    commands: Option<mpsc::UnboundedSender<WsCommand>>,
+    conn_id: Option<String>,
+}
+
+/// A subscription running on its own task, returned by `Stream::spawn` and
+/// `Stream::ws_subscribe_spawn`.
+///
+/// The handle owns the subscription's command channel, so `stop` closes exactly this
+/// subscription even when several run on clones of the same `Stream`. Dropping the handle
+/// leaves the task running.
+pub struct StreamHandle {
+    stream: Stream,
+    task: JoinHandle<Result<(), BybitError>>,
+}
+
+impl StreamHandle {
+    /// The stream the subscription runs on, for `ws_unsubscribe`, `ws_send_raw` or `conn_id`.
+    pub fn stream(&self) -> &Stream {
+        &self.stream
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Closes the connection gracefully and waits for the task to finish. A subscription that
+    /// has not been acknowledged yet, or whose close is not handled within `ACK_TIMEOUT`
+    /// seconds (e.g. while it waits to reconnect), is aborted instead.
+    pub async fn stop(self) -> Result<(), BybitError> {
+        if !self.task.is_finished() {
+            let closed = match self.stream.conn_id() {
+                Some(_) => {
+                    tokio::time::timeout(Duration::from_secs(ACK_TIMEOUT), self.stream.close())
+                        .await
+                        .is_ok_and(|result| result.is_ok())
+                }
+                None => false,
+            };
+            if !closed {
+                self.task.abort();
+            }
+        }
+        self.join().await
+    }
+
+    /// Waits for the subscription to end and returns its result. A subscription that was
+    /// aborted by `stop` ends with `Ok(())`.
+    pub async fn join(self) -> Result<(), BybitError> {
+        match self.task.await {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => Ok(()),
+            Err(e) => Err(BybitError::Join(e)),
+        }
+    }
+}
+
+/// Most topics Bybit accepts in the `args` of a single subscribe request.
+pub const MAX_ARGS_PER_REQUEST: usize = 10;
+
+/// Bybit's reply to `Stream::ws_ping`.
+#[derive(Clone, Debug)]
+pub struct Pong {
+    pub data: PongData,
+    /// Time between sending the ping and receiving its pong.
+    pub latency: Duration,
+}
+
+/// Seconds to wait for Bybit to acknowledge a subscribe request.
+pub(crate) const ACK_TIMEOUT: u64 = 10;
+
+/// Reply channel for a `WsCommand`, resolved when Bybit acknowledges the frame.
+pub type CommandAck = oneshot::Sender<Result<(), BybitError>>;
+
+/// Requests delivered to a running `event_loop` and written on its socket.
+#[derive(Debug)]
+pub enum WsCommand {
+    /// Subscribes to at most `MAX_ARGS_PER_REQUEST` more topics, which are replayed on reconnect
+    /// once Bybit acknowledges them.
+    Subscribe {
+        args: Vec<String>,
+        ack: CommandAck,
+    },
+    Unsubscribe {
+        args: Vec<String>,
+        ack: CommandAck,
+    },
+    /// Sends a heartbeat ping; `ack` resolves when Bybit's pong arrives.
+    Ping {
+        ack: CommandAck,
+    },
+    /// Performs the websocket close handshake and ends the event loop with `Ok(())`.
+    Close {
+        ack: CommandAck,
+    },
+    /// Writes `frame` as is; `ack` resolves once it is sent. See `Stream::ws_send_raw`.
+    Raw {
+        frame: String,
+        ack: CommandAck,
+    },
+}
+
+/// A request written by `event_loop` that is waiting for the reply carrying its `req_id`.
+enum AwaitingReply {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    Ping,
+}
+
+/// Owns the task behind `ws_subscribe_stream` and aborts it when the stream is dropped, which
+/// drops the connection.
+struct AbortOnDrop(JoinHandle<Result<(), BybitError>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// The topics a subscription currently holds and the receiving end of its command channel.
+/// `event_loop` keeps `topics` up to date so that a reconnect only replays what is still subscribed.
+#[derive(Debug)]
+pub struct SubscriptionState {
+    pub topics: Vec<String>,
+    pub commands: mpsc::UnboundedReceiver<WsCommand>,
 }
 
 impl Stream {
-    pub async fn ws_ping(&self, private: bool) -> Result<(), BybitError> {
-        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("req_id".into(), generate_random_uid(8).into());
-        parameters.insert("op".into(), "ping".into());
-        let request = build_json_request(&parameters);
+    /// Opens a new connection, sends one ping and returns Bybit's pong with the round-trip
+    /// latency. This only checks that the endpoint is reachable; use `keepalive` to ping the
+    /// connection of a running subscription.
+    ///
+    /// The latency is measured from the ping to its pong, after the connection (and, when
+    /// `private`, its authentication) is established.
+    pub async fn ws_ping(&self, private: bool) -> Result<Pong, BybitError> {
         let endpoint = if private {
             WebsocketAPI::Private
         } else {
@@ -36,51 +175,70 @@ impl Stream {
         };
         let mut response = self
             .client
-            .wss_connect(endpoint, Some(request), private, None)
+            .wss_connect(endpoint, None, private, None)
             .await?;
-        let data = response.next().await.unwrap()?;
-        match data {
-            WsMessage::Text(data) => {
-                let response: PongResponse = serde_json::from_str(&data)?;
-                match response {
-                    PongResponse::PublicPong(pong) => {
-                        println!("Pong received successfully: {:#?}", pong);
-                    }
-                    PongResponse::PrivatePong(pong) => {
-                        println!("Pong received successfully: {:#?}", pong);
-                    }
+        if private {
+            Self::await_auth_ack(&mut response, &mut |_| Ok(())).await?;
+        }
+        let req_id = generate_unique_uid(8);
+        let sent = Instant::now();
+        response
+            .send(WsMessage::Text(Self::build_ping_frame(Some(&req_id))))
+            .await?;
+        let read_pong = async {
+            while let Some(data) = response.next().await {
+                let WsMessage::Text(data) = data? else {
+                    continue;
+                };
+                let Ok(PongResponse::PublicPong(pong) | PongResponse::PrivatePong(pong)) =
+                    serde_json::from_str(&data)
+                else {
+                    continue;
+                };
+                if pong.req_id.as_deref() == Some(req_id.as_str()) {
+                    debug!("pong: {data}");
+                    return Ok(Pong {
+                        latency: sent.elapsed(),
+                        data: pong,
+                    });
                 }
             }
-            _ => {}
-        }
-        Ok(())
+            Err(BybitError::ConnectionClosed(String::new()))
+        };
+        tokio::time::timeout(Duration::from_secs(ACK_TIMEOUT), read_pong)
+            .await
+            .map_err(|_| BybitError::Base("Timed out waiting for pong".to_string()))?
     }
 
+    /// Authenticates on the private endpoint, subscribes to `req` and feeds every event to
+    /// `handler`. Returns the error that ended the connection; it does not reconnect.
     pub async fn ws_priv_subscribe<'a, F>(
         &self,
         req: Subscription<'a>,
-        handler: F,
+        ping_interval: Option<u64>,
+        mut handler: F,
     ) -> Result<(), BybitError>
     where
         F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
     {
-        let request = Self::build_subscription(req);
-        let response = self
-            .client
-            .wss_connect(WebsocketAPI::Private, Some(request), true, Some(10))
-            .await?;
-        match Self::event_loop(response, handler, None).await {
-            Ok(_) => {}
-            Err(_) => {}
-        }
-        Ok(())
+        let state = self.register_subscription(&req);
+        self.run_subscription(
+            WebsocketAPI::Private,
+            state,
+            ping_interval,
+            None,
+            &mut handler,
+        )
+        .await
     }
 
     pub async fn ws_subscribe<'a, F>(
         &self,
         req: Subscription<'a>,
         category: Category,
-        handler: F,
+        ping_interval: Option<u64>,
+        reconnect: Option<ReconnectConfig>,
+        mut handler: F,
     ) -> Result<(), BybitError>
     where
         F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
@@ -90,22 +248,579 @@ impl Stream {
                 Category::Linear => WebsocketAPI::Public(Public::Linear),
                 Category::Inverse => WebsocketAPI::Public(Public::Inverse),
                 Category::Spot => WebsocketAPI::Public(Public::Spot),
-                _ => unimplemented!("Option has not been implemented"),
+                Category::Option => WebsocketAPI::Public(Public::Option),
+            }
+        };
+        let state = self.register_subscription(&req);
+        self.run_subscription(endpoint, state, ping_interval, reconnect, &mut handler)
+            .await
+    }
+
+    /// `ws_subscribe` that hands each event to the matching method of `handler`.
+    pub async fn ws_subscribe_handler<'a, H>(
+        &self,
+        req: Subscription<'a>,
+        category: Category,
+        ping_interval: Option<u64>,
+        reconnect: Option<ReconnectConfig>,
+        mut handler: H,
+    ) -> Result<(), BybitError>
+    where
+        H: EventHandler + 'static + Send,
+    {
+        self.ws_subscribe(req, category, ping_interval, reconnect, move |event| {
+            handler.on_event(event)
+        })
+        .await
+    }
+
+    /// Connects to `endpoint`, subscribes to `state.topics` and runs the event loop, reconnecting
+    /// and replaying the topics still held when `reconnect` is set. The private endpoint is
+    /// authenticated first.
+    pub(crate) async fn run_subscription<F>(
+        &self,
+        endpoint: WebsocketAPI,
+        mut state: SubscriptionState,
+        ping_interval: Option<u64>,
+        reconnect: Option<ReconnectConfig>,
+        mut handler: F,
+    ) -> Result<(), BybitError>
+    where
+        F: FnMut(WebsocketEvents) -> Result<(), BybitError> + Send,
+    {
+        let private = matches!(endpoint, WebsocketAPI::Private);
+        let mut attempts = 0;
+        loop {
+            let topics = state.topics.iter().map(String::as_str).collect();
+            let frames =
+                Self::build_subscriptions(Subscription::new(SubscriptionOp::Subscribe, topics));
+            let result = match self
+                .client
+                .wss_connect(endpoint.clone(), None, private, private.then_some(10))
+                .await
+            {
+                Ok(mut response) => match async {
+                    if private {
+                        Self::await_auth_ack(&mut response, &mut handler).await?;
+                    }
+                    self.send_subscriptions(&mut response, &mut handler, frames)
+                        .await
+                }
+                .await
+                {
+                    Ok(_) => {
+                        if attempts > 0 {
+                            if let Some(callback) =
+                                reconnect.as_ref().and_then(|c| c.on_reconnect.as_ref())
+                            {
+                                callback();
+                            }
+                        }
+                        attempts = 0;
+                        let read_timeout = self.client.ws_read_timeout();
+                        Self::event_loop(
+                            response,
+                            &mut handler,
+                            None,
+                            ping_interval,
+                            read_timeout,
+                            Some(&mut state),
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            let Err(e) = result else { return Ok(()) };
+            // A rejected topic, a rejected auth or a failing handler would fail the same way on
+            // a new connection, so there is nothing to retry.
+            if !e.is_transient() {
+                return Err(e);
+            }
+            let Some(config) = reconnect.as_ref() else {
+                return Err(e);
+            };
+            if attempts >= config.max_retries {
+                return Err(BybitError::ReconnectFailed {
+                    attempts,
+                    source: Box::new(e),
+                });
             }
+            let delay = config.backoff(attempts);
+            warn!(
+                "Websocket disconnected ({e}), reconnect attempt {} in {delay:?}",
+                attempts + 1
+            );
+            tokio::time::sleep(delay).await;
+            attempts += 1;
+        }
+    }
+
+    /// Subscribes to a mix of topic families (e.g. `orderbook.50.BTCUSDT`, `publicTrade.BTCUSDT`
+    /// and `tickers.BTCUSDT`) over a single connection. Each event reaches `handler` as its own
+    /// `WebsocketEvents` variant.
+    pub async fn ws_subscribe_many<F>(
+        &self,
+        topics: Vec<Topic>,
+        category: Category,
+        handler: F,
+    ) -> Result<(), BybitError>
+    where
+        F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
+    {
+        let topics: Vec<String> = topics.iter().map(Topic::to_string).collect();
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            topics.iter().map(String::as_str).collect(),
+        );
+        self.ws_subscribe(request, category, None, None, handler)
+            .await
+    }
+
+    /// Sets the read timeout of the connections this stream opens; see
+    /// `Client::with_ws_read_timeout`.
+    pub fn with_read_timeout(self, timeout: Duration) -> Self {
+        Stream {
+            client: self.client.with_ws_read_timeout(timeout),
+            ..self
+        }
+    }
+
+    /// Applies `config` to the websocket connections this stream opens; see
+    /// `Client::with_ws_config`.
+    pub fn with_ws_config(self, config: WebSocketConfig) -> Self {
+        Stream {
+            client: self.client.with_ws_config(config),
+            ..self
+        }
+    }
+
+    /// Runs `subscribe` on a spawned task and returns a handle to stop or join it.
+    ///
+    /// `subscribe` receives its own copy of the stream, which the handle commands, e.g.
+    /// `stream.spawn(|s| async move { s.ws_trades(subs, category, sender).await })`. Calling
+    /// the helper directly and awaiting it remains the simplest option for single-subscription
+    /// scripts.
+    pub fn spawn<F, Fut>(&self, subscribe: F) -> StreamHandle
+    where
+        F: FnOnce(Stream) -> Fut,
+        Fut: std::future::Future<Output = Result<(), BybitError>> + Send + 'static,
+    {
+        let stream = Stream {
+            client: self.client.clone(),
+            active: Arc::default(),
         };
-        let request = Self::build_subscription(req);
-        let response = self
+        let task = tokio::spawn(subscribe(stream.clone()));
+        StreamHandle { stream, task }
+    }
+
+    /// Spawning variant of `ws_subscribe`; see `Stream::spawn`.
+    pub fn ws_subscribe_spawn<F>(
+        &self,
+        req: Subscription<'_>,
+        category: Category,
+        ping_interval: Option<u64>,
+        reconnect: Option<ReconnectConfig>,
+        handler: F,
+    ) -> StreamHandle
+    where
+        F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
+    {
+        let op = req.op;
+        let args: Vec<String> = req.args.iter().map(|arg| arg.to_string()).collect();
+        self.spawn(move |stream| async move {
+            let request = Subscription::new(op, args.iter().map(String::as_str).collect());
+            stream
+                .ws_subscribe(request, category, ping_interval, reconnect, handler)
+                .await
+        })
+    }
+
+    /// Subscribes, waits for `count` typed events, closes the connection and returns them.
+    ///
+    /// Acks, pongs and `WebsocketEvents::Unknown` frames are not counted. If the subscription
+    /// ends first, its error is returned, or `ConnectionClosed` when it ended cleanly.
+    pub async fn ws_collect(
+        &self,
+        req: Subscription<'_>,
+        category: Category,
+        count: usize,
+    ) -> Result<Vec<WebsocketEvents>, BybitError> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let handle = self.ws_subscribe_spawn(req, category, None, None, move |event| {
+            if !matches!(event, WebsocketEvents::Unknown(_)) {
+                let _ = sender.send(event);
+            }
+            Ok(())
+        });
+        let mut events = Vec::with_capacity(count);
+        while events.len() < count {
+            match receiver.recv().await {
+                Some(event) => events.push(event),
+                None => {
+                    handle.join().await?;
+                    return Err(BybitError::ConnectionClosed(format!(
+                        "Stream ended after {} of {count} events",
+                        events.len()
+                    )));
+                }
+            }
+        }
+        handle.stop().await?;
+        Ok(events)
+    }
+
+    /// Subscribes on a spawned task and forwards every decoded event to the returned receiver.
+    /// The connection is pinged every `DEFAULT_PING_INTERVAL` seconds.
+    ///
+    /// The task finishes with `Ok(())` when the next event arrives after the receiver was
+    /// dropped, or with the stream error that ended the subscription. Abort the handle to end it
+    /// right away.
+    pub fn ws_subscribe_channel(
+        &self,
+        req: Subscription<'_>,
+        category: Category,
+    ) -> (
+        JoinHandle<Result<(), BybitError>>,
+        mpsc::UnboundedReceiver<WebsocketEvents>,
+    ) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let stream = self.clone();
+        let op = req.op;
+        let args: Vec<String> = req.args.iter().map(|arg| arg.to_string()).collect();
+        let handle = tokio::spawn(async move {
+            let watch = sender.clone();
+            let request = Subscription::new(op, args.iter().map(String::as_str).collect());
+            let result = stream
+                .ws_subscribe(
+                    request,
+                    category,
+                    Some(DEFAULT_PING_INTERVAL),
+                    None,
+                    move |event| {
+                        sender
+                            .send(event)
+                            .map_err(|_| BybitError::Base("Event receiver was dropped".to_string()))
+                    },
+                )
+                .await;
+            if watch.is_closed() {
+                return Ok(());
+            }
+            result
+        });
+        (handle, receiver)
+    }
+
+    /// Subscribes and returns the events as a `futures::Stream` instead of taking a handler.
+    ///
+    /// The subscription runs on a spawned task via `ws_subscribe_channel`. The error that ends
+    /// it, if any, is yielded as the final item. Dropping the stream aborts the task, which
+    /// drops the connection.
+    pub fn ws_subscribe_stream(
+        &self,
+        req: Subscription<'_>,
+        category: Category,
+    ) -> impl futures::Stream<Item = Result<WebsocketEvents, BybitError>> {
+        let (handle, receiver) = self.ws_subscribe_channel(req, category);
+        let task = AbortOnDrop(handle);
+        futures::stream::unfold(Some((task, receiver)), |state| async move {
+            let (mut task, mut receiver) = state?;
+            match receiver.recv().await {
+                Some(event) => Some((Ok(event), Some((task, receiver)))),
+                None => match (&mut task.0).await {
+                    Ok(Ok(())) => None,
+                    Ok(Err(e)) => Some((Err(e), None)),
+                    Err(e) => Some((Err(BybitError::Join(e)), None)),
+                },
+            }
+        })
+    }
+
+    /// Subscribes to more topics on the most recently started subscription of this stream.
+    ///
+    /// Resolves once Bybit acknowledges the request, after which the topics are replayed if the
+    /// connection is re-established. At most `MAX_ARGS_PER_REQUEST` topics can be added at once.
+    pub async fn ws_subscribe_more(&self, args: Vec<&str>) -> Result<(), BybitError> {
+        if args.is_empty() || args.len() > MAX_ARGS_PER_REQUEST {
+            return Err(BybitError::InvalidParams(format!(
+                "between 1 and {MAX_ARGS_PER_REQUEST} topics can be subscribed at once, got {}",
+                args.len()
+            )));
+        }
+        let args = args.into_iter().map(String::from).collect();
+        self.send_command(|ack| WsCommand::Subscribe { args, ack })
+            .await
+    }
+
+    /// Pings the connection of the most recently started subscription of this stream, waits up
+    /// to `ACK_TIMEOUT` seconds for the pong and returns the round-trip latency.
+    ///
+    /// The event loop already pings every `ping_interval`; a manual ping restarts that interval.
+    pub async fn keepalive(&self) -> Result<Duration, BybitError> {
+        let sent = Instant::now();
+        tokio::time::timeout(
+            Duration::from_secs(ACK_TIMEOUT),
+            self.send_command(|ack| WsCommand::Ping { ack }),
+        )
+        .await
+        .map_err(|_| BybitError::Base("Timed out waiting for pong".to_string()))??;
+        Ok(sent.elapsed())
+    }
+
+    /// Unsubscribes from `args` on the most recently started subscription of this stream.
+    ///
+    /// The frame is written by the running event loop, and this resolves once Bybit
+    /// acknowledges it. Unsubscribed topics are not replayed if the connection is re-established.
+    pub async fn ws_unsubscribe(&self, args: Vec<&str>) -> Result<(), BybitError> {
+        let args = args.into_iter().map(String::from).collect();
+        self.send_command(|ack| WsCommand::Unsubscribe { args, ack })
+            .await
+    }
+
+    /// Sends an arbitrary JSON frame on the most recently started subscription of this stream.
+    ///
+    /// Low level and unstable: this is an escape hatch for operations the crate has no typed
+    /// helper for yet. Nothing is validated, and the call resolves once the frame is written,
+    /// not when Bybit answers. Replies and pushes for such operations reach the subscription's
+    /// handler, as raw JSON when it was started with `ws_subscribe_raw`, otherwise as
+    /// `WebsocketEvents::Unknown`.
+    pub async fn ws_send_raw(&self, value: Value) -> Result<(), BybitError> {
+        let frame = value.to_string();
+        self.send_command(|ack| WsCommand::Raw { frame, ack }).await
+    }
+
+    /// Subscribes to `req` on `endpoint` and hands every frame to `handler` as raw JSON, without
+    /// decoding it into `WebsocketEvents`.
+    ///
+    /// Low level and unstable, for topics the typed helpers do not cover yet. Private and trade
+    /// stream endpoints are authenticated first. The connection is not re-established when it
+    /// drops.
+    pub async fn ws_subscribe_raw<'a, F>(
+        &self,
+        endpoint: WebsocketAPI,
+        req: Subscription<'a>,
+        handler: F,
+    ) -> Result<(), BybitError>
+    where
+        F: FnMut(Value) -> Result<(), BybitError> + 'static + Send,
+    {
+        let private = !matches!(endpoint, WebsocketAPI::Public(_));
+        let mut handler = RawFrames(handler);
+        let mut state = self.register_subscription(&req);
+        let mut response = self
             .client
-            .wss_connect(endpoint, Some(request), false, None)
+            .wss_connect(endpoint, None, private, private.then_some(10))
             .await?;
-        Self::event_loop(response, handler, None).await?;
-        Ok(())
+        self.send_subscriptions(&mut response, &mut handler, Self::build_subscriptions(req))
+            .await?;
+        Self::event_loop(
+            response,
+            handler,
+            None,
+            None,
+            self.client.ws_read_timeout(),
+            Some(&mut state),
+        )
+        .await
+    }
+
+    /// Closes the most recently started subscription of this stream.
+    ///
+    /// The event loop sends a Close frame and waits for Bybit's Close reply before the
+    /// subscribing call returns `Ok(())`, so no half-open connection is left behind and no
+    /// reconnect is attempted.
+    pub async fn close(&self) -> Result<(), BybitError> {
+        self.send_command(|ack| WsCommand::Close { ack }).await
+    }
+
+    async fn send_command<F>(&self, command: F) -> Result<(), BybitError>
+    where
+        F: FnOnce(CommandAck) -> WsCommand,
+    {
+        let sender = self
+            .active
+            .lock()
+            .unwrap()
+            .commands
+            .clone()
+            .ok_or_else(|| BybitError::Base("No active websocket subscription".to_string()))?;
+        let (ack, response) = oneshot::channel();
+        sender
+            .send(command(ack))
+            .map_err(|_| BybitError::Base("Websocket event loop has stopped".to_string()))?;
+        response.await.map_err(|_| {
+            BybitError::Base("Connection closed before the command was acknowledged".to_string())
+        })?
+    }
+
+    /// Returns the `conn_id` Bybit assigned to the most recently acknowledged subscription.
+    pub fn conn_id(&self) -> Option<String> {
+        self.active.lock().unwrap().conn_id.clone()
+    }
+
+    /// Writes `frames` on a freshly connected socket and waits until every one is acknowledged.
+    async fn send_subscriptions<H>(
+        &self,
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        handler: &mut H,
+        frames: Vec<String>,
+    ) -> Result<Option<PongData>, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        let requests = requested_topics(&frames);
+        for frame in frames {
+            stream.send(WsMessage::Text(frame)).await?;
+        }
+        self.await_acks(stream, handler, requests.len(), &requests)
+            .await
+    }
+
+    /// Reads frames until Bybit has acknowledged `expected` subscribe requests, failing fast on a
+    /// rejected auth or topic, and returns the last ack. Topic messages that arrive in between are
+    /// passed on to `handler`.
+    pub async fn await_subscribe_ack<H>(
+        &self,
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        handler: &mut H,
+        expected: usize,
+    ) -> Result<Option<PongData>, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        self.await_acks(stream, handler, expected, &HashMap::new())
+            .await
+    }
+
+    /// `await_subscribe_ack` that names the topics of a rejected request, looked up by its
+    /// `req_id` in `requests`.
+    async fn await_acks<H>(
+        &self,
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        handler: &mut H,
+        expected: usize,
+        requests: &HashMap<String, Vec<String>>,
+    ) -> Result<Option<PongData>, BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        let read_ack = async {
+            let mut last = None;
+            let mut remaining = expected;
+            while remaining > 0 {
+                let Some(msg) = stream.next().await else {
+                    return Err(BybitError::Base("Stream was closed".to_string()));
+                };
+                let WsMessage::Text(msg) = msg? else { continue };
+                if let Some(auth) = Self::parse_auth_ack(&msg) {
+                    auth?;
+                    continue;
+                }
+                let Ok(ack) = serde_json::from_str::<PongData>(&msg) else {
+                    handler.handle_msg(&msg)?;
+                    continue;
+                };
+                match ack.op.as_str() {
+                    "subscribe" if !ack.success.unwrap_or(false) => {
+                        return Err(subscription_failed(requests, ack));
+                    }
+                    "subscribe" => {
+                        remaining -= 1;
+                        last = Some(ack);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(last)
+        };
+        let ack = tokio::time::timeout(Duration::from_secs(ACK_TIMEOUT), read_ack)
+            .await
+            .map_err(|_| BybitError::Base("Timed out waiting for subscribe ack".to_string()))??;
+        if let Some(ack) = &ack {
+            info!("Subscribed on connection {}", ack.conn_id);
+            self.active.lock().unwrap().conn_id = Some(ack.conn_id.clone());
+        }
+        Ok(ack)
+    }
+
+    /// Reads frames until the `auth` request sent by `wss_connect` is acknowledged. Topic messages
+    /// that arrive first are passed on to `handler`.
+    pub async fn await_auth_ack<H>(
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        handler: &mut H,
+    ) -> Result<(), BybitError>
+    where
+        H: WebSocketHandler,
+    {
+        let read_ack = async {
+            while let Some(msg) = stream.next().await {
+                let WsMessage::Text(msg) = msg? else { continue };
+                match Self::parse_auth_ack(&msg) {
+                    Some(auth) => return auth,
+                    None => handler.handle_msg(&msg)?,
+                }
+            }
+            Err(BybitError::Base("Stream was closed".to_string()))
+        };
+        tokio::time::timeout(Duration::from_secs(ACK_TIMEOUT), read_ack)
+            .await
+            .map_err(|_| BybitError::Base("Timed out waiting for auth ack".to_string()))?
+    }
+
+    /// Returns `None` unless `msg` answers an `auth` request. The private stream replies with
+    /// `success`/`ret_msg` while the trade stream uses `retCode`/`retMsg`.
+    pub fn parse_auth_ack(msg: &str) -> Option<Result<(), BybitError>> {
+        let frame: Value = serde_json::from_str(msg).ok()?;
+        if frame.get("op")?.as_str()? != "auth" {
+            return None;
+        }
+        let success = match frame.get("success").and_then(Value::as_bool) {
+            Some(success) => success,
+            None => frame.get("retCode").and_then(Value::as_i64) == Some(0),
+        };
+        if success {
+            return Some(Ok(()));
+        }
+        let ret_msg = frame
+            .get("ret_msg")
+            .or_else(|| frame.get("retMsg"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        Some(Err(BybitError::AuthFailed { ret_msg }))
+    }
+
+    pub(crate) fn register_subscription(&self, req: &Subscription) -> SubscriptionState {
+        let (sender, commands) = mpsc::unbounded_channel();
+        self.active.lock().unwrap().commands = Some(sender);
+        SubscriptionState {
+            topics: req.args.iter().map(|arg| arg.to_string()).collect(),
+            commands,
+        }
+    }
+
+    /// Builds one frame per `MAX_ARGS_PER_REQUEST` args, since Bybit rejects larger subscribe requests.
+    pub fn build_subscriptions(action: Subscription) -> Vec<String> {
+        action
+            .args
+            .chunks(MAX_ARGS_PER_REQUEST)
+            .map(|args| Self::build_subscription(Subscription::new(action.op, args.to_vec())))
+            .collect()
     }
 
     pub fn build_subscription(action: Subscription) -> String {
+        Self::build_op(&generate_unique_uid(8), action)
+    }
+
+    fn build_op(req_id: &str, action: Subscription) -> String {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("req_id".into(), generate_random_uid(8).into());
-        parameters.insert("op".into(), action.op.into());
+        parameters.insert("req_id".into(), req_id.into());
+        parameters.insert("op".into(), action.op.as_str().into());
         let args_value: Value = action
             .args
             .iter()
@@ -118,10 +833,25 @@ impl Stream {
     }
 
     pub fn build_trade_subscription(orders: RequestType, recv_window: Option<u64>) -> String {
+        Self::build_trade_request(
+            &generate_unique_uid(16),
+            get_timestamp(),
+            orders,
+            recv_window,
+        )
+    }
+
+    /// Builds a trade stream request frame with the given `reqId` and header timestamp.
+    pub fn build_trade_request(
+        req_id: &str,
+        timestamp: u64,
+        orders: RequestType,
+        recv_window: Option<u64>,
+    ) -> String {
         let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-        parameters.insert("reqId".into(), generate_random_uid(16).into());
+        parameters.insert("reqId".into(), req_id.into());
         let mut header_map: BTreeMap<String, String> = BTreeMap::new();
-        header_map.insert("X-BAPI-TIMESTAMP".into(), get_timestamp().to_string());
+        header_map.insert("X-BAPI-TIMESTAMP".into(), timestamp.to_string());
         header_map.insert(
             "X-BAPI-RECV-WINDOW".into(),
             recv_window.unwrap_or(5000).to_string(),
@@ -164,12 +894,20 @@ impl Stream {
         category: Category,
         sender: mpsc::UnboundedSender<OrderBookUpdate>,
     ) -> Result<(), BybitError> {
+        check_symbols(subs.iter().map(|(_, sym)| *sym))?;
         let arr: Vec<String> = subs
             .into_iter()
-            .map(|(num, sym)| format!("orderbook.{}.{}", num, sym.to_uppercase()))
-            .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(AsRef::as_ref).collect());
-        self.ws_subscribe(request, category, move |event| {
+            .map(|(depth, sym)| {
+                let depth = u16::try_from(depth).unwrap_or_default();
+                Topic::orderbook(depth, sym).map(|topic| topic.to_string())
+            })
+            .collect::<Result<_, _>>()?;
+        let arr = unique_args(arr);
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(AsRef::as_ref).collect(),
+        );
+        self.ws_subscribe(request, category, None, None, move |event| {
             if let WebsocketEvents::OrderBookEvent(order_book) = event {
                 sender.send(order_book).unwrap();
             }
@@ -198,11 +936,16 @@ impl Stream {
         category: Category,
         sender: mpsc::UnboundedSender<WsTrade>,
     ) -> Result<(), BybitError> {
+        check_symbols(subs.iter().copied())?;
         let arr: Vec<String> = subs
             .iter()
-            .map(|&sub| format!("publicTrade.{}", sub.to_uppercase()))
+            .map(|&sub| Topic::public_trade(sub).to_string())
             .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(AsRef::as_ref).collect());
+        let arr = unique_args(arr);
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(AsRef::as_ref).collect(),
+        );
         let handler = move |event| {
             if let WebsocketEvents::TradeEvent(trades) = event {
                 for trade in trades.data {
@@ -212,7 +955,8 @@ impl Stream {
             Ok(())
         };
 
-        self.ws_subscribe(request, category, handler).await
+        self.ws_subscribe(request, category, None, None, handler)
+            .await
     }
 
     /// Subscribes to ticker events for the specified symbols and category.
@@ -237,11 +981,16 @@ impl Stream {
         category: Category,
         sender: mpsc::UnboundedSender<Tickers>,
     ) -> Result<(), BybitError> {
+        check_symbols(subs.iter().copied())?;
         let arr: Vec<String> = subs
             .into_iter()
-            .map(|sub| format!("tickers.{}", sub.to_uppercase()))
+            .map(|sub| Topic::ticker(sub).to_string())
             .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(String::as_str).collect());
+        let arr = unique_args(arr);
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(String::as_str).collect(),
+        );
 
         let handler = move |event| {
             if let WebsocketEvents::TickerEvent(tickers) = event {
@@ -250,13 +999,48 @@ impl Stream {
                         sender.send(Tickers::Linear(linear_ticker)).unwrap()
                     }
                     Tickers::Spot(spot_ticker) => sender.send(Tickers::Spot(spot_ticker)).unwrap(),
+                    Tickers::Option(option_ticker) => {
+                        sender.send(Tickers::Option(option_ticker)).unwrap()
+                    }
                 }
             }
             Ok(())
         };
 
-        self.ws_subscribe(request, category, handler).await
+        self.ws_subscribe(request, category, None, None, handler)
+            .await
     }
+    /// Streams the greeks of the given option contracts (e.g. `BTC-27DEC24-60000-C`) from
+    /// their ticker topic on the option endpoint.
+    pub async fn ws_greeks(
+        &self,
+        symbols: Vec<&str>,
+        sender: mpsc::UnboundedSender<Greeks>,
+    ) -> Result<(), BybitError> {
+        let arr: Vec<String> = symbols
+            .into_iter()
+            .map(|symbol| Topic::ticker(symbol).to_string())
+            .collect();
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(String::as_str).collect(),
+        );
+        self.ws_subscribe(request, Category::Option, None, None, move |event| {
+            if let WebsocketEvents::TickerEvent(WsTicker {
+                data: Tickers::Option(ticker),
+                ts,
+                ..
+            }) = event
+            {
+                sender
+                    .send(ticker.greeks(ts)?)
+                    .map_err(|_| BybitError::Base("Greeks receiver was dropped".to_string()))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn ws_liquidations(
         &self,
         subs: Vec<&str>,
@@ -265,9 +1049,12 @@ impl Stream {
     ) -> Result<(), BybitError> {
         let arr: Vec<String> = subs
             .into_iter()
-            .map(|sub| format!("liquidation.{}", sub.to_uppercase()))
+            .map(|sub| Topic::liquidation(sub).to_string())
             .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(String::as_str).collect());
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(String::as_str).collect(),
+        );
 
         let handler = move |event| {
             if let WebsocketEvents::LiquidationEvent(liquidation) = event {
@@ -276,20 +1063,94 @@ impl Stream {
             Ok(())
         };
 
-        self.ws_subscribe(request, category, handler).await
+        self.ws_subscribe(request, category, None, None, handler)
+            .await
     }
+
+    /// Streams insurance pool balance updates for `coins`. `USDT` and `USDC` pools are
+    /// published on the linear stream and the `inverse` pool on the inverse stream, so one
+    /// call cannot mix them.
+    pub async fn ws_insurance(
+        &self,
+        coins: Vec<&str>,
+        sender: mpsc::UnboundedSender<InsuranceData>,
+    ) -> Result<(), BybitError> {
+        let topics: Vec<Topic> = coins.into_iter().map(Topic::insurance).collect();
+        let inverse =
+            |topic: &Topic| matches!(topic, Topic::Insurance { coin } if coin == "inverse");
+        let category = match topics.iter().filter(|topic| inverse(topic)).count() {
+            0 => Category::Linear,
+            n if n == topics.len() => Category::Inverse,
+            _ => {
+                return Err(BybitError::InvalidParams(
+                    "inverse insurance cannot be combined with USDT or USDC".into(),
+                ))
+            }
+        };
+        let arr: Vec<String> = topics.iter().map(Topic::to_string).collect();
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(String::as_str).collect(),
+        );
+        self.ws_subscribe(request, category, None, None, move |event| {
+            if let WebsocketEvents::InsuranceEvent(insurance) = event {
+                for pool in insurance.data {
+                    sender.send(pool).unwrap();
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Like `ws_liquidations`, but on the `allLiquidation` topic, which reports every
+    /// liquidation instead of the largest one per second.
+    pub async fn ws_all_liquidations(
+        &self,
+        subs: Vec<&str>,
+        category: Category,
+        sender: mpsc::UnboundedSender<LiquidationData>,
+    ) -> Result<(), BybitError> {
+        let arr: Vec<String> = subs
+            .into_iter()
+            .map(|sub| Topic::all_liquidation(sub).to_string())
+            .collect();
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(String::as_str).collect(),
+        );
+
+        let handler = move |event| {
+            if let WebsocketEvents::AllLiquidationEvent(liquidations) = event {
+                for liquidation in liquidations.data {
+                    sender.send(liquidation).unwrap();
+                }
+            }
+            Ok(())
+        };
+
+        self.ws_subscribe(request, category, None, None, handler)
+            .await
+    }
+
+    /// Streams klines for `(interval, symbol)` pairs, e.g. `(Interval::OneHour, "BTCUSDT")`.
     pub async fn ws_klines(
         &self,
-        subs: Vec<(&str, &str)>,
+        subs: Vec<(Interval, &str)>,
         category: Category,
         sender: mpsc::UnboundedSender<WsKline>,
     ) -> Result<(), BybitError> {
         let arr: Vec<String> = subs
             .into_iter()
-            .map(|(interval, sym)| format!("kline.{}.{}", interval, sym.to_uppercase()))
-            .collect();
-        let request = Subscription::new("subscribe", arr.iter().map(AsRef::as_ref).collect());
-        self.ws_subscribe(request, category, move |event| {
+            .map(|(interval, sym)| {
+                Topic::kline(interval.as_str(), sym).map(|topic| topic.to_string())
+            })
+            .collect::<Result<_, _>>()?;
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(AsRef::as_ref).collect(),
+        );
+        self.ws_subscribe(request, category, None, None, move |event| {
             if let WebsocketEvents::KlineEvent(kline) = event {
                 sender.send(kline).unwrap();
             }
@@ -298,6 +1159,32 @@ impl Stream {
         .await
     }
 
+    /// Streams leveraged token NAV klines for `(interval, symbol)` pairs, e.g.
+    /// `("5", "EOS3LUSDT")`, from the spot stream.
+    pub async fn ws_lt_klines(
+        &self,
+        subs: Vec<(&str, &str)>,
+        sender: mpsc::UnboundedSender<WsLtKline>,
+    ) -> Result<(), BybitError> {
+        let arr: Vec<String> = subs
+            .into_iter()
+            .map(|(interval, sym)| Topic::lt_kline(interval, sym).map(|topic| topic.to_string()))
+            .collect::<Result<_, _>>()?;
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            arr.iter().map(AsRef::as_ref).collect(),
+        );
+        self.ws_subscribe(request, Category::Spot, None, None, move |event| {
+            if let WebsocketEvents::LtKlineEvent(kline) = event {
+                sender.send(kline).unwrap();
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Streams position updates of `cat`, or of every category when `None`. Spot has no
+    /// positions, so `Category::Spot` is rejected with `InvalidParams` before connecting.
     pub async fn ws_position(
         &self,
         cat: Option<Category>,
@@ -307,14 +1194,19 @@ impl Stream {
             match v {
                 Category::Linear => "position.linear",
                 Category::Inverse => "position.inverse",
-                _ => "",
+                Category::Option => "position.option",
+                Category::Spot => {
+                    return Err(BybitError::InvalidParams(
+                        "Spot has no position stream; use ws_wallet for spot balances".to_string(),
+                    ))
+                }
             }
         } else {
             "position"
         };
 
-        let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec![sub_str]);
+        self.ws_priv_subscribe(request, None, move |event| {
             if let WebsocketEvents::PositionEvent(position) = event {
                 for v in position.data {
                     sender.send(v).unwrap();
@@ -341,8 +1233,8 @@ impl Stream {
             "execution"
         };
 
-        let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec![sub_str]);
+        self.ws_priv_subscribe(request, None, move |event| {
             if let WebsocketEvents::ExecutionEvent(execute) = event {
                 for v in execute.data {
                     sender.send(v).unwrap();
@@ -353,15 +1245,34 @@ impl Stream {
         .await
     }
 
-    pub async fn  ws_fast_exec(
+    pub async fn ws_fast_exec(
         &self,
         sender: mpsc::UnboundedSender<FastExecData>,
-    ) -> Result<(), BybitError>
-    {
-        let sub_str = "execution.fast";
-let request = Subscription::new("subscribe", vec![sub_str]);
+    ) -> Result<(), BybitError> {
+        self.ws_fast_executions(None, sender).await
+    }
 
-        self.ws_priv_subscribe(request, move |event| {
+    /// Streams the slimmer, lower latency `execution.fast` pushes of `cat`, or of every category
+    /// when `None`. Options have no fast execution topic and are rejected with `InvalidParams`.
+    pub async fn ws_fast_executions(
+        &self,
+        cat: Option<Category>,
+        sender: mpsc::UnboundedSender<FastExecData>,
+    ) -> Result<(), BybitError> {
+        let sub_str = match cat {
+            Some(Category::Linear) => "execution.fast.linear",
+            Some(Category::Inverse) => "execution.fast.inverse",
+            Some(Category::Spot) => "execution.fast.spot",
+            Some(Category::Option) => {
+                return Err(BybitError::InvalidParams(
+                    "Option has no fast execution stream; use ws_executions".to_string(),
+                ))
+            }
+            None => "execution.fast",
+        };
+
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec![sub_str]);
+        self.ws_priv_subscribe(request, None, move |event| {
             if let WebsocketEvents::FastExecEvent(execution) = event {
                 for v in execution.data {
                     sender.send(v).unwrap();
@@ -372,6 +1283,24 @@ let request = Subscription::new("subscribe", vec![sub_str]);
         .await
     }
 
+    /// Streams the account's total greeks per base coin from the private `greeks` topic. Unlike
+    /// `ws_greeks`, which follows single option contracts, these cover the whole portfolio.
+    pub async fn ws_greeks_private(
+        &self,
+        sender: mpsc::UnboundedSender<PortfolioGreeks>,
+    ) -> Result<(), BybitError> {
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["greeks"]);
+        self.ws_priv_subscribe(request, None, move |event| {
+            if let WebsocketEvents::GreeksEvent(greeks) = event {
+                for v in greeks.data {
+                    sender.send(v).unwrap();
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn ws_orders(
         &self,
         cat: Option<Category>,
@@ -388,8 +1317,8 @@ let request = Subscription::new("subscribe", vec![sub_str]);
             "order"
         };
 
-        let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec![sub_str]);
+        self.ws_priv_subscribe(request, None, move |event| {
             if let WebsocketEvents::OrderEvent(order) = event {
                 for v in order.data {
                     sender.send(v).unwrap();
@@ -400,13 +1329,34 @@ let request = Subscription::new("subscribe", vec![sub_str]);
         .await
     }
 
+    /// Arms disconnect cancel all (DCP) for `product`: if the private connection drops for more
+    /// than `time_window_secs` (3 to 300), Bybit cancels every open order of that product.
+    ///
+    /// Bybit has no websocket frame for the window itself, so it is set over REST; DCP then
+    /// triggers on the loss of a private connection subscribed to `Topic::dcp(product)`. Pass
+    /// that topic to `ws_priv_subscribe` alongside your other private topics, and include it
+    /// again whenever the private stream is re-established after a disconnect, since a fresh
+    /// connection without it is not watched.
+    pub async fn ws_set_dcp(
+        &self,
+        product: DcpProduct,
+        time_window_secs: u32,
+    ) -> Result<(), BybitError> {
+        let trader = Trader {
+            client: self.client.clone(),
+            recv_window: TRADE_RECV_WINDOW,
+        };
+        trader.set_dcp_options(product, time_window_secs).await?;
+        Ok(())
+    }
+
     pub async fn ws_wallet(
         &self,
         sender: mpsc::UnboundedSender<WalletData>,
     ) -> Result<(), BybitError> {
         let sub_str = "wallet";
-        let request = Subscription::new("subscribe", vec![sub_str]);
-        self.ws_priv_subscribe(request, move |event| {
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec![sub_str]);
+        self.ws_priv_subscribe(request, None, move |event| {
             if let WebsocketEvents::Wallet(wallet) = event {
                 for v in wallet.data {
                     sender.send(v).unwrap();
@@ -420,75 +1370,642 @@ let request = Subscription::new("subscribe", vec![sub_str]);
     pub async fn ws_trade_stream<'a, F>(
         &self,
         req: mpsc::UnboundedReceiver<RequestType<'a>>,
-        handler: F,
+        mut handler: F,
     ) -> Result<(), BybitError>
     where
         F: FnMut(WebsocketEvents) -> Result<(), BybitError> + 'static + Send,
         'a: 'static,
     {
-        let response = self
+        let mut response = self
             .client
             .wss_connect(WebsocketAPI::TradeStream, None, true, Some(10))
             .await?;
-        Self::event_loop(response, handler, Some(req)).await?;
-        
+        Self::await_auth_ack(&mut response, &mut handler).await?;
+        Self::event_loop(
+            response,
+            handler,
+            Some(req),
+            None,
+            self.client.ws_read_timeout(),
+            None,
+        )
+        .await?;
+
         Ok(())
     }
 
+    /// Opens a connection to `endpoint` that is kept as a `WsSession`, to subscribe and
+    /// unsubscribe topics over time and read their events with `WsSession::poll`. The private
+    /// endpoint is authenticated first; the trade stream has `ws_trade_session` instead.
+    pub async fn connect(&self, endpoint: WebsocketAPI) -> Result<WsSession, BybitError> {
+        let private = match endpoint {
+            WebsocketAPI::Public(_) => false,
+            WebsocketAPI::Private => true,
+            WebsocketAPI::TradeStream => {
+                return Err(BybitError::InvalidParams(
+                    "use ws_trade_session for the trade stream".to_string(),
+                ))
+            }
+        };
+        let mut stream = self
+            .client
+            .wss_connect(endpoint, None, private, Some(10))
+            .await?;
+        let mut pending = VecDeque::new();
+        if private {
+            Self::await_auth_ack(&mut stream, &mut |event| {
+                pending.push_back(event);
+                Ok(())
+            })
+            .await?;
+        }
+        Ok(WsSession {
+            pending,
+            ..WsSession::new(stream)
+        })
+    }
+
+    /// Opens an authenticated connection to the trade stream for sending orders one at a
+    /// time and awaiting each acknowledgement. Keep the session around to avoid a new
+    /// handshake per order.
+    pub async fn ws_trade_session(&self) -> Result<TradeSession, BybitError> {
+        let mut stream = self
+            .client
+            .wss_connect(WebsocketAPI::TradeStream, None, true, Some(10))
+            .await?;
+        Self::await_auth_ack(&mut stream, &mut |_: WebsocketEvents| Ok(())).await?;
+        Ok(TradeSession {
+            client: self.client.clone(),
+            stream,
+            recv_window: TRADE_RECV_WINDOW,
+        })
+    }
+
+    /// Places a single order over the trade stream and returns Bybit's acknowledgement.
+    ///
+    /// This opens and closes a connection for the one order; use `ws_trade_session` to
+    /// place several orders on the same connection.
+    pub async fn ws_place_order(
+        &self,
+        req: OrderRequest<'_>,
+    ) -> Result<TradeStreamEvent, BybitError> {
+        let mut session = self.ws_trade_session().await?;
+        let response = session.place_order(req).await;
+        session.close().await;
+        response
+    }
+
+    /// Drives a websocket connection, feeding every text frame to `handler`.
+    ///
+    /// Server pings are answered with a pong. A Close frame from the server ends the loop
+    /// with `BybitError::ConnectionClosed`, which `ws_subscribe` treats like any other
+    /// dropped connection and reconnects from if configured to.
+    ///
+    /// A heartbeat `ping` is written on the same socket every `ping_interval` seconds
+    /// (defaulting to [`DEFAULT_PING_INTERVAL`], at least one) so Bybit does not drop idle
+    /// connections. Reads, pings and outgoing trade requests are multiplexed on a single task,
+    /// so the socket is never written to concurrently.
     pub async fn event_loop<'a, H>(
         mut stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
         mut handler: H,
         mut order_sender: Option<mpsc::UnboundedReceiver<RequestType<'a>>>,
-        
+        ping_interval: Option<u64>,
+        read_timeout: Option<Duration>,
+        mut state: Option<&mut SubscriptionState>,
     ) -> Result<(), BybitError>
     where
         H: WebSocketHandler,
     {
-        let mut interval = Instant::now();
+        let period = Duration::from_secs(ping_interval.unwrap_or(DEFAULT_PING_INTERVAL).max(1));
+        let mut heartbeat = tokio::time::interval_at(Instant::now() + period, period);
+        // With a read timeout, silence first triggers a probing ping, then ends the loop.
+        let mut last_read = Instant::now();
+        let mut probed = false;
+        // Subscribe and unsubscribe requests still waiting for their ack, keyed by req_id.
+        let mut pending: HashMap<String, (AwaitingReply, CommandAck)> = HashMap::new();
         loop {
-            let msg = stream
-                .next()
-                .await;
-            match msg {
-                Some(Ok(WsMessage::Text(msg))) => {
-                    if let Err(_) = handler.handle_msg(&msg) {
-                        return Err(BybitError::Base(
-                            "Error handling stream message".to_string(),
-                        ));
+            tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(Ok(msg)) => {
+                        last_read = Instant::now();
+                        probed = false;
+                        let msg = match msg {
+                            WsMessage::Text(msg) => msg,
+                            // Bybit sends JSON as text, but accept it in a binary frame too.
+                            WsMessage::Binary(bytes) => match String::from_utf8(bytes) {
+                                Ok(msg) => msg,
+                                Err(_) => continue,
+                            },
+                            WsMessage::Ping(payload) => {
+                                stream.send(WsMessage::Pong(payload)).await?;
+                                continue;
+                            }
+                            WsMessage::Close(frame) => {
+                                return Err(BybitError::ConnectionClosed(
+                                    frame.map(|f| f.reason.into_owned()).unwrap_or_default(),
+                                ));
+                            }
+                            _ => continue,
+                        };
+                        if !pending.is_empty() {
+                            if let Ok(ack) = serde_json::from_str::<PongData>(&msg) {
+                                if let Some((request, reply)) =
+                                    ack.req_id.as_ref().and_then(|id| pending.remove(id))
+                                {
+                                    let result = match (request, ack.success.unwrap_or(false)) {
+                                        // The private stream answers pings without `success`.
+                                        (AwaitingReply::Ping, _) if ack.success != Some(false) => Ok(()),
+                                        (AwaitingReply::Ping, _) => Err(BybitError::Base(ack.ret_msg)),
+                                        (AwaitingReply::Subscribe(args), true) => {
+                                            if let Some(state) = state.as_mut() {
+                                                for topic in args {
+                                                    if !state.topics.contains(&topic) {
+                                                        state.topics.push(topic);
+                                                    }
+                                                }
+                                            }
+                                            Ok(())
+                                        }
+                                        (AwaitingReply::Unsubscribe(args), true) => {
+                                            if let Some(state) = state.as_mut() {
+                                                state.topics.retain(|topic| !args.contains(topic));
+                                            }
+                                            Ok(())
+                                        }
+                                        (AwaitingReply::Subscribe(topics), false) => {
+                                            Err(BybitError::SubscriptionFailed {
+                                                topics,
+                                                ret_msg: ack.ret_msg,
+                                            })
+                                        }
+                                        (AwaitingReply::Unsubscribe(_), false) => {
+                                            Err(BybitError::Base(ack.ret_msg))
+                                        }
+                                    };
+                                    let _ = reply.send(result);
+                                    continue;
+                                }
+                            }
+                        }
+                        debug!("frame: {msg}");
+                        handler.handle_msg(&msg).inspect_err(|err| {
+                            error!("Failed to handle stream message ({err}): {msg}");
+                        })?;
+                    }
+                    Some(Err(e)) => {
+                        return Err(e.into());
+                    }
+                    None => {
+                        return Err(BybitError::ConnectionClosed(String::new()));
                     }
+                },
+                Some(v) = async {
+                    match order_sender.as_mut() {
+                        Some(sender) => sender.recv().await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    let order_req = Self::build_trade_subscription(v, Some(3000));
+                    stream.send(WsMessage::Text(order_req)).await?;
                 }
-                Some(Err(e)) => {
-                    return Err(BybitError::from(e.to_string()));
+                Some(command) = async {
+                    match state.as_mut() {
+                        Some(state) => state.commands.recv().await,
+                        None => std::future::pending().await,
+                    }
+                } => match command {
+                    WsCommand::Subscribe { args, ack } => {
+                        let req_id = generate_unique_uid(8);
+                        let topics = args.iter().map(String::as_str).collect();
+                        let request = Self::build_op(&req_id, Subscription::new(SubscriptionOp::Subscribe, topics));
+                        stream.send(WsMessage::Text(request)).await?;
+                        pending.insert(req_id, (AwaitingReply::Subscribe(args), ack));
+                    }
+                    WsCommand::Unsubscribe { args, ack } => {
+                        let req_id = generate_unique_uid(8);
+                        let topics = args.iter().map(String::as_str).collect();
+                        let request = Self::build_op(&req_id, Subscription::new(SubscriptionOp::Unsubscribe, topics));
+                        stream.send(WsMessage::Text(request)).await?;
+                        pending.insert(req_id, (AwaitingReply::Unsubscribe(args), ack));
+                    }
+                    // The trade stream rejects pings with a `req_id`, so those resolve once sent.
+                    WsCommand::Ping { ack } if order_sender.is_some() => {
+                        let result = stream.send(WsMessage::Text(Self::build_ping(false))).await;
+                        heartbeat.reset();
+                        let _ = ack.send(result.map_err(BybitError::from));
+                    }
+                    WsCommand::Ping { ack } => {
+                        let req_id = generate_unique_uid(8);
+                        stream.send(WsMessage::Text(Self::build_ping_frame(Some(&req_id)))).await?;
+                        heartbeat.reset();
+                        pending.insert(req_id, (AwaitingReply::Ping, ack));
+                    }
+                    WsCommand::Raw { frame, ack } => {
+                        let result = stream.send(WsMessage::Text(frame)).await;
+                        let _ = ack.send(result.map_err(BybitError::from));
+                    }
+                    WsCommand::Close { ack } => {
+                        let result = Self::close_handshake(&mut stream).await;
+                        let _ = ack.send(result);
+                        return Ok(());
+                    }
+                },
+                _ = heartbeat.tick() => {
+                    let request = Self::build_ping(order_sender.is_none());
+                    stream.send(WsMessage::Text(request)).await?;
                 }
-                None => {
-                    return Err(BybitError::Base(
-                        "Stream was closed".to_string(),
-                    ));
+                _ = async {
+                    match read_timeout {
+                        Some(timeout) => tokio::time::sleep_until(last_read + timeout).await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    let timeout = read_timeout.unwrap_or_default();
+                    if probed {
+                        warn!("No data received for {:?}, dropping the connection", timeout * 2);
+                        return Err(BybitError::ConnectionClosed(format!(
+                            "No data received for {:?}",
+                            timeout * 2
+                        )));
+                    }
+                    let request = Self::build_ping(order_sender.is_none());
+                    stream.send(WsMessage::Text(request)).await?;
+                    last_read = Instant::now();
+                    probed = true;
                 }
-                _ => {}
             }
-            if let Some(sender) = order_sender.as_mut() {
-                if let Some(v) = sender.recv().await  {
-                    let order_req = Self::build_trade_subscription(v, Some(3000));
-                    stream.send(WsMessage::Text(order_req)).await?;
+        }
+    }
+
+    /// Sends a Close frame and reads until the server's Close reply ends the stream.
+    async fn close_handshake(
+        stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), BybitError> {
+        stream.close(None).await?;
+        let drain = async {
+            while let Some(msg) = stream.next().await {
+                match msg {
+                    Ok(_) => continue,
+                    Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed) => break,
+                    Err(e) => return Err(BybitError::from(e)),
+                }
+            }
+            Ok(())
+        };
+        tokio::time::timeout(Duration::from_secs(ACK_TIMEOUT), drain)
+            .await
+            .map_err(|_| BybitError::Base("Timed out waiting for close reply".to_string()))?
+    }
+
+    /// Builds the `{"op":"ping"}` heartbeat frame. The trade stream rejects a `req_id`
+    /// on pings, so it is only attached when `with_req_id` is set.
+    pub fn build_ping(with_req_id: bool) -> String {
+        Self::build_ping_frame(with_req_id.then(|| generate_unique_uid(8)).as_deref())
+    }
+
+    fn build_ping_frame(req_id: Option<&str>) -> String {
+        let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
+        if let Some(req_id) = req_id {
+            parameters.insert("req_id".into(), req_id.into());
+        }
+        parameters.insert("op".into(), "ping".into());
+        build_json_request(&parameters)
+    }
+}
+
+/// Rejects an empty symbol list, which Bybit would accept without subscribing to anything, and
+/// blank symbols.
+fn check_symbols<'a>(symbols: impl Iterator<Item = &'a str>) -> Result<(), BybitError> {
+    let mut count = 0;
+    for symbol in symbols {
+        if symbol.trim().is_empty() {
+            return Err(BybitError::InvalidParams(
+                "Blank symbol in subscription".to_string(),
+            ));
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Err(BybitError::InvalidParams(
+            "No symbols to subscribe to".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Drops repeated topics, keeping the first occurrence, since Bybit may reject a request that
+/// subscribes to a topic twice.
+fn unique_args(args: Vec<String>) -> Vec<String> {
+    let mut unique = Vec::with_capacity(args.len());
+    for arg in args {
+        if !unique.contains(&arg) {
+            unique.push(arg);
+        }
+    }
+    unique
+}
+
+/// Maps the `req_id` of each request frame built by `build_subscriptions` to its `args`.
+fn requested_topics(frames: &[String]) -> HashMap<String, Vec<String>> {
+    frames
+        .iter()
+        .filter_map(|frame| {
+            let frame: Value = serde_json::from_str(frame).ok()?;
+            let topics = frame["args"]
+                .as_array()?
+                .iter()
+                .filter_map(|arg| arg.as_str().map(String::from))
+                .collect();
+            Some((frame["req_id"].as_str()?.to_string(), topics))
+        })
+        .collect()
+}
+
+/// Names the topics of the request that `ack` rejects, or of every request in `requests` when
+/// the ack does not echo a known `req_id`.
+fn subscription_failed(requests: &HashMap<String, Vec<String>>, ack: PongData) -> BybitError {
+    let topics = match ack.req_id.as_ref().and_then(|id| requests.get(id)) {
+        Some(topics) => topics.clone(),
+        None => requests.values().flatten().cloned().collect(),
+    };
+    BybitError::SubscriptionFailed {
+        topics,
+        ret_msg: ack.ret_msg,
+    }
+}
+
+/// Default `X-BAPI-RECV-WINDOW` for requests sent on a `TradeSession`, in milliseconds.
+pub const TRADE_RECV_WINDOW: u64 = 5000;
+
+/// An authenticated trade stream connection, opened with `Stream::ws_trade_session`.
+///
+/// Requests are sent one at a time: each call waits for the response carrying its own
+/// `reqId`, for at most `ACK_TIMEOUT` seconds.
+pub struct TradeSession {
+    client: Client,
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    pub recv_window: u64,
+}
+
+impl TradeSession {
+    pub async fn place_order(
+        &mut self,
+        req: OrderRequest<'_>,
+    ) -> Result<TradeStreamEvent, BybitError> {
+        let category = req.category;
+        self.send(RequestType::Create(BatchPlaceRequest::new(
+            category,
+            vec![req],
+        )))
+        .await
+    }
+
+    /// Sends an `order.create`, `order.amend` or `order.cancel` request and waits for its
+    /// response. A non-zero `retCode` is returned as `BybitError::BybitError`.
+    pub async fn send(&mut self, orders: RequestType<'_>) -> Result<TradeStreamEvent, BybitError> {
+        let req_id = generate_unique_uid(16);
+        let request = Stream::build_trade_request(
+            &req_id,
+            self.client.timestamp(),
+            orders,
+            Some(self.recv_window),
+        );
+        self.stream.send(WsMessage::Text(request)).await?;
+
+        let read_response = async {
+            while let Some(msg) = self.stream.next().await {
+                let WsMessage::Text(msg) = msg? else { continue };
+                let frame: Value = serde_json::from_str(&msg)?;
+                if frame.get("reqId").and_then(Value::as_str) != Some(req_id.as_str()) {
+                    continue;
                 }
+                let error: BybitContentError = serde_json::from_value(frame.clone())?;
+                if error.code != 0 {
+                    return Err(BybitError::BybitError(error));
+                }
+                return Ok(serde_json::from_value(frame)?);
+            }
+            Err(BybitError::Base("Stream was closed".to_string()))
+        };
+        tokio::time::timeout(Duration::from_secs(ACK_TIMEOUT), read_response)
+            .await
+            .map_err(|_| BybitError::Base("Timed out waiting for order response".to_string()))?
+    }
+
+    pub async fn close(mut self) {
+        let _ = self.stream.close(None).await;
+    }
+}
+
+/// A websocket connection held as state, opened with `Stream::connect`.
+///
+/// Topics are added and removed on the open connection with `subscribe` and `unsubscribe`, and
+/// events are read one at a time with `poll`. Nothing runs in the background, so `poll` must be
+/// called regularly: it answers server pings and sends a heartbeat `ping` every `ping_interval`.
+/// The session does not reconnect; use the `ws_*` helpers or `StreamManager` for that.
+pub struct WsSession {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    heartbeat: tokio::time::Interval,
+    /// Events read while waiting for an ack, handed out by `poll` first.
+    pending: VecDeque<WebsocketEvents>,
+    topics: Vec<String>,
+    conn_id: Option<String>,
+}
+
+impl WsSession {
+    fn new(stream: WebSocketStream<MaybeTlsStream<TcpStream>>) -> Self {
+        let period = Duration::from_secs(DEFAULT_PING_INTERVAL);
+        Self {
+            stream,
+            heartbeat: tokio::time::interval_at(Instant::now() + period, period),
+            pending: VecDeque::new(),
+            topics: Vec::new(),
+            conn_id: None,
+        }
+    }
+
+    /// Seconds between heartbeat pings, `DEFAULT_PING_INTERVAL` unless changed.
+    pub fn with_ping_interval(self, ping_interval: u64) -> Self {
+        let period = Duration::from_secs(ping_interval.max(1));
+        Self {
+            heartbeat: tokio::time::interval_at(Instant::now() + period, period),
+            ..self
+        }
+    }
+
+    /// Topics currently subscribed on this connection.
+    pub fn topics(&self) -> &[String] {
+        &self.topics
+    }
+
+    /// Bybit's id for this connection, known after the first acknowledged subscription.
+    pub fn conn_id(&self) -> Option<&str> {
+        self.conn_id.as_deref()
+    }
+
+    /// Subscribes to `topics` and waits until every chunk of `MAX_ARGS_PER_REQUEST` is
+    /// acknowledged. A rejected topic fails with `BybitError::SubscriptionFailed`.
+    pub async fn subscribe(&mut self, topics: Vec<&str>) -> Result<(), BybitError> {
+        check_symbols(topics.iter().copied())?;
+        let topics = unique_args(topics.into_iter().map(String::from).collect());
+        self.send_op(SubscriptionOp::Subscribe, &topics).await?;
+        for topic in topics {
+            if !self.topics.contains(&topic) {
+                self.topics.push(topic);
             }
-            
-            if interval.elapsed() > Duration::from_secs(300) {
-                let mut parameters: BTreeMap<String, Value> = BTreeMap::new();
-                if order_sender.is_none() {
-                    parameters.insert("req_id".into(), generate_random_uid(8).into());
+        }
+        Ok(())
+    }
+
+    /// Unsubscribes from `topics` and waits for Bybit's acknowledgement.
+    pub async fn unsubscribe(&mut self, topics: Vec<&str>) -> Result<(), BybitError> {
+        check_symbols(topics.iter().copied())?;
+        let topics = unique_args(topics.into_iter().map(String::from).collect());
+        self.send_op(SubscriptionOp::Unsubscribe, &topics).await?;
+        self.topics.retain(|topic| !topics.contains(topic));
+        Ok(())
+    }
+
+    async fn send_op(&mut self, op: SubscriptionOp, topics: &[String]) -> Result<(), BybitError> {
+        let args = topics.iter().map(String::as_str).collect();
+        let frames = Stream::build_subscriptions(Subscription::new(op, args));
+        let requests = requested_topics(&frames);
+        for frame in frames {
+            self.stream.send(WsMessage::Text(frame)).await?;
+        }
+        let Self {
+            stream,
+            pending,
+            conn_id,
+            ..
+        } = self;
+        let read_acks = async {
+            let mut remaining = requests.len();
+            while remaining > 0 {
+                let Some(msg) = stream.next().await else {
+                    return Err(BybitError::Base("Stream was closed".to_string()));
+                };
+                let WsMessage::Text(msg) = msg? else { continue };
+                match serde_json::from_str::<PongData>(&msg) {
+                    Ok(ack) if ack.op == op.as_str() => {
+                        if !ack.success.unwrap_or(false) {
+                            return Err(match op {
+                                SubscriptionOp::Subscribe => subscription_failed(&requests, ack),
+                                _ => BybitError::Base(ack.ret_msg),
+                            });
+                        }
+                        *conn_id = Some(ack.conn_id);
+                        remaining -= 1;
+                    }
+                    _ => (|event| {
+                        pending.push_back(event);
+                        Ok(())
+                    })
+                    .handle_msg(&msg)?,
                 }
-                parameters.insert("op".into(), "ping".into());
-                let request = build_json_request(&parameters);
-                let _ = stream
-                    .send(WsMessage::Text(request))
-                    .await
-                    .map_err(BybitError::from);
-                interval = Instant::now();
+            }
+            Ok(())
+        };
+        tokio::time::timeout(Duration::from_secs(ACK_TIMEOUT), read_acks)
+            .await
+            .map_err(|_| BybitError::Base(format!("Timed out waiting for {} ack", op.as_str())))?
+    }
+
+    /// Returns the next topic event, or `None` once the connection has been closed. Acks and
+    /// pongs are skipped.
+    pub async fn poll(&mut self) -> Result<Option<WebsocketEvents>, BybitError> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            tokio::select! {
+                _ = self.heartbeat.tick() => {
+                    self.stream.send(WsMessage::Text(Stream::build_ping(true))).await?;
+                }
+                msg = self.stream.next() => match msg {
+                    Some(Ok(WsMessage::Text(msg))) => {
+                        let pending = &mut self.pending;
+                        (|event| {
+                            pending.push_back(event);
+                            Ok(())
+                        })
+                        .handle_msg(&msg)?;
+                    }
+                    Some(Ok(WsMessage::Ping(payload))) => {
+                        self.stream.send(WsMessage::Pong(payload)).await?;
+                    }
+                    Some(Ok(WsMessage::Close(_))) | None => return Ok(None),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                },
             }
         }
     }
+
+    pub async fn close(mut self) {
+        let _ = self.stream.close(None).await;
+    }
+}
+
+/// Receives decoded websocket events by type, as an alternative to matching on
+/// `WebsocketEvents` inside a closure. Every method defaults to ignoring its event, so an
+/// implementation only overrides the ones it cares about. Events without a method of their own
+/// go to `on_other`.
+///
+/// Pass an implementation to `Stream::ws_subscribe_handler`, or to any of the closure based
+/// helpers as `move |event| handler.on_event(event)`. An error returned from a method ends the
+/// subscription like an error returned from a closure does.
+pub trait EventHandler {
+    fn on_orderbook(&mut self, _event: OrderBookUpdate) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_trade(&mut self, _event: TradeUpdate) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_ticker(&mut self, _event: WsTicker) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_kline(&mut self, _event: WsKline) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_order(&mut self, _event: OrderEvent) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_position(&mut self, _event: PositionEvent) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_execution(&mut self, _event: Execution) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_wallet(&mut self, _event: WalletEvent) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    fn on_other(&mut self, _event: WebsocketEvents) -> Result<(), BybitError> {
+        Ok(())
+    }
+
+    /// Calls the method matching `event`'s variant.
+    fn on_event(&mut self, event: WebsocketEvents) -> Result<(), BybitError> {
+        match event {
+            WebsocketEvents::OrderBookEvent(event) => self.on_orderbook(event),
+            WebsocketEvents::TradeEvent(event) => self.on_trade(event),
+            WebsocketEvents::TickerEvent(event) => self.on_ticker(event),
+            WebsocketEvents::KlineEvent(event) => self.on_kline(event),
+            WebsocketEvents::OrderEvent(event) => self.on_order(event),
+            WebsocketEvents::PositionEvent(event) => self.on_position(event),
+            WebsocketEvents::ExecutionEvent(event) => self.on_execution(event),
+            WebsocketEvents::Wallet(event) => self.on_wallet(event),
+            event => self.on_other(event),
+        }
+    }
 }
 
 pub trait WebSocketHandler {
@@ -496,17 +2013,41 @@ pub trait WebSocketHandler {
     fn handle_msg(&mut self, msg: &str) -> Result<(), BybitError>;
 }
 
+/// Ops whose replies the closure handler of `ws_subscribe` drops, as `event_loop` handles them.
+const SESSION_OPS: [&str; 5] = ["auth", "subscribe", "unsubscribe", "ping", "pong"];
+
+/// Adapts a closure over raw JSON frames to `WebSocketHandler`, for `ws_subscribe_raw`.
+pub struct RawFrames<F>(pub F);
+
+impl<F> WebSocketHandler for RawFrames<F>
+where
+    F: FnMut(Value) -> Result<(), BybitError>,
+{
+    type Event = Value;
+    fn handle_msg(&mut self, msg: &str) -> Result<(), BybitError> {
+        (self.0)(serde_json::from_str(msg)?)
+    }
+}
+
 impl<F> WebSocketHandler for F
 where
     F: FnMut(WebsocketEvents) -> Result<(), BybitError>,
 {
     type Event = WebsocketEvents;
     fn handle_msg(&mut self, msg: &str) -> Result<(), BybitError> {
-        let update: Value = serde_json::from_str(msg)?;
-        if let Ok(event) = serde_json::from_value::<WebsocketEvents>(update.clone()) {
-            self(event)?;
+        let event: WebsocketEvents = serde_json::from_str(msg)?;
+        match event {
+            // Subscribe/auth acks and pongs carry an `op` but no topic data. Replies to other ops,
+            // e.g. ones sent with `ws_send_raw`, reach the handler.
+            WebsocketEvents::Unknown(ref frame)
+                if frame
+                    .get("op")
+                    .and_then(Value::as_str)
+                    .is_some_and(|op| SESSION_OPS.contains(&op)) =>
+            {
+                Ok(())
+            }
+            event => self(event),
         }
-
-        Ok(())
     }
 }
@@ -4,7 +4,8 @@ use tokio;
 
 #[cfg(test)]
 mod tests {
-    use bybit::account::AccountManager;
+    use bybit::account::{AccountManager, FeeRateCache};
+    use bybit::mock::MockClient;
 
     use super::*;
     static API_KEY: &str = ""; //Mockup string
@@ -14,7 +15,9 @@ mod tests {
     async fn test_wallet() {
         let account: AccountManager =
             Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
-        let wallet = account.get_wallet_balance("UNIFIED", None).await;
+        let wallet = account
+            .get_wallet_balance(AccountType::Unified, Some(vec!["USDT", "BTC"]))
+            .await;
 
         println!("{:?}", wallet);
     }
@@ -23,7 +26,9 @@ mod tests {
     async fn test_fee_rate() {
         let account: AccountManager =
             Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
-        let wallet = account.get_fee_rate(Category::Linear, Some("BTCUSDT".to_string())).await;
+        let wallet = account
+            .get_fee_rate(Category::Linear, Some("BTCUSDT"), None)
+            .await;
 
         println!("{:?}", wallet);
     }
@@ -32,8 +37,77 @@ mod tests {
     async fn test_borrow_history() {
         let account: AccountManager =
             Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
-        let wallet = account.get_fee_rate(Category::Spot, None).await;
+        let wallet = account.get_fee_rate(Category::Spot, None, None).await;
 
         println!("{:?}", wallet);
     }
+
+    async fn mock_account(body: &'static str) -> AccountManager {
+        MockClient::start([body])
+            .await
+            .unwrap()
+            .client(Some(API_KEY.into()), Some(SECRET.into()))
+    }
+
+    #[tokio::test]
+    async fn test_borrow_quota() {
+        let account = mock_account(r#"{"retCode":0,"retMsg":"OK","result":{"symbol":"BTCUSDT","side":"Buy","maxTradeQty":"6.6065","maxTradeAmount":"218023.250733","spotMaxTradeQty":"0","spotMaxTradeAmount":"0","borrowCoin":"USDT"},"retExtInfo":{},"time":1698895841534}"#).await;
+        let quota = account
+            .get_borrow_quota(Category::Spot, "BTCUSDT", Side::Buy)
+            .await
+            .unwrap()
+            .result;
+        assert_eq!(quota.borrow_coin, "USDT");
+        assert_eq!(quota.max_trade_qty, 6.6065);
+        assert_eq!(quota.available_balance, None);
+
+        let result = account
+            .get_borrow_quota(Category::Linear, "BTCUSDT", Side::Buy)
+            .await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+        let result = account.set_spot_leverage(11.0).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_spot_margin_mode() {
+        let account = mock_account(r#"{"retCode":0,"retMsg":"success","result":{"spotMarginMode":"1"},"retExtInfo":{},"time":1672297794480}"#).await;
+        let response = account.set_spot_margin_mode(true).await.unwrap();
+        assert_eq!(response.result.spot_margin_mode, "1");
+    }
+
+    #[tokio::test]
+    async fn test_cached_fee_rate() {
+        let account = mock_account(r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"symbol":"ETHUSDT","takerFeeRate":"0.0006","makerFeeRate":"0.0001"}]},"retExtInfo":{},"time":1676360412576}"#).await;
+        let cache = FeeRateCache::new(account, FeeRateCache::DEFAULT_TTL);
+        for _ in 0..2 {
+            // The mock answers once, so the second call must be served from the cache.
+            let rates = cache
+                .get_fee_rate(Category::Linear, Some("ETHUSDT"), None)
+                .await
+                .unwrap();
+            assert_eq!(rates[0].maker_fee_rate, 0.0001);
+            assert_eq!(rates[0].taker_fee_rate, 0.0006);
+        }
+    }
+
+    #[test]
+    fn test_account_info() {
+        let raw = r#"{"retCode":0,"retMsg":"OK","result":{"marginMode":"REGULAR_MARGIN","updatedTime":"1697078946000","unifiedMarginStatus":4,"dcpStatus":"OFF","timeWindow":10,"smpGroup":0,"isMasterTrader":false,"spotHedgingStatus":"OFF"},"retExtInfo":{},"time":1697078946000}"#;
+        let info = serde_json::from_str::<AccountInfoResponse>(raw)
+            .unwrap()
+            .result;
+        assert_eq!(info.margin_mode, AccountMarginMode::RegularMargin);
+        assert_eq!(info.unified_margin_status, UnifiedMarginStatus::Uta1Pro);
+        assert!(info.unified_margin_status.is_unified());
+        assert_eq!(info.dcp_status, Toggle::Off);
+        assert_eq!(info.updated_time, 1697078946000);
+        assert_eq!(UnifiedMarginStatus::from(7), UnifiedMarginStatus::Other(7));
+    }
 }
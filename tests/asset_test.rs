@@ -0,0 +1,124 @@
+use bybit::asset::AssetManager;
+use bybit::model::{
+    AccountType, DeliveryRecordRequest, InternalTransferRequest, SettlementRecordRequest, Side,
+    TransferHistoryRequest,
+};
+
+mod tests {
+    use super::*;
+    use bybit::mock::MockClient;
+
+    /// Answers with the given replies, in order; the mock records each request.
+    async fn mock_asset(bodies: Vec<&'static str>) -> (AssetManager, MockClient) {
+        let mock = MockClient::start(bodies).await.unwrap();
+        let asset = mock.client(Some("key".into()), Some("secret".into()));
+        (asset, mock)
+    }
+
+    #[tokio::test]
+    async fn test_create_transfer() {
+        let (asset, mock) = mock_asset(vec![
+            r#"{"retCode":0,"retMsg":"success","result":{"transferId":"42c0cfb0-6bca-c242-bc76-4e6df6cbcb16","status":"SUCCESS"},"retExtInfo":{},"time":1670986690556}"#,
+        ])
+        .await;
+        let request =
+            InternalTransferRequest::new("USDT", 25.5, AccountType::Unified, AccountType::Fund);
+        let transfer_id = asset.create_transfer(request).await.unwrap();
+        assert_eq!(transfer_id, "42c0cfb0-6bca-c242-bc76-4e6df6cbcb16");
+
+        let body = mock.requests()[0].json().unwrap();
+        assert_eq!(body["amount"], "25.5");
+        assert_eq!(body["fromAccountType"], "UNIFIED");
+        assert_eq!(body["toAccountType"], "FUND");
+        let sent_id = body["transferId"].as_str().unwrap();
+        assert_eq!(sent_id.len(), 36);
+        assert_eq!(&sent_id[14..15], "4");
+    }
+
+    #[tokio::test]
+    async fn test_transfer_history() {
+        let (asset, mock) = mock_asset(vec![
+            r#"{"retCode":0,"retMsg":"success","result":{"list":[{"transferId":"selfTransfer_a1091cc7-9364-4b74-8de1-18f02c6f2d5c","coin":"USDT","amount":"5000","fromAccountType":"SPOT","toAccountType":"UNIFIED","timestamp":"1667283263000","status":"SUCCESS"}],"nextPageCursor":"eyJtaW5JRCI6MTM1ODQ2OCwibWF4SUQiOjEzNTg0Njh9"},"retExtInfo":{},"time":1670988271677}"#,
+        ])
+        .await;
+        let request = TransferHistoryRequest::new(Some("USDT"), None, None, Some(20));
+        let response = asset.get_transfer_history(request).await.unwrap();
+        let record = &response.result.list[0];
+        assert_eq!(record.amount, 5000.0);
+        assert_eq!(record.timestamp, 1667283263000);
+        assert_eq!(record.to_account_type, "UNIFIED");
+        assert!(!response.result.next_page_cursor.is_empty());
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.query, "coin=USDT&limit=20");
+    }
+
+    #[tokio::test]
+    async fn test_deposit_address_and_balance() {
+        let (asset, mock) = mock_asset(vec![
+            r#"{"retCode":0,"retMsg":"success","result":{"coin":"USDT","chains":[{"chainType":"ERC20","addressDeposit":"0xd9e1cd77afa0e50b452a62fbb68a3340602286c3","tagDeposit":"","chain":"ETH","batchReleaseLimit":"-1","contractAddress":"0xdac17f958d2ee523a2206206994597c13d831ec7"},{"chainType":"TON","addressDeposit":"EQBghGm3Bk4hEBzf7LTQPfQa53","tagDeposit":"1363661252","chain":"TON","batchReleaseLimit":"-1","contractAddress":""}]},"retExtInfo":{},"time":1672192792860}"#,
+            r#"{"retCode":0,"retMsg":"success","result":{"accountType":"FUND","bizType":1,"accountId":"1631385","memberId":"1631373","balance":{"coin":"USDC","walletBalance":"9.96","transferBalance":"9.96","bonus":"0"}},"retExtInfo":{},"time":1675866354913}"#,
+        ])
+        .await;
+        let address = asset
+            .get_deposit_address("USDT", None)
+            .await
+            .unwrap()
+            .result;
+        assert_eq!(address.chains.len(), 2);
+        let ton = address.chain("TON").unwrap();
+        assert_eq!(ton.tag_deposit, "1363661252");
+        assert!(address.chain("SOL").is_none());
+        assert_eq!(mock.requests()[0].param("coin"), Some("USDT"));
+
+        let balance = asset
+            .get_coin_balance(AccountType::Fund, "USDC")
+            .await
+            .unwrap()
+            .result;
+        assert_eq!(balance.account_type, "FUND");
+        assert_eq!(balance.balance.wallet_balance, 9.96);
+        assert_eq!(balance.balance.bonus, Some(0.0));
+        assert_eq!(mock.requests()[1].query, "accountType=FUND&coin=USDC");
+    }
+
+    #[tokio::test]
+    async fn test_delivery_and_settlement_records() {
+        let (asset, mock) = mock_asset(vec![
+            r#"{"retCode":0,"retMsg":"success","result":{"nextPageCursor":"132791%3A0%2C132791%3A0","category":"option","list":[{"symbol":"BTC-14JUN24-69000-C","side":"Buy","deliveryTime":1718352000000,"strike":"69000","fee":"0.0000","position":"0.5","deliveryPrice":"66460.37","deliveryRpl":"-12.5"}]},"retExtInfo":{},"time":1718630071227}"#,
+            r#"{"retCode":0,"retMsg":"success","result":{"nextPageCursor":"","category":"option","list":[{"symbol":"BTC-28JUN24","side":"Sell","deliveryTime":1719561600000,"strike":"","fee":"0.27","position":"0.01","entryPrice":"61000","deliveryPrice":"60855.1","deliveryRpl":"1.449"}]},"retExtInfo":{},"time":1718630071227}"#,
+            r#"{"retCode":0,"retMsg":"success","result":{"nextPageCursor":"","category":"linear","list":[{"symbol":"ETHPERP","side":"Sell","size":"0.02","sessionAvgPrice":"1709.8","markPrice":"1739.64","realisedPnl":"0.5968","createdTime":"1671523200000"}]},"retExtInfo":{},"time":1672284883480}"#,
+        ])
+        .await;
+        let request = DeliveryRecordRequest::new(
+            bybit::model::Category::Option,
+            None,
+            Some("14JUN24"),
+            Some(1),
+            None,
+        );
+        let records = asset.get_all_delivery_records(request).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].strike, Some(69000.0));
+        assert_eq!(records[0].delivery_price, 66460.37);
+        assert_eq!(records[0].delivery_rpl, -12.5);
+        assert_eq!(records[0].position, 0.5);
+        assert_eq!(records[1].side, Side::Sell);
+        assert_eq!(records[1].strike, None);
+        assert_eq!(records[1].entry_price, Some(61000.0));
+        let requests = mock.requests();
+        assert_eq!(requests[0].query, "category=option&expDate=14JUN24&limit=1");
+        assert_eq!(requests[1].param("cursor"), Some("132791%3A0%2C132791%3A0"));
+
+        let settlements = asset
+            .get_settlement_record(SettlementRecordRequest::new(Some("ETHPERP"), None, None))
+            .await
+            .unwrap()
+            .result
+            .list;
+        assert_eq!(settlements[0].realised_pnl, 0.5968);
+        assert_eq!(settlements[0].created_time, 1671523200000);
+        assert_eq!(mock.requests()[2].query, "category=linear&symbol=ETHPERP");
+    }
+}
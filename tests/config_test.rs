@@ -0,0 +1,36 @@
+use bybit::config::{Config, Environment, ReconnectConfig};
+
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let config = ReconnectConfig::new(5, Duration::from_secs(1), Duration::from_secs(10));
+        assert_eq!(config.backoff(0), Duration::from_secs(1));
+        assert_eq!(config.backoff(2), Duration::from_secs(4));
+        assert_eq!(config.backoff(4), Duration::from_secs(10));
+        assert_eq!(config.backoff(64), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_testnet_endpoints() {
+        let config = Config::testnet();
+        assert!(config.rest_api_endpoint.contains("api-testnet"));
+        assert!(config.ws_endpoint.contains("stream-testnet"));
+    }
+
+    #[test]
+    fn test_environment_endpoints() {
+        let config = Config::demo();
+        assert_eq!(config.rest_api_endpoint, "https://api-demo.bybit.com");
+        assert_eq!(config.ws_endpoint, "wss://stream-demo.bybit.com/v5");
+        let mainnet = Config::environment(Environment::default());
+        assert_eq!(mainnet.rest_api_endpoint, Config::DEFAULT_REST_API_ENDPOINT);
+        assert_eq!(mainnet.ws_endpoint, Config::DEFAULT_WS_ENDPOINT);
+        assert_eq!(
+            Config::environment(Environment::Testnet).ws_endpoint,
+            Config::testnet().ws_endpoint
+        );
+    }
+}
@@ -6,9 +6,110 @@ mod tests {
     use super::*;
     // Import the Bybit general module.
     use bybit::general::General;
+    // Import the mock REST server.
+    use bybit::mock::{MockClient, MockResponse};
     // Import the tokio test module.
     use tokio::test;
 
+    /// Answers with the given response bodies, in order, and returns a `General` pointed at
+    /// the mock server.
+    async fn mock_rest(bodies: Vec<&'static str>) -> General {
+        MockClient::start(bodies).await.unwrap().client(None, None)
+    }
+
+    #[test]
+    async fn test_api_error() {
+        let general = mock_rest(vec![
+            r#"{"retCode":10006,"retMsg":"Too many visits!","result":{},"retExtInfo":{},"time":1}"#,
+        ])
+        .await;
+        let err = general.get_server_time().await.unwrap_err();
+        assert!(err.is_rate_limited());
+        assert!(
+            matches!(err, bybit::errors::BybitError::BybitError(ref e) if e.msg == "Too many visits!")
+        );
+    }
+
+    #[test]
+    async fn test_retry() {
+        use bybit::config::RetryConfig;
+        use std::time::Duration;
+        let server_error =
+            r#"{"retCode":10016,"retMsg":"Internal error.","result":{},"retExtInfo":{},"time":1}"#;
+        let ok = r#"{"retCode":0,"retMsg":"OK","result":{"timeSecond":"1688639403","timeNano":"1688639403423213947"},"retExtInfo":{},"time":1688639403423}"#;
+        let retry = RetryConfig {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(1),
+            ..RetryConfig::default()
+        };
+
+        let mut general = mock_rest(vec![server_error, ok]).await;
+        general.client = general.client.with_retry(retry.clone());
+        assert!(general.get_server_time().await.is_ok());
+
+        let mut general = mock_rest(vec![server_error, server_error]).await;
+        general.client = general.client.with_retry(retry);
+        let err = general.get_server_time().await.unwrap_err();
+        assert_eq!(err.ret_code(), Some(10016));
+    }
+
+    #[test]
+    async fn test_sync_time() {
+        // A server clock one hour ahead of the local one.
+        let ahead: &'static str = Box::leak(
+            format!(
+                r#"{{"retCode":0,"retMsg":"OK","result":{{"timeSecond":"0","timeNano":"{}"}},"retExtInfo":{{}},"time":0}}"#,
+                (bybit::util::get_timestamp() + 3_600_000) * 1_000_000
+            )
+            .into_boxed_str(),
+        );
+        let general = mock_rest(vec![ahead]).await;
+        let offset = general.client.sync_time().await.unwrap();
+        assert!((offset - 3_600_000).abs() < 1_000);
+        let skew = general.client.timestamp() - bybit::util::get_timestamp();
+        assert!(skew > 3_590_000);
+    }
+
+    #[test]
+    async fn test_parse_response() {
+        use bybit::client::parse_response;
+        use bybit::model::ServerTimeResponse;
+        let ok = r#"{"retCode":0,"retMsg":"OK","result":{"timeSecond":"1688639403","timeNano":"1688639403423213947"},"retExtInfo":{},"time":1688639403423}"#;
+        assert!(parse_response::<ServerTimeResponse>(ok).is_ok());
+        let expired = r#"{"retCode":10002,"retMsg":"invalid request, please check your server timestamp or recv_window param","result":{},"retExtInfo":{},"time":1}"#;
+        let err = parse_response::<ServerTimeResponse>(expired).unwrap_err();
+        assert!(err.is_timestamp_error());
+    }
+
+    #[test]
+    async fn test_maintenance() {
+        use bybit::client::parse_response;
+        use bybit::errors::BybitError;
+        use bybit::model::ServerTimeResponse;
+        let page = "<html><body><h1>System maintenance in progress</h1></body></html>";
+        let err = parse_response::<ServerTimeResponse>(page).unwrap_err();
+        assert!(err.is_maintenance());
+        assert!(matches!(err, BybitError::Maintenance { status: 200, ref body } if body == page));
+        // Empty and unexpected JSON bodies are not maintenance pages.
+        for body in ["", " ", "[]", r#"{"retCode":0,"#] {
+            let err = parse_response::<ServerTimeResponse>(body).unwrap_err();
+            assert!(matches!(err, BybitError::Json(_)), "{body:?}: {err:?}");
+        }
+
+        // A maintenance page, then an IP rate limit hit, which comes without a page.
+        let mock = MockClient::start([MockResponse::new(503, page), MockResponse::new(403, "")])
+            .await
+            .unwrap();
+        let general: General = mock.client(None, None);
+        let err = general.get_server_time().await.unwrap_err();
+        assert!(matches!(
+            err,
+            BybitError::Maintenance { status: 503, ref body } if body.contains("maintenance")
+        ));
+        let err = general.get_server_time().await.unwrap_err();
+        assert!(matches!(err, BybitError::StatusCode(403)));
+    }
+
     /// Test case that checks the functionality of the `get_server_time` and `ping`
     /// methods of the `Bybit::General` struct.
     ///
@@ -37,5 +138,3 @@ mod tests {
         }
     }
 }
-
-
@@ -1,7 +1,7 @@
 use bybit::api::*;
 use bybit::config::*;
 use bybit::market::*;
-use bybit::model::{Category, InstrumentRequest, KlineRequest, OrderbookRequest};
+use bybit::model::{Category, InstrumentRequest, Interval, KlineRequest, OrderbookRequest};
 use tokio;
 use tokio::time::{Duration, Instant};
 
@@ -9,18 +9,280 @@ use tokio::time::{Duration, Instant};
 mod tests {
 
     use super::*;
+    use bybit::mock::MockClient;
     use bybit::model::{
-        FundingHistoryRequest, HistoricalVolatilityRequest, OpenInterestRequest,
-        RecentTradesRequest, RiskLimitRequest,
+        AnnouncementRequest, FundingHistoryRequest, HistoricalVolatilityRequest, OiInterval,
+        OpenInterestRequest, RecentTradesRequest, RiskLimitRequest,
     };
 
+    #[test]
+    fn test_instrument_rounding() {
+        let linear: bybit::model::InstrumentInfo = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","status":"Trading","baseCoin":"BTC","quoteCoin":"USDT",
+            "leverageFilter":{"minLeverage":"1","maxLeverage":"100.00","leverageStep":"0.01"},
+            "priceFilter":{"minPrice":"0.10","maxPrice":"199999.80","tickSize":"0.10"},
+            "lotSizeFilter":{"maxOrderQty":"100.000","minOrderQty":"0.001","qtyStep":"0.001"}}"#,
+        )
+        .unwrap();
+        assert!(linear.leverage_filter.is_some());
+        assert_eq!(linear.round_price(27123.46), 27123.5);
+        assert_eq!(linear.round_qty(0.0129), 0.012);
+        assert_eq!(linear.round_qty(0.3), 0.3);
+
+        let spot: bybit::model::InstrumentInfo = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","status":"Trading","baseCoin":"BTC","quoteCoin":"USDT",
+            "priceFilter":{"tickSize":"0.01"},
+            "lotSizeFilter":{"basePrecision":"0.000001","minOrderQty":"0.000048","maxOrderQty":"71.73956243"}}"#,
+        )
+        .unwrap();
+        assert!(spot.leverage_filter.is_none());
+        assert_eq!(spot.round_qty(0.12345678), 0.123456);
+    }
+
+    #[test]
+    fn test_instrument_validation() {
+        let linear: bybit::model::InstrumentInfo = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","status":"Trading","baseCoin":"BTC","quoteCoin":"USDT",
+            "priceFilter":{"minPrice":"0.10","maxPrice":"199999.80","tickSize":"0.10"},
+            "lotSizeFilter":{"maxOrderQty":"100.000","minOrderQty":"0.001","qtyStep":"0.001","minNotionalValue":"5"}}"#,
+        )
+        .unwrap();
+        let violated = |price: Option<f64>, qty: f64| match linear.validate_order(price, qty) {
+            Err(bybit::errors::BybitError::InvalidParams(msg)) => msg,
+            other => panic!("expected InvalidParams, got {:?}", other),
+        };
+        assert!(linear.validate_order(Some(27123.5), 0.013).is_ok());
+        assert!(linear.validate_order(None, 0.001).is_ok());
+        assert!(violated(Some(27123.45), 0.01).contains("tickSize"));
+        assert!(violated(Some(200000.0), 0.01).contains("maxPrice"));
+        assert!(violated(Some(27123.5), 0.0125).contains("qtyStep"));
+        assert!(violated(None, 0.0).contains("minOrderQty"));
+        assert!(violated(None, 101.0).contains("maxOrderQty"));
+        assert!(violated(Some(100.0), 0.001).contains("minNotionalValue"));
+
+        let spot: bybit::model::InstrumentInfo = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","status":"Trading","baseCoin":"BTC","quoteCoin":"USDT",
+            "priceFilter":{"tickSize":"0.01"},
+            "lotSizeFilter":{"basePrecision":"0.000001","minOrderQty":"0.000048","maxOrderQty":"71.73956243","minOrderAmt":"1","maxOrderAmt":"2000000"}}"#,
+        )
+        .unwrap();
+        assert!(spot.validate_order(Some(65000.01), 0.000123).is_ok());
+        match spot.validate_order(Some(65000.0), 0.0000123) {
+            Err(bybit::errors::BybitError::InvalidParams(msg)) => {
+                assert!(msg.contains("basePrecision"))
+            }
+            other => panic!("expected InvalidParams, got {:?}", other),
+        }
+        match spot.validate_order(Some(10000.0), 0.00005) {
+            Err(bybit::errors::BybitError::InvalidParams(msg)) => {
+                assert!(msg.contains("minOrderAmt"))
+            }
+            other => panic!("expected InvalidParams, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tickers_parse() {
+        use bybit::model::{Ticker, TickersResponse};
+        let raw = r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[
+            {"symbol":"BTCUSDT","lastPrice":"16597.00","indexPrice":"16598.54","markPrice":"16596.00","prevPrice24h":"16464.50","price24hPcnt":"0.008047","highPrice24h":"30912.50","lowPrice24h":"15700.00","prevPrice1h":"16595.50","openInterest":"373504107","openInterestValue":"6198056150.47","turnover24h":"2352.94950046","volume24h":"49337318","fundingRate":"-0.001034","nextFundingTime":"1672387200000","predictedDeliveryPrice":"","basisRate":"","deliveryFeeRate":"","deliveryTime":"0","ask1Size":"1","bid1Price":"16596.00","ask1Price":"16597.50","bid1Size":"1","basis":""},
+            {"symbol":"BTCUSDT","bid1Price":"20517.96","bid1Size":"2","ask1Price":"20527.77","ask1Size":"1.862172","lastPrice":"20533.13","prevPrice24h":"20393.48","price24hPcnt":"0.0068","highPrice24h":"21128.12","lowPrice24h":"20318.89","turnover24h":"243765620.65899866","volume24h":"11801.27771","usdIndexPrice":"20784.12009279"},
+            {"symbol":"BTC-30DEC22-18000-C","bid1Price":"0","bid1Size":"0","bid1Iv":"0","ask1Price":"435","ask1Size":"0.66","ask1Iv":"5","lastPrice":"435","highPrice24h":"435","lowPrice24h":"165","markPrice":"0.00000009","indexPrice":"16600.55","markIv":"0.7567","underlyingPrice":"16590.42","openInterest":"6.3","turnover24h":"2482.73","volume24h":"0.15","totalVolume":"99","totalTurnover":"1967653","delta":"0.00000001","gamma":"0.00000001","vega":"0.00000004","theta":"-0.00000152","predictedDeliveryPrice":"0","change24h":"86"}
+        ]},"retExtInfo":{},"time":1672376496682}"#;
+        let response: TickersResponse = serde_json::from_str(raw).unwrap();
+        let list = &response.result.list;
+        assert!(matches!(list[0], Ticker::Futures(_)));
+        assert!(matches!(list[1], Ticker::Spot(_)));
+        assert!(matches!(list[2], Ticker::Option(ref t) if t.mark_iv == 0.7567));
+        assert_eq!(list[1].last_price(), 20533.13);
+    }
+
+    async fn mock_market(bodies: Vec<String>) -> MarketData {
+        MockClient::start(bodies).await.unwrap().client(None, None)
+    }
+
+    fn funding_page(timestamps: impl Iterator<Item = u64>) -> String {
+        let list: Vec<String> = timestamps
+            .map(|ts| {
+                format!(
+                    r#"{{"symbol":"BTCUSDT","fundingRate":"0.0001","fundingRateTimestamp":"{}"}}"#,
+                    ts
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"retCode":0,"retMsg":"OK","result":{{"category":"linear","list":[{}]}},"retExtInfo":{{}},"time":1}}"#,
+            list.join(",")
+        )
+    }
+
+    #[tokio::test]
+    async fn test_funding_history_range() {
+        let hour = 3_600_000;
+        let end = 1_000 * hour;
+        let start = end - 202 * hour;
+        // A full first page, then a short page reaching past `start`.
+        let market = mock_market(vec![
+            funding_page((0..MAX_FUNDING_LIMIT).map(|i| end - i * hour)),
+            funding_page((200..205).map(|i| end - i * hour)),
+        ])
+        .await;
+        let rates = market
+            .get_funding_history_range(Category::Linear, "BTCUSDT", start, end)
+            .await
+            .unwrap();
+        assert_eq!(rates.len(), 203);
+        assert_eq!(rates[0].funding_rate_timestamp, end);
+        assert_eq!(rates[202].funding_rate_timestamp, start);
+        assert!(market
+            .get_funding_history_range(Category::Spot, "BTCUSDT", start, end)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_server_time_and_announcements() {
+        let market = mock_market(vec![
+            r#"{"retCode":0,"retMsg":"OK","result":{"timeSecond":"1688639403","timeNano":"1688639403423213947"},"retExtInfo":{},"time":1688639403423}"#.to_string(),
+            r#"{"retCode":0,"retMsg":"OK","result":{"total":735,"list":[{"title":"Scheduled system maintenance","description":"Trading will be suspended during the upgrade.","type":{"title":"Maintenance Updates","key":"maintenance_updates"},"tags":["Maintenance"],"url":"https://announcements.bybit.com/en-US/article/maintenance","dateTimestamp":1679045608000,"startDateTimestamp":1679050000000,"endDateTimestamp":1679053600000,"publishTime":1679045608000}]},"retExtInfo":{},"time":1679415136117}"#.to_string(),
+        ])
+        .await;
+        let time = market.get_server_time().await.unwrap();
+        assert_eq!(time.time_second, 1688639403);
+        assert_eq!(time.millis(), 1688639403423);
+
+        let announcements = market
+            .get_announcements(AnnouncementRequest::maintenance("en-US"))
+            .await
+            .unwrap()
+            .result;
+        let maintenance = &announcements.list[0];
+        assert_eq!(maintenance.announcement_type.key, "maintenance_updates");
+        assert!(maintenance.is_active_at(1679051000000));
+        assert!(!maintenance.is_active_at(1679053600001));
+    }
+
+    #[tokio::test]
+    async fn test_long_short_ratio_and_delivery_price() {
+        let market = mock_market(vec![
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"symbol":"BTCUSDT","buyRatio":"0.5777","sellRatio":"0.4223","timestamp":"1695772800000"}]},"retExtInfo":{},"time":1695785131028}"#.to_string(),
+            r#"{"retCode":0,"retMsg":"success","result":{"category":"option","nextPageCursor":"","list":[{"symbol":"BTC-13FEB25-89000-P","deliveryPrice":"97236.3","deliveryTime":"1739433600000"}]},"retExtInfo":{},"time":1739528995316}"#.to_string(),
+        ])
+        .await;
+        let ratios = market
+            .get_long_short_ratio(Category::Linear, "BTCUSDT", OiInterval::OneHour, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(ratios[0].buy_ratio, 0.5777);
+        assert_eq!(ratios[0].timestamp, 1695772800000);
+        assert!(market
+            .get_long_short_ratio(Category::Spot, "BTCUSDT", OiInterval::OneHour, None)
+            .await
+            .is_err());
+
+        let delivery = market
+            .get_delivery_price(Category::Option, None, Some("BTC"), None)
+            .await
+            .unwrap();
+        assert_eq!(delivery.result.list[0].delivery_price, 97236.3);
+        assert!(market
+            .get_delivery_price(Category::Spot, None, None, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_all_open_interest() {
+        let market = mock_market(vec![
+            r#"{"retCode":0,"retMsg":"OK","result":{"symbol":"BTCUSDT","category":"linear","list":[{"openInterest":"461134384.00000000","timestamp":"1669571400000"},{"openInterest":"461134292.00000000","timestamp":"1669571100000"}],"nextPageCursor":"2"},"retExtInfo":{},"time":1672053548579}"#.to_string(),
+            r#"{"retCode":0,"retMsg":"OK","result":{"symbol":"BTCUSDT","category":"linear","list":[{"openInterest":"461133000.00000000","timestamp":"1669570800000"}],"nextPageCursor":""},"retExtInfo":{},"time":1672053548580}"#.to_string(),
+        ])
+        .await;
+        let request = OpenInterestRequest::new(
+            Category::Linear,
+            "BTCUSDT",
+            OiInterval::FiveMinutes,
+            None,
+            None,
+            Some(2),
+        );
+        let entries = market.get_all_open_interest(request).await.unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].open_interest, 461134384.0);
+        assert_eq!(entries[2].timestamp, 1669570800000);
+    }
+
+    #[tokio::test]
+    async fn test_public_trades() {
+        let market = mock_market(vec![
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"spot","list":[{"execId":"2100000000007764263","symbol":"BTCUSDT","price":"16618.49","size":"0.00012","side":"Buy","time":"1672052955758","isBlockTrade":false},{"execId":"2100000000007764262","symbol":"BTCUSDT","price":"16618.00","size":"0.5","side":"Sell","time":"1672052955700","isBlockTrade":false}]},"retExtInfo":{},"time":1672053054358}"#.to_string(),
+        ])
+        .await;
+        let request = RecentTradesRequest::new(Category::Spot, Some("BTCUSDT"), None, Some(2));
+        let trades = market.get_public_trades(request).await.unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].timestamp, 1672052955700);
+        assert_eq!(trades[1].id, "2100000000007764263");
+        assert_eq!(trades[1].volume, 0.00012);
+
+        let request = RecentTradesRequest::new(Category::Linear, None, None, None);
+        assert!(matches!(
+            market.get_recent_trades(request).await,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_option_recent_trades() {
+        let mock = MockClient::start([
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"option","list":[]},"retExtInfo":{},"time":1672053054358}"#,
+        ])
+        .await
+        .unwrap();
+        let market: MarketData = mock.client(None, None);
+        let request = RecentTradesRequest::option("BTC", Some(5));
+        market.get_recent_trades(request).await.unwrap();
+        let sent = &mock.requests()[0];
+        assert_eq!(sent.param("category"), Some("option"));
+        assert_eq!(sent.param("baseCoin"), Some("BTC"));
+        assert_eq!(sent.param("limit"), Some("5"));
+        assert_eq!(sent.param("symbol"), None);
+    }
+
+    #[test]
+    fn test_kline_parse() {
+        let raw = r#"{"retCode":0,"retMsg":"OK","result":{"symbol":"BTCUSDT","category":"linear","list":[["1670608800000","17071","17073","17027","17055.5","268611","15.74462667"]]},"retExtInfo":{},"time":1672025956592}"#;
+        let response: bybit::model::KlineResponse = serde_json::from_str(raw).unwrap();
+        let kline = &response.result.list[0];
+        assert_eq!(kline.start_time, 1670608800000);
+        assert_eq!(kline.close_price, 17055.5);
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(Interval::from_minutes(1).unwrap(), Interval::OneMinute);
+        assert_eq!(Interval::from_minutes(240).unwrap(), Interval::FourHours);
+        assert_eq!(Interval::from_minutes(1440).unwrap(), Interval::OneDay);
+        assert_eq!(Interval::from_minutes(10080).unwrap(), Interval::OneWeek);
+        assert!(Interval::from_minutes(2).is_err());
+        assert_eq!("W".parse::<Interval>().unwrap(), Interval::OneWeek);
+        assert!("1m".parse::<Interval>().is_err());
+        for interval in Interval::ALL {
+            assert_eq!(interval.to_string().parse::<Interval>().unwrap(), interval);
+            assert_eq!(
+                serde_json::to_string(&interval).unwrap(),
+                format!("\"{}\"", interval)
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_kline() {
         let market: MarketData = Bybit::new(None, None);
         let request = KlineRequest::new(
             Some(Category::Linear),
             "MATICUSDT",
-            "60",
+            Interval::OneHour,
             Some("010124"),
             Some("050224"),
             None,
@@ -118,8 +380,14 @@ mod tests {
     #[tokio::test]
     async fn test_open_interest() {
         let market: MarketData = Bybit::new(None, None);
-        let request =
-            OpenInterestRequest::new(Category::Linear, "MATICUSDT", "4h", None, None, None);
+        let request = OpenInterestRequest::new(
+            Category::Linear,
+            "MATICUSDT",
+            OiInterval::FourHours,
+            None,
+            None,
+            None,
+        );
         let open_interest = market.get_open_interest(request).await;
         if let Ok(data) = open_interest {
             println!("{:#?}", data.result.list.last().unwrap());
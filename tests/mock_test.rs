@@ -0,0 +1,109 @@
+use bybit::client::Credentials;
+use bybit::market::MarketData;
+use bybit::mock::{MockClient, MockResponse, MockStream};
+use bybit::model::{Category, PositionIdx, Side, Subscription, SubscriptionOp, WebsocketEvents};
+use bybit::trade::Trader;
+use bybit::ws::Stream;
+
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_client() {
+        let mock = MockClient::start([
+            MockResponse::from(
+                r#"{"retCode":0,"retMsg":"OK","result":{"orderId":"1321003749386327552","orderLinkId":"test-1"},"retExtInfo":{},"time":1}"#,
+            ),
+            MockResponse::new(503, "<html>maintenance</html>"),
+        ])
+        .await
+        .unwrap();
+        let trader: Trader = mock.client(Some("XXXXXXXXXX".into()), Some("secret".into()));
+        let placed = trader
+            .place_futures_limit_order(
+                Category::Linear,
+                "BTCUSDT",
+                Side::Buy,
+                0.01,
+                30000.0,
+                PositionIdx::OneWay,
+            )
+            .await
+            .unwrap();
+        assert_eq!(placed.result.order_id, "1321003749386327552");
+
+        let sent = &mock.requests()[0];
+        assert_eq!(sent.method, "POST");
+        assert_eq!(sent.path, "/v5/order/create");
+        let body = sent.json().unwrap();
+        assert_eq!(body["symbol"], "BTCUSDT");
+        assert_eq!(body["side"], "Buy");
+        assert!(sent.is_signed_by(&Credentials::Hmac {
+            key: "XXXXXXXXXX".into(),
+            secret: "secret".into(),
+        }));
+        assert!(!sent.is_signed_by(&Credentials::Hmac {
+            key: "XXXXXXXXXX".into(),
+            secret: "other".into(),
+        }));
+
+        let market: MarketData = mock.client(None, None);
+        let result = market.get_server_time().await;
+        assert!(result.unwrap_err().is_maintenance());
+        let sent = &mock.requests()[1];
+        assert_eq!(sent.method, "GET");
+        assert_eq!(sent.path, "/v5/market/time");
+    }
+
+    #[tokio::test]
+    async fn test_mock_stream() {
+        let mock = MockStream::start([
+            r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,"data":[{"T":1672304486865,"s":"BTCUSDT","S":"Buy","v":"0.001","p":"16578.50","L":"PlusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}]}"#,
+            r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486869,"data":[{"T":1672304486866,"s":"BTCUSDT","S":"Sell","v":"0.002","p":"16578.00","L":"MinusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023b0","BT":false}]}"#,
+        ])
+        .await
+        .unwrap();
+        let ws: Stream = mock.client(None, None);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["publicTrade.BTCUSDT"]);
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, None, move |event| {
+                if let WebsocketEvents::TradeEvent(trade) = event {
+                    sender.send(trade.data[0].side.clone()).unwrap();
+                }
+                Ok(())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(receiver.recv().await.unwrap(), "Buy");
+        assert_eq!(receiver.recv().await.unwrap(), "Sell");
+        let frames = mock.frames();
+        assert_eq!(frames[0]["op"], "subscribe");
+        assert_eq!(frames[0]["args"][0], "publicTrade.BTCUSDT");
+    }
+
+    #[tokio::test]
+    async fn test_mock_stream_custom_op() {
+        // A reply to an op sent with `ws_send_raw`, and a pong, which the crate handles itself.
+        let mock = MockStream::start([
+            r#"{"success":true,"ret_msg":"","conn_id":"mock","req_id":"7","op":"brandNewOp"}"#,
+            r#"{"success":true,"ret_msg":"pong","conn_id":"mock","req_id":"8","op":"pong"}"#,
+        ])
+        .await
+        .unwrap();
+        let ws: Stream = mock.client(None, None);
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["publicTrade.BTCUSDT"]);
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, None, move |event| {
+                if let WebsocketEvents::Unknown(frame) = event {
+                    sender.send(frame["op"].clone()).unwrap();
+                }
+                Ok(())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(receiver.recv().await.unwrap(), "brandNewOp");
+        assert!(receiver.recv().await.is_none());
+    }
+}
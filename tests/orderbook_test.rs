@@ -0,0 +1,78 @@
+use bybit::model::{OrderBook, OrderBookKind, OrderBookUpdate};
+use bybit::orderbook::LocalOrderBook;
+
+mod tests {
+    use super::*;
+
+    fn update(kind: &str, u: u64, bids: &str, asks: &str) -> OrderBookUpdate {
+        let raw = format!(
+            r#"{{"topic":"orderbook.50.BTCUSDT","type":"{}","ts":1,"data":{{"s":"BTCUSDT","b":{},"a":{},"u":{},"seq":{}}},"cts":1}}"#,
+            kind, bids, asks, u, u
+        );
+        serde_json::from_str(&raw).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_and_delta() {
+        let mut book = LocalOrderBook::new();
+        book.apply(&update(
+            "snapshot",
+            10,
+            r#"[["100.0","1"],["99.5","2"]]"#,
+            r#"[["100.5","3"],["101.0","4"]]"#,
+        ))
+        .unwrap();
+        assert_eq!(book.best_bid().unwrap().price, 100.0);
+        assert_eq!(book.best_ask().unwrap().price, 100.5);
+
+        book.apply(&update(
+            "delta",
+            11,
+            r#"[["100.0","0"],["99.8","5"]]"#,
+            r#"[["100.5","1.5"]]"#,
+        ))
+        .unwrap();
+        let (bids, asks) = book.depth(2);
+        assert_eq!(bids[0].price, 99.8);
+        assert_eq!(bids[1].price, 99.5);
+        assert_eq!(asks[0].qty, 1.5);
+        assert_eq!(asks.len(), 2);
+    }
+
+    #[test]
+    fn test_update_kind() {
+        let snapshot = update("snapshot", 10, r#"[["100.0","1"]]"#, "[]");
+        assert_eq!(snapshot.kind, OrderBookKind::Snapshot);
+        assert!(snapshot.is_snapshot());
+        let delta = update("delta", 11, "[]", r#"[["100.5","2"]]"#);
+        assert_eq!(delta.kind, OrderBookKind::Delta);
+        assert!(!delta.is_snapshot());
+        assert_eq!(delta.seq(), 11);
+        assert_eq!(delta.cts, 1);
+        let unknown = r#"{"topic":"orderbook.50.BTCUSDT","type":"partial","ts":1,"data":{"s":"BTCUSDT","b":[],"a":[],"u":1,"seq":1},"cts":1}"#;
+        assert!(serde_json::from_str::<OrderBookUpdate>(unknown).is_err());
+    }
+
+    #[test]
+    fn test_from_rest_snapshot() {
+        let snapshot: OrderBook = serde_json::from_str(
+            r#"{"s":"BTCUSDT","b":[["100.0","1"]],"a":[["100.5","2"]],"ts":1,"u":20,"seq":7}"#,
+        )
+        .unwrap();
+        let mut book = LocalOrderBook::from_snapshot(&snapshot);
+        assert_eq!(book.update_id, 20);
+        book.apply(&update("delta", 21, r#"[["100.2","1"]]"#, "[]"))
+            .unwrap();
+        assert_eq!(book.best_bid().unwrap().price, 100.2);
+        assert!(book.apply(&update("delta", 23, "[]", "[]")).is_err());
+    }
+
+    #[test]
+    fn test_sequence_gap() {
+        let mut book = LocalOrderBook::new();
+        assert!(book.apply(&update("delta", 5, "[]", "[]")).is_err());
+        book.apply(&update("snapshot", 5, "[]", "[]")).unwrap();
+        assert!(book.apply(&update("delta", 7, "[]", "[]")).is_err());
+        assert_eq!(book.update_id, 5);
+    }
+}
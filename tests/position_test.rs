@@ -1,7 +1,12 @@
 mod tests {
     use bybit::{
         api::*,
-        model::{Category, LeverageRequest, PositionRequest},
+        mock::MockClient,
+        model::{
+            Category, ChangeMarginRequest, ClosedPnlRequest, LeverageRequest, MarginMode,
+            MarginModeRequest, PositionMode, PositionRequest, SetRiskLimit, Side, StopLevel,
+            TradingStopRequest,
+        },
         position::PositionManager,
     };
     use tokio::test;
@@ -20,6 +25,17 @@ mod tests {
         }
     }
 
+    #[test]
+    async fn all_positions() {
+        let position: PositionManager =
+            Bybit::new(Some(API_KEY.to_string()), Some(SECRET_KEY.to_string()));
+        let request = PositionRequest::new(Category::Linear, None, None, Some("USDT"), Some(50));
+        match position.get_all_positions(request).await {
+            Ok(data) => println!("{:?}", data),
+            Err(e) => println!("{:?}", e),
+        }
+    }
+
     #[test]
     async fn set_leverage() {
         let position: PositionManager =
@@ -30,5 +46,112 @@ mod tests {
             Err(e) => println!("{:?}", e),
         }
     }
-    
+
+    #[test]
+    async fn trading_stop_params() {
+        let req = TradingStopRequest {
+            take_profit: Some(StopLevel::Price(72000.5)),
+            stop_loss: Some(StopLevel::Clear),
+            trailing_stop: Some(250.0.into()),
+            tpsl_mode: Some("Full".into()),
+            ..TradingStopRequest::default()
+        };
+        let parameters = PositionManager::build_trading_stop(req);
+        assert_eq!(parameters["takeProfit"], "72000.5");
+        assert_eq!(parameters["stopLoss"], "0");
+        assert_eq!(parameters["trailingStop"], "250");
+        assert_eq!(parameters["tpslMode"], "Full");
+        assert_eq!(parameters["positionIdx"], 1);
+        assert!(!parameters.contains_key("tpTriggerBy"));
+        assert!(!parameters.contains_key("slSize"));
+    }
+
+    async fn mock_position(bodies: Vec<String>) -> PositionManager {
+        MockClient::start(bodies)
+            .await
+            .unwrap()
+            .client(Some(API_KEY.to_string()), Some(SECRET_KEY.to_string()))
+    }
+
+    #[test]
+    async fn mode_switch_not_modified() {
+        let position = mock_position(vec![
+            r#"{"retCode":110026,"retMsg":"Cross/isolated margin mode is not modified","result":{},"retExtInfo":{},"time":1}"#.to_string(),
+            r#"{"retCode":110025,"retMsg":"Position mode is not modified","result":{},"retExtInfo":{},"time":1}"#.to_string(),
+            r#"{"retCode":10001,"retMsg":"params error","result":{},"retExtInfo":{},"time":1}"#.to_string(),
+        ])
+        .await;
+        let margin = ChangeMarginRequest::new(Category::Linear, "BTCUSDT", MarginMode::Isolated, 5);
+        let response = position.set_margin_mode(margin).await.unwrap();
+        assert_eq!(response.ret_code, 110026);
+        let mode =
+            MarginModeRequest::new(Category::Linear, PositionMode::Hedge, None, Some("USDT"));
+        let response = position.set_position_mode(mode.clone()).await.unwrap();
+        assert_eq!(response.ret_code, 110025);
+        assert!(position.set_position_mode(mode).await.is_err());
+        assert_eq!(MarginModeRequest::default().mode, PositionMode::OneWay);
+    }
+
+    #[test]
+    async fn risk_limit_tiers() {
+        let tiers = r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[{"id":1,"symbol":"BTCUSDT","riskLimitValue":"2000000","maintenanceMargin":"0.005","initialMargin":"0.01","isLowestRisk":1,"maxLeverage":"100.00","mmDeduction":""},{"id":2,"symbol":"BTCUSDT","riskLimitValue":"4000000","maintenanceMargin":"0.01","initialMargin":"0.0175","isLowestRisk":0,"maxLeverage":"57.14","mmDeduction":"10000"}],"nextPageCursor":""},"retExtInfo":{},"time":1672054488010}"#;
+        let position = mock_position(vec![
+            tiers.to_string(),
+            r#"{"retCode":110075,"retMsg":"RiskId not modified","result":{},"retExtInfo":{},"time":1}"#.to_string(),
+            tiers.to_string(),
+        ])
+        .await;
+        let list = position
+            .get_risk_limit(Category::Linear, "BTCUSDT")
+            .await
+            .unwrap();
+        assert_eq!(list[1].max_leverage, 57.14);
+        assert_eq!(list[1].initial_margin, 0.0175);
+        assert_eq!(list[0].mm_deduction, None);
+        assert_eq!(list[1].mm_deduction, Some(10000.0));
+
+        let response = position
+            .set_risk_limit(SetRiskLimit::new(Category::Linear, "BTCUSDT", 2, None))
+            .await
+            .unwrap();
+        assert_eq!(response.ret_code, 110075);
+        assert_eq!(response.result.risk_id, 2);
+        assert_eq!(response.result.risk_limit_value, 4000000);
+    }
+
+    #[test]
+    async fn closed_pnl_total() {
+        let page = |pnl: &str, cursor: &str| {
+            format!(
+                r#"{{"retCode":0,"retMsg":"OK","result":{{"nextPageCursor":"{}","category":"linear","list":[{{
+                    "symbol":"ETHPERP","orderType":"Market","leverage":"3","updatedTime":"1672214887236",
+                    "side":"Sell","orderId":"5a373bfe-188d-4913-9c81-d57ab5be8068","closedPnl":"{}",
+                    "avgEntryPrice":"1185.80","qty":"0.35","cumEntryValue":"415.03","createdTime":"1672214887231",
+                    "orderPrice":"1122.95","closedSize":"0.35","avgExitPrice":"1180.59","execType":"Trade",
+                    "fillCount":"1","cumExitValue":"413.2065"}}]}},"retExtInfo":{{}},"time":1672284129153}}"#,
+                cursor, pnl
+            )
+        };
+        let position = mock_position(vec![
+            page("-1.824", "page2"),
+            page("1.25", "page3"),
+            page("2.5", ""),
+        ])
+        .await;
+        let items = position
+            .get_all_closed_pnl(ClosedPnlRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].qty, 0.35);
+        assert_eq!(items[0].created_time, 1672214887231);
+        assert!(matches!(items[0].side, Side::Sell));
+
+        let position = mock_position(vec![page("-1.5", "page2"), page("2.5", "")]).await;
+        let total = position
+            .get_total_closed_pnl(ClosedPnlRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(total, 1.0);
+    }
 }
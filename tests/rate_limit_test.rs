@@ -0,0 +1,92 @@
+use bybit::config::RateLimitConfig;
+use bybit::errors::BybitError;
+use bybit::rate_limit::{EndpointGroup, RateLimitStatus, RateLimiter};
+
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn config(order: u32, block: bool) -> RateLimitConfig {
+        RateLimitConfig {
+            order,
+            block,
+            ..RateLimitConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_bucket_exhausts_per_group() {
+        let limiter = RateLimiter::new(config(2, false));
+        assert!(limiter.try_acquire(EndpointGroup::Order).is_ok());
+        assert!(limiter.try_acquire(EndpointGroup::Order).is_ok());
+        match limiter.try_acquire(EndpointGroup::Order) {
+            Err(BybitError::RateLimited { retry_after }) => {
+                assert!(retry_after <= Duration::from_millis(500))
+            }
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+        // Other groups keep their own budget.
+        assert!(limiter.try_acquire(EndpointGroup::Market).is_ok());
+    }
+
+    #[test]
+    fn test_observe_limit_status() {
+        let limiter = RateLimiter::new(config(10, false));
+        let reset = bybit::util::get_timestamp() + 60_000;
+        limiter.observe(EndpointGroup::Order, 0, Some(reset));
+        match limiter.try_acquire(EndpointGroup::Order) {
+            Err(BybitError::RateLimited { retry_after }) => {
+                assert!(retry_after > Duration::from_secs(50))
+            }
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_acquire_blocks() {
+        let limiter = RateLimiter::new(config(20, true));
+        let start = Instant::now();
+        for _ in 0..21 {
+            limiter.acquire(EndpointGroup::Order).await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_status_from_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("X-Bapi-Limit", "20".parse().unwrap());
+        headers.insert("X-Bapi-Limit-Status", "19".parse().unwrap());
+        assert_eq!(
+            RateLimitStatus::from_headers(EndpointGroup::Order, &headers),
+            None
+        );
+        headers.insert(
+            "X-Bapi-Limit-Reset-Timestamp",
+            "1711001595207".parse().unwrap(),
+        );
+        let status = RateLimitStatus::from_headers(EndpointGroup::Order, &headers).unwrap();
+        assert_eq!(status.limit, 20);
+        assert_eq!(status.remaining, 19);
+        assert_eq!(status.reset.timestamp_millis(), 1711001595207);
+    }
+
+    #[tokio::test]
+    async fn test_last_rate_limit() {
+        use bybit::general::General;
+        use bybit::mock::{MockClient, MockResponse};
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"timeSecond":"1688639403","timeNano":"1688639403423213947"},"retExtInfo":{},"time":1688639403423}"#;
+        let reply = MockResponse::new(200, body)
+            .with_header("X-Bapi-Limit", "600")
+            .with_header("X-Bapi-Limit-Status", "598")
+            .with_header("X-Bapi-Limit-Reset-Timestamp", "1688639404000");
+        let mock = MockClient::start([reply]).await.unwrap();
+        let general: General = mock.client(None, None);
+        assert_eq!(general.client.last_rate_limit(), None);
+        general.get_server_time().await.unwrap();
+        let status = general.client.clone().last_rate_limit().unwrap();
+        assert_eq!(status.group, EndpointGroup::Market);
+        assert_eq!((status.limit, status.remaining), (600, 598));
+        assert_eq!(status.reset.timestamp_millis(), 1688639404000);
+    }
+}
@@ -7,6 +7,7 @@ use tokio;
 mod tests {
 
     use super::*;
+    use bybit::mock::MockClient;
 
     static API_KEY: &str = ""; //Mockup string
     static SECRET: &str = ""; // Mockup string
@@ -15,11 +16,309 @@ mod tests {
     async fn test_trade() {
         let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
         let order = trade
-            .place_futures_limit_order(Category::Linear, "MATICUSDT", Side::Buy, 100.0, 0.7500, 0)
+            .place_futures_limit_order(
+                Category::Linear,
+                "MATICUSDT",
+                Side::Buy,
+                100.0,
+                0.7500,
+                PositionIdx::OneWay,
+            )
             .await;
         println!("{:#?}", order);
     }
 
+    #[test]
+    fn test_order_constructors() {
+        let order = OrderRequest::limit(Category::Linear, "BTCUSDT", Side::Sell, 0.01, 65000.0);
+        let parameters = Trader::build_orders(Action::Order(order, false));
+        assert_eq!(parameters["orderType"], "Limit");
+        assert_eq!(parameters["timeInForce"], "GTC");
+        assert_eq!(parameters["price"], "65000");
+
+        let order = OrderRequest {
+            reduce_only: Some(true),
+            ..OrderRequest::market(Category::Spot, "ETHUSDT", Side::Buy, 1.5)
+        };
+        let parameters = Trader::build_orders(Action::Order(order, false));
+        assert_eq!(parameters["orderType"], "Market");
+        assert!(!parameters.contains_key("price"));
+    }
+
+    #[test]
+    fn test_order_enums() {
+        assert_eq!(serde_json::to_string(&Side::Buy).unwrap(), r#""Buy""#);
+        assert_eq!(
+            serde_json::to_string(&OrderType::Limit).unwrap(),
+            r#""Limit""#
+        );
+        assert_eq!(
+            serde_json::to_string(&TimeInForce::PostOnly).unwrap(),
+            r#""PostOnly""#
+        );
+        assert_eq!(
+            serde_json::from_str::<TimeInForce>(r#""IOC""#).unwrap(),
+            TimeInForce::IOC
+        );
+        assert!(serde_json::from_str::<Side>(r#""buy""#).is_err());
+
+        let order = OrderRequest {
+            time_in_force: Some(TimeInForce::FOK),
+            tp_order_type: Some(OrderType::Limit),
+            take_profit: Some(70000.0),
+            ..OrderRequest::limit(Category::Linear, "BTCUSDT", Side::Buy, 0.01, 65000.0)
+        };
+        let parameters = Trader::build_orders(Action::Order(order, false));
+        assert_eq!(parameters["timeInForce"], "FOK");
+        assert_eq!(parameters["side"], "Buy");
+    }
+
+    #[test]
+    fn test_amend_only_sends_changed_fields() {
+        let req = AmendOrderRequest {
+            order_id: Some("1234".into()),
+            price: Some(0.75),
+            ..AmendOrderRequest::default()
+        };
+        let parameters = Trader::build_orders(Action::Amend(req, false));
+        assert_eq!(parameters["price"], "0.75");
+        assert!(!parameters.contains_key("qty"));
+        assert!(!parameters.contains_key("takeProfit"));
+    }
+
+    #[test]
+    fn test_batch_results() {
+        let raw = r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"category":"linear","symbol":"BTCUSDT","orderId":"1","orderLinkId":"a","createAt":"1"},{"category":"linear","symbol":"BTCUSDT","orderId":"","orderLinkId":"b","createAt":""}]},"retExtInfo":{"list":[{"code":0,"msg":"OK"},{"code":10001,"msg":"Qty invalid"}]},"time":1}"#;
+        let response: BatchPlaceResponse = serde_json::from_str(raw).unwrap();
+        let results = response.results();
+        assert!(results[0].is_success());
+        assert_eq!(results[1].order_link_id, "b");
+        assert_eq!(results[1].code, 10001);
+    }
+
+    #[tokio::test]
+    async fn test_batch_size_limit() {
+        let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
+        let requests = (0..MAX_BATCH_ORDERS + 1)
+            .map(|_| OrderRequest::market(Category::Linear, "BTCUSDT", Side::Buy, 0.001))
+            .collect();
+        let result = trade
+            .batch_place_order(BatchPlaceRequest::new(Category::Linear, requests))
+            .await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_batch() {
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"category":"spot","symbol":"BTCUSDT","orderId":"1","orderLinkId":"a"},{"category":"spot","symbol":"BTCUSDT","orderId":"","orderLinkId":"b"}]},"retExtInfo":{"list":[{"code":0,"msg":"OK"},{"code":170213,"msg":"Order does not exist."}]},"time":1}"#;
+        let trade = mock_trader(vec![body.to_string()]).await;
+        let cancels = vec![
+            CancelRequest::by_order_id("BTCUSDT", "1"),
+            CancelRequest::by_order_link_id("BTCUSDT", "b"),
+        ];
+        let results = trade
+            .cancel_batch(Category::Spot, cancels)
+            .await
+            .unwrap()
+            .results();
+        assert!(results[0].is_success());
+        assert_eq!(results[1].order_link_id, "b");
+        assert_eq!(results[1].code, 170213);
+
+        let missing_id = CancelRequest {
+            order_id: None,
+            ..CancelRequest::by_order_id("BTCUSDT", "1")
+        };
+        let result = trade.cancel_batch(Category::Linear, vec![missing_id]).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+        let too_many = (0..MAX_BATCH_ORDERS + 1)
+            .map(|_| CancelRequest::by_order_id("BTCUSDT", "1"))
+            .collect();
+        let result = trade.cancel_batch(Category::Option, too_many).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_requires_id() {
+        let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
+        let req = CancelOrderRequest {
+            category: Category::Linear,
+            symbol: "BTCUSDT".into(),
+            order_id: None,
+            order_link_id: None,
+            order_filter: None,
+        };
+        let result = trade.cancel_order(req).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    const ORDER: &str = r#"{
+        "orderId":"fd4300ae-7847-404e-b947-b46980a4d140","orderLinkId":"test-000005","blockTradeId":"",
+        "symbol":"ETHUSDT","price":"1600.00","qty":"0.10","side":"Buy","isLeverage":"","positionIdx":1,
+        "orderStatus":"PartiallyFilledCanceled","cancelType":"UNKNOWN","rejectReason":"EC_NoError",
+        "avgPrice":"1600.00","leavesQty":"0.00","leavesValue":"0","cumExecQty":"0.05","cumExecValue":"80",
+        "cumExecFee":"0.048","timeInForce":"GTC","orderType":"Limit","stopOrderType":"UNKNOWN","orderIv":"",
+        "triggerPrice":"0.00","takeProfit":"2500.00","stopLoss":"1500.00","tpTriggerBy":"LastPrice",
+        "slTriggerBy":"LastPrice","triggerDirection":0,"triggerBy":"UNKNOWN","lastPriceOnCreated":"",
+        "reduceOnly":false,"closeOnTrigger":false,"smpType":"None","smpGroup":0,"smpOrderId":"",
+        "tpslMode":"Full","tpLimitPrice":"0","slLimitPrice":"0","placeType":"",
+        "createdTime":"1684738540559","updatedTime":"1684738540561"}"#;
+
+    async fn mock_trader(bodies: Vec<String>) -> Trader {
+        MockClient::start(bodies)
+            .await
+            .unwrap()
+            .client(Some(API_KEY.into()), Some(SECRET.into()))
+    }
+
+    #[tokio::test]
+    async fn test_place_validated_order() {
+        let instrument: InstrumentInfo = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","status":"Trading","baseCoin":"BTC","quoteCoin":"USDT",
+            "priceFilter":{"minPrice":"0.10","maxPrice":"199999.80","tickSize":"0.10"},
+            "lotSizeFilter":{"maxOrderQty":"100.000","minOrderQty":"0.001","qtyStep":"0.001"}}"#,
+        )
+        .unwrap();
+        // Rejected locally: the mock server has no reply to give.
+        let trader = mock_trader(vec![]).await;
+        let order = OrderRequest::limit(Category::Linear, "BTCUSDT", Side::Buy, 0.01, 65000.05);
+        let result = trader.place_validated_order(order, &instrument).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+        let order = OrderRequest::limit(Category::Linear, "ETHUSDT", Side::Buy, 0.01, 3000.0);
+        let result = trader.place_validated_order(order, &instrument).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+
+        let created = r#"{"retCode":0,"retMsg":"OK","result":{"orderId":"1321003749386327552","orderLinkId":"x"},"retExtInfo":{},"time":1672211918471}"#;
+        let trader = mock_trader(vec![created.to_string()]).await;
+        let order = OrderRequest::limit(Category::Linear, "BTCUSDT", Side::Buy, 0.01, 65000.1);
+        let placed = trader
+            .place_validated_order(order, &instrument)
+            .await
+            .unwrap();
+        assert!(!placed.is_duplicate());
+    }
+
+    #[tokio::test]
+    async fn test_hedge_mode_order() {
+        let order = OrderRequest {
+            position_idx: Some(PositionIdx::HedgeSell),
+            ..OrderRequest::limit(Category::Linear, "BTCUSDT", Side::Sell, 0.01, 65000.0)
+        };
+        let parameters = Trader::build_orders(Action::Order(order.clone(), false));
+        assert_eq!(parameters["positionIdx"], 2);
+        let parameters = Trader::build_orders(Action::Order(
+            OrderRequest::limit(Category::Linear, "BTCUSDT", Side::Sell, 0.01, 65000.0),
+            false,
+        ));
+        assert!(!parameters.contains_key("positionIdx"));
+        assert_eq!(serde_json::to_string(&PositionIdx::HedgeBuy).unwrap(), "1");
+        assert_eq!(
+            serde_json::from_str::<PositionIdx>("2").unwrap(),
+            PositionIdx::HedgeSell
+        );
+        assert!(serde_json::from_str::<PositionIdx>("3").is_err());
+
+        let created = r#"{"retCode":0,"retMsg":"OK","result":{"orderId":"1321003749386327552","orderLinkId":"x"},"retExtInfo":{},"time":1672211918471}"#;
+        let trader = mock_trader(vec![created.to_string()]).await;
+        let placed = trader.place_custom_order(order).await.unwrap();
+        assert!(!placed.is_duplicate());
+    }
+
+    fn orders_page(orders: &[&str], cursor: &str) -> String {
+        format!(
+            r#"{{"retCode":0,"retMsg":"OK","result":{{"category":"linear","nextPageCursor":"{}","list":[{}]}},"retExtInfo":{{}},"time":1684766282976}}"#,
+            cursor,
+            orders.join(",")
+        )
+    }
+
+    #[tokio::test]
+    async fn test_all_open_orders() {
+        let trade = mock_trader(vec![
+            orders_page(&[ORDER, ORDER], "page2"),
+            orders_page(&[ORDER], ""),
+        ])
+        .await;
+        let orders = trade
+            .get_all_open_orders(OpenOrdersRequest::for_settle_coin(Category::Linear, "USDT"))
+            .await
+            .unwrap();
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[0].leaves_qty, 0.0);
+        assert_eq!(orders[0].cum_exec_qty, 0.05);
+        assert_eq!(orders[0].price, 1600.0);
+        assert!(!orders[0].is_fully_filled());
+        assert_eq!(orders[0].fill_ratio(), 0.5);
+
+        let unscoped = OpenOrdersRequest {
+            symbol: None,
+            ..OpenOrdersRequest::default()
+        };
+        assert!(matches!(
+            trade.get_open_orders(unscoped).await,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_all_trade_history() {
+        let execution = r#"{"symbol":"BOBAUSDT","orderType":"Market","underlyingPrice":"","orderLinkId":"",
+            "side":"Buy","indexPrice":"","orderId":"4c3ef6cf-1b5f-4f3d-9ea2-c9a3bb515b4c",
+            "stopOrderType":"UNKNOWN","leavesQty":"0","execTime":"1672221263862","feeCurrency":"","isMaker":false,
+            "execFee":"0.0000591","feeRate":"0.00055","execId":"0ab1bdf7-4219-438b-b30a-32ec863018f7",
+            "tradeIv":"","blockTradeId":"","markPrice":"0.3391","execPrice":"0.3374","markIv":"",
+            "orderQty":"1","orderPrice":"0.3374","execValue":"0.3374","execType":"Trade","execQty":"1",
+            "closedSize":"","seq":4688002127}"#;
+        let page = |cursor: &str| {
+            format!(
+                r#"{{"retCode":0,"retMsg":"OK","result":{{"nextPageCursor":"{}","category":"linear","list":[{}]}},"retExtInfo":{{}},"time":1672283754510}}"#,
+                cursor, execution
+            )
+        };
+        let trade = mock_trader(vec![page("page2"), page("")]).await;
+        let executions = trade
+            .get_all_trade_history(TradeHistoryRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(executions.len(), 2);
+        assert_eq!(executions[0].exec_price, 0.3374);
+        assert_eq!(executions[0].exec_qty, 1.0);
+        assert_eq!(executions[0].exec_fee, 0.0000591);
+        assert_eq!(executions[0].fee_rate, 0.00055);
+        assert_eq!(executions[0].exec_time, 1672221263862);
+        assert!(!executions[0].is_maker);
+    }
+
+    #[test]
+    fn test_order_history_page() {
+        let raw = orders_page(&[ORDER], "page2");
+        let response: OrderHistoryResponse = serde_json::from_str(&raw).unwrap();
+        let (orders, cursor) = response.result.into_page();
+        assert_eq!(cursor.as_deref(), Some("page2"));
+        let state = orders[0].state().unwrap();
+        assert_eq!(state, OrderState::PartiallyFilledCanceled);
+        assert!(state.is_final());
+        assert!(!OrderState::PartiallyFilled.is_final());
+    }
+
     #[tokio::test]
     async fn test_order_history() {
         let trade: Trader = Bybit::new(Some(API_KEY.to_string()), Some(SECRET.to_string()));
@@ -83,4 +382,70 @@ mod tests {
         let batch = trade.batch_place_order(data).await;
         println!("{:#?}", batch);
     }
+
+    #[tokio::test]
+    async fn test_set_dcp_options() {
+        let trader = mock_trader(vec![
+            r#"{"retCode":0,"retMsg":"success","result":{},"retExtInfo":{},"time":1675742580706}"#
+                .into(),
+        ])
+        .await;
+        let response = trader
+            .set_dcp_options(DcpProduct::Derivatives, 40)
+            .await
+            .unwrap();
+        assert_eq!(response.ret_msg, "success");
+        assert!(matches!(
+            trader.set_dcp_options(DcpProduct::Spot, 2).await,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+        assert_eq!(
+            Topic::dcp(DcpProduct::Derivatives).to_string(),
+            "dcp.future"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_order_link_id() {
+        let trader = mock_trader(vec![
+            r#"{"retCode":110072,"retMsg":"OrderLinkedID is duplicate","result":{},"retExtInfo":{},"time":1}"#.into(),
+            orders_page(&[ORDER], ""),
+        ])
+        .await;
+        let req = OrderRequest {
+            category: Category::Linear,
+            symbol: "ETHUSDT".into(),
+            side: Side::Buy,
+            qty: 0.1,
+            order_type: OrderType::Market,
+            order_link_id: Some("test-000005".into()),
+            ..Default::default()
+        };
+        let placed = trader.place_custom_order(req).await.unwrap();
+        assert!(placed.is_duplicate());
+        assert_eq!(placed.order_link_id(), "test-000005");
+        let PlacedOrder::Duplicate { order, .. } = placed else {
+            unreachable!()
+        };
+        assert_eq!(
+            order.unwrap().order_id,
+            "fd4300ae-7847-404e-b947-b46980a4d140"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_leverage_token_orders() {
+        let trader = mock_trader(vec![
+            r#"{"retCode":0,"retMsg":"OK","result":{"ltCoin":"BTC3L","ltOrderStatus":"2","execQty":"","execAmt":"","amount":"10","purchaseId":"2611","serialNo":"","valueCoin":"USDT"},"retExtInfo":{},"time":1}"#.into(),
+            r#"{"retCode":0,"retMsg":"OK","result":{"ltCoin":"BTC3L","ltOrderStatus":"1","quantity":"2.5","execQty":"2.5","execAmt":"9.95","redeemId":"2612","serialNo":"","valueCoin":"USDT"},"retExtInfo":{},"time":1}"#.into(),
+        ])
+        .await;
+        let purchase = trader.purchase_leverage_token("BTC3L", 10.0).await.unwrap();
+        assert_eq!(purchase.lt_order_status, LtOrderStatus::InProgress);
+        assert_eq!(purchase.amount, 10.0);
+        assert_eq!(purchase.exec_qty, None);
+        let redemption = trader.redeem_leverage_token("BTC3L", 2.5).await.unwrap();
+        assert_eq!(redemption.lt_order_status, LtOrderStatus::Completed);
+        assert_eq!(redemption.exec_amt, Some(9.95));
+    }
 }
@@ -0,0 +1,69 @@
+use bybit::util::{
+    generate_order_link_id, generate_unique_uid, millis_to_datetime, round_to_step, round_to_tick,
+    string_to_float_optional, string_to_u64_optional, RoundingMode, MAX_ORDER_LINK_ID_LEN,
+};
+use serde::Deserialize;
+
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Fields {
+        #[serde(with = "string_to_float_optional", default)]
+        price: Option<f64>,
+        #[serde(with = "string_to_u64_optional", default)]
+        count: Option<u64>,
+        #[serde(with = "millis_to_datetime")]
+        time: chrono::DateTime<chrono::Utc>,
+    }
+
+    #[test]
+    fn test_string_numbers() {
+        let fields: Fields =
+            serde_json::from_str(r#"{"price":"43250.5","count":"3","time":"1700000000000"}"#)
+                .unwrap();
+        assert_eq!(fields.price, Some(43250.5));
+        assert_eq!(fields.count, Some(3));
+        assert_eq!(fields.time.timestamp_millis(), 1700000000000);
+    }
+
+    #[test]
+    fn test_empty_and_missing() {
+        let fields: Fields = serde_json::from_str(r#"{"price":"","time":1700000000000}"#).unwrap();
+        assert_eq!(fields.price, None);
+        assert_eq!(fields.count, None);
+        assert_eq!(fields.time.timestamp_millis(), 1700000000000);
+        assert!(serde_json::from_str::<Fields>(r#"{"price":"abc","time":"0"}"#).is_err());
+    }
+
+    #[test]
+    fn test_order_link_id() {
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..1000 {
+            let id = generate_order_link_id("my-bot_1");
+            assert_eq!(id.len(), MAX_ORDER_LINK_ID_LEN);
+            assert!(id.starts_with("mybot1"));
+            assert!(id.chars().all(|c| c.is_ascii_alphanumeric()));
+            assert!(seen.insert(id));
+        }
+        let long = generate_order_link_id(&"x".repeat(50));
+        assert_eq!(long.len(), MAX_ORDER_LINK_ID_LEN);
+        assert_ne!(generate_unique_uid(0), generate_unique_uid(0));
+    }
+
+    #[test]
+    fn test_round_to_tick() {
+        assert_eq!(round_to_tick(27123.46, 0.1, RoundingMode::Nearest), 27123.5);
+        assert_eq!(round_to_tick(27123.46, 0.1, RoundingMode::Floor), 27123.4);
+        assert_eq!(round_to_tick(27123.41, 0.1, RoundingMode::Ceil), 27123.5);
+        assert_eq!(round_to_tick(1.005, 0.5, RoundingMode::Ceil), 1.5);
+        // 0.1 + 0.2 is 0.30000000000000004, which must not ceil to 0.4 or floor to 0.2.
+        assert_eq!(round_to_tick(0.1 + 0.2, 0.1, RoundingMode::Ceil), 0.3);
+        assert_eq!(round_to_tick(0.3, 0.1, RoundingMode::Floor), 0.3);
+        assert_eq!(round_to_tick(5.0, 0.0, RoundingMode::Nearest), 5.0);
+
+        assert_eq!(round_to_step(0.0129, 0.001), 0.012);
+        assert_eq!(round_to_step(0.12345678, 0.000001), 0.123456);
+        assert_eq!(round_to_step(7.0, 5.0), 5.0);
+    }
+}
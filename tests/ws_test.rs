@@ -5,10 +5,19 @@ use tokio;
 mod tests {
 
     use bybit::{
-        model::{Category, Subscription, Tickers, WebsocketEvents},
+        model::{
+            Category, Interval, OrderState, Subscription, SubscriptionBuilder, SubscriptionOp,
+            Tickers, Topic, WebsocketEvents, WsTicker,
+        },
         ws::Stream,
     };
+    use futures::{SinkExt, StreamExt};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
     use tokio::{sync::mpsc, time::Instant};
+    use tokio_tungstenite::tungstenite::Message;
 
     use super::*;
 
@@ -34,16 +43,40 @@ mod tests {
         println!("{:#?}", response);
     }
 
+    #[test]
+    fn test_subscription_builder() {
+        let symbols = [
+            "BTCUSDT", "ETHUSDT", "SOLUSDT", "XRPUSDT", "ADAUSDT", "DOGEUSDT", "BNBUSDT",
+            "LTCUSDT", "DOTUSDT", "LINKUSDT", "AVAXUSDT",
+        ];
+        let builder = SubscriptionBuilder::subscribe()
+            .topics(symbols.iter().map(|symbol| Topic::public_trade(symbol)))
+            .topic(Topic::public_trade("BTCUSDT"));
+        let frames = builder.build();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].args.len(), 10);
+        assert_eq!(frames[1].args, vec!["publicTrade.AVAXUSDT"]);
+        assert_eq!(builder.subscription().args.len(), 11);
+
+        let frame = Stream::build_subscription(frames[1].clone());
+        let frame: serde_json::Value = serde_json::from_str(&frame).unwrap();
+        assert_eq!(frame["op"], "subscribe");
+
+        let unsubscribe = SubscriptionBuilder::unsubscribe().topic(Topic::ticker("BTCUSDT"));
+        assert_eq!(unsubscribe.build()[0].op, SubscriptionOp::Unsubscribe);
+        assert!(SubscriptionBuilder::subscribe().build().is_empty());
+    }
+
     #[tokio::test]
     async fn test_order_book() {
         let ws: Stream = Bybit::new(None, None);
         let request = Subscription {
             args: vec!["publicTrade.ADAUSDT"],
-            op: "subscribe",
+            op: SubscriptionOp::Subscribe,
         };
 
         let response = ws
-            .ws_subscribe(request, Category::Linear, |event| {
+            .ws_subscribe(request, Category::Linear, None, None, |event| {
                 match event {
                     WebsocketEvents::TradeEvent(trade) => {
                         // Handle Trade
@@ -71,6 +104,9 @@ mod tests {
                             Tickers::Spot(spot_ticker) => {
                                 println!("{:#?}", spot_ticker);
                             }
+                            Tickers::Option(option_ticker) => {
+                                println!("{:#?}", option_ticker);
+                            }
                         }
                     }
                     WebsocketEvents::KlineEvent(kline) => {
@@ -135,6 +171,9 @@ mod tests {
                 Tickers::Spot(spot_ticker) => {
                     println!("{:#?}", spot_ticker);
                 }
+                Tickers::Option(option_ticker) => {
+                    println!("{:#?}", option_ticker);
+                }
             }
         }
     }
@@ -142,7 +181,7 @@ mod tests {
     #[tokio::test]
     async fn test_default_klines() {
         let ws: Stream = Bybit::new(None, None);
-        let request = vec![("1", "MATICUSDT")];
+        let request = vec![(Interval::OneMinute, "MATICUSDT")];
         let (tx, mut rx) = mpsc::unbounded_channel();
         tokio::spawn(async move {
             ws.ws_klines(request, Category::Linear, tx).await.unwrap();
@@ -151,4 +190,1246 @@ mod tests {
             println!("{:#?}", data);
         }
     }
+
+    #[tokio::test]
+    async fn test_subscribe_channel() {
+        let ws: Stream = Bybit::new(None, None);
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["publicTrade.BTCUSDT"]);
+        let (handle, mut rx) = ws.ws_subscribe_channel(request, Category::Linear);
+        for _ in 0..5 {
+            match rx.recv().await {
+                Some(event) => println!("{:#?}", event),
+                None => break,
+            }
+        }
+        drop(rx);
+        println!("{:?}", handle.await);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_stream() {
+        let ws: Stream = Bybit::new(None, None);
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let events = ws.ws_subscribe_stream(request, Category::Linear);
+        let mut events = Box::pin(events.take(5));
+        while let Some(event) = events.next().await {
+            println!("{:#?}", event);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dropped_stream_disconnects() {
+        let mut server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-15").await;
+            let push = r#"{"topic":"spread.orderbook.BTCUSDT","ts":1,"data":{}}"#;
+            socket.send(Message::Text(push.into())).await.unwrap();
+            drain(&mut socket).await
+        })
+        .await;
+        let request =
+            Subscription::new(SubscriptionOp::Subscribe, vec!["spread.orderbook.BTCUSDT"]);
+        let mut events = Box::pin(
+            server
+                .stream()
+                .ws_subscribe_stream(request, Category::Linear),
+        );
+        assert!(events.next().await.unwrap().is_ok());
+        drop(events);
+        // No further event arrives, so only aborting the task ends the connection.
+        let disconnected =
+            tokio::time::timeout(std::time::Duration::from_secs(2), server.result()).await;
+        assert!(disconnected.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_testnet_tickers() {
+        let ws: Stream = Bybit::new_testnet(None, None);
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let (handle, mut rx) = ws.ws_subscribe_channel(request, Category::Linear);
+        if let Some(event) = rx.recv().await {
+            println!("{:#?}", event);
+        }
+        drop(rx);
+        println!("{:?}", handle.await);
+    }
+
+    #[test]
+    fn test_option_ticker_deserialize() {
+        let raw = r#"{"id":"tickers.BTC-6JAN23-17500-C-2480334983-1672917511074","topic":"tickers.BTC-6JAN23-17500-C","ts":1672917511074,"data":{"symbol":"BTC-6JAN23-17500-C","bidPrice":"0","bidSize":"0","bidIv":"0","askPrice":"10","askSize":"5.1","askIv":"0.514","lastPrice":"10","highPrice24h":"25","lowPrice24h":"5","markPrice":"7.86976724","indexPrice":"16823.73","markPriceIv":"0.4896","underlyingPrice":"16815.1","openInterest":"49.85","turnover24h":"446802.8473","volume24h":"26.55","totalVolume":"86","totalTurnover":"1437431","delta":"0.047831","gamma":"0.00021453","vega":"0.81351067","theta":"-19.9115368","predictedDeliveryPrice":"0","change24h":"-0.33333334"},"type":"snapshot"}"#;
+        let event: WebsocketEvents = serde_json::from_str(raw).unwrap();
+        match event {
+            WebsocketEvents::TickerEvent(ticker) => {
+                assert!(matches!(ticker.data, Tickers::Option(_)))
+            }
+            _ => panic!("expected a ticker event"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_event_is_forwarded() {
+        use bybit::ws::WebSocketHandler;
+        let mut unknown = 0;
+        let mut handler = |event| {
+            if let WebsocketEvents::Unknown(_) = event {
+                unknown += 1;
+            }
+            Ok(())
+        };
+        handler
+            .handle_msg(r#"{"success":true,"ret_msg":"pong","conn_id":"abc","op":"ping"}"#)
+            .unwrap();
+        handler
+            .handle_msg(r#"{"topic":"newTopic.BTCUSDT","type":"snapshot","ts":1,"data":{}}"#)
+            .unwrap();
+        assert_eq!(unknown, 1);
+    }
+
+    type MockSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+
+    /// A websocket server on localhost that runs a scripted session on every connection.
+    struct MockServer<T> {
+        config: bybit::config::Config,
+        connections: Arc<AtomicUsize>,
+        results: mpsc::UnboundedReceiver<T>,
+    }
+
+    impl<T: Send + 'static> MockServer<T> {
+        /// Runs `session` on each accepted connection with its zero-based index. What a session
+        /// returns is handed out by `result`, in the order the sessions finish.
+        async fn start<F, Fut>(session: F) -> Self
+        where
+            F: Fn(usize, MockSocket) -> Fut + Send + 'static,
+            Fut: std::future::Future<Output = T> + Send + 'static,
+        {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            let connections = Arc::new(AtomicUsize::new(0));
+            let accepted = connections.clone();
+            let (sender, results) = mpsc::unbounded_channel();
+            tokio::spawn(async move {
+                while let Ok((socket, _)) = listener.accept().await {
+                    let id = accepted.fetch_add(1, Ordering::SeqCst);
+                    let Ok(socket) = tokio_tungstenite::accept_async(socket).await else {
+                        continue;
+                    };
+                    let session = session(id, socket);
+                    let sender = sender.clone();
+                    tokio::spawn(async move {
+                        let _ = sender.send(session.await);
+                    });
+                }
+            });
+            let endpoint: &'static str = Box::leak(format!("ws://{}", address).into_boxed_str());
+            let config = bybit::config::Config {
+                ws_endpoint: endpoint,
+                ..bybit::config::Config::default()
+            };
+            Self {
+                config,
+                connections,
+                results,
+            }
+        }
+
+        fn stream(&self) -> Stream {
+            Bybit::new_with_config(&self.config, None, None)
+        }
+
+        fn connections(&self) -> usize {
+            self.connections.load(Ordering::SeqCst)
+        }
+
+        async fn result(&mut self) -> T {
+            self.results.recv().await.unwrap()
+        }
+    }
+
+    /// Reads the next text frame and acknowledges it like Bybit does, returning the request.
+    /// Returns `None` once the client disconnects.
+    async fn ack_next(socket: &mut MockSocket, conn_id: &str) -> Option<serde_json::Value> {
+        let request: serde_json::Value = loop {
+            match socket.next().await?.ok()? {
+                Message::Text(frame) => break serde_json::from_str(&frame).unwrap(),
+                _ => continue,
+            }
+        };
+        let op = request["op"].as_str().unwrap_or_default().to_string();
+        let ack = serde_json::json!({
+            "success": true,
+            "ret_msg": if op == "ping" { "pong" } else { "" },
+            "conn_id": conn_id,
+            "req_id": request["req_id"],
+            "op": op,
+        });
+        socket.send(Message::Text(ack.to_string())).await.ok()?;
+        Some(request)
+    }
+
+    /// Reads until the client disconnects, returning whether it sent a Close frame.
+    async fn drain(socket: &mut MockSocket) -> bool {
+        let mut got_close = false;
+        while let Some(Ok(msg)) = socket.next().await {
+            got_close |= msg.is_close();
+        }
+        got_close
+    }
+
+    /// Serves websocket connections on localhost that answer the first frame with `reply`.
+    async fn mock_endpoint(reply: &'static str) -> Stream {
+        let server = MockServer::start(move |_, mut socket| async move {
+            socket.next().await;
+            socket.send(Message::Text(reply.to_string())).await.unwrap();
+            socket.close(None).await.ok();
+        })
+        .await;
+        server.stream()
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_ack() {
+        let ws = mock_endpoint(
+            r#"{"success":true,"ret_msg":"","conn_id":"conn-1","req_id":"1","op":"subscribe"}"#,
+        )
+        .await;
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, None, |_| Ok(()))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(ws.conn_id().as_deref(), Some("conn-1"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_rejected() {
+        let ws = mock_endpoint(
+            r#"{"success":false,"ret_msg":"error:handler not found,topic:foo.BTCUSDT","conn_id":"conn-2","req_id":"1","op":"subscribe"}"#,
+        )
+        .await;
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["foo.BTCUSDT"]);
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, None, |_| Ok(()))
+            .await;
+        let Err(bybit::errors::BybitError::SubscriptionFailed { topics, ret_msg }) = result else {
+            panic!("expected SubscriptionFailed, got {:?}", result);
+        };
+        // The mock's `req_id` is not the one sent, so every requested topic is named.
+        assert_eq!(topics, vec!["foo.BTCUSDT"]);
+        assert!(ret_msg.contains("handler not found"));
+    }
+
+    #[tokio::test]
+    async fn test_handler_error_propagated() {
+        let server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-14").await;
+            let ticker = r#"{"topic":"tickers.BTCUSDT","type":"snapshot","ts":1,"cs":1,"data":{"symbol":"BTCUSDT"}}"#;
+            socket.send(Message::Text(ticker.into())).await.ok();
+            drain(&mut socket).await;
+        })
+        .await;
+        let ws = server.stream();
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        // A failing handler would fail again on a new connection, so it is not retried.
+        let reconnect = bybit::config::ReconnectConfig::new(
+            3,
+            std::time::Duration::from_millis(10),
+            std::time::Duration::from_millis(10),
+        );
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, Some(reconnect), |_| {
+                Err(bybit::errors::BybitError::InvalidParams("stop".to_string()))
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(msg)) if msg == "stop"
+        ));
+        assert_eq!(server.connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_event_handler() {
+        use bybit::ws::EventHandler;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Strategy {
+            seen: Arc<Mutex<Vec<&'static str>>>,
+        }
+
+        impl EventHandler for Strategy {
+            fn on_ticker(&mut self, event: WsTicker) -> Result<(), bybit::errors::BybitError> {
+                assert_eq!(event.topic, "tickers.BTCUSDT");
+                self.seen.lock().unwrap().push("ticker");
+                Ok(())
+            }
+
+            fn on_other(
+                &mut self,
+                _event: WebsocketEvents,
+            ) -> Result<(), bybit::errors::BybitError> {
+                self.seen.lock().unwrap().push("other");
+                Ok(())
+            }
+        }
+
+        let mut strategy = Strategy::default();
+        let seen = strategy.seen.clone();
+        let greeks: WebsocketEvents = serde_json::from_str(
+            r#"{"id":"1","topic":"greeks","creationTime":1,"data":[{"baseCoin":"ETH","totalDelta":"0","totalGamma":"0","totalVega":"0","totalTheta":"0"}]}"#,
+        )
+        .unwrap();
+        strategy.on_event(greeks).unwrap();
+        // Trades have no override and are ignored.
+        let trade: WebsocketEvents = serde_json::from_str(
+            r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1,"data":[]}"#,
+        )
+        .unwrap();
+        strategy.on_event(trade).unwrap();
+
+        let ws = mock_endpoint(
+            r#"{"topic":"tickers.BTCUSDT","type":"snapshot","data":{"symbol":"BTCUSDT","tickDirection":"PlusTick","price24hPcnt":"0.017103","lastPrice":"17216.00","prevPrice24h":"16926.50","highPrice24h":"17281.50","lowPrice24h":"16915.00","prevPrice1h":"17238.00","markPrice":"17217.33","indexPrice":"17227.36","openInterest":"68744.761","openInterestValue":"1183601235.91","turnover24h":"1570383121.943499","volume24h":"91705.276","nextFundingTime":"1673280000000","fundingRate":"-0.000212","bid1Price":"17215.50","bid1Size":"84.489","ask1Price":"17216.00","ask1Size":"83.020"},"cs":24987956059,"ts":1673272861686}"#,
+        )
+        .await;
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let result = ws
+            .ws_subscribe_handler(request, Category::Linear, None, None, strategy)
+            .await;
+        assert!(result.is_err());
+        assert_eq!(*seen.lock().unwrap(), vec!["other", "ticker"]);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_subscribe_acks() {
+        let mut server = MockServer::start(|_, mut socket| async move {
+            let mut sizes = Vec::new();
+            for frame in 0..3 {
+                let Some(Ok(Message::Text(request))) = socket.next().await else {
+                    break;
+                };
+                let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+                sizes.push(request["args"].as_array().unwrap().len());
+                // Only the last chunk carries the unknown topic.
+                let ack = format!(
+                    r#"{{"success":{},"ret_msg":"","conn_id":"conn-5","req_id":{},"op":"subscribe"}}"#,
+                    frame < 2,
+                    request["req_id"]
+                );
+                socket.send(Message::Text(ack)).await.unwrap();
+            }
+            sizes
+        })
+        .await;
+        let ws = server.stream();
+
+        let topics: Vec<String> = (0..25).map(|i| format!("tickers.COIN{}USDT", i)).collect();
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            topics.iter().map(String::as_str).collect(),
+        );
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, None, |_| Ok(()))
+            .await;
+        let Err(bybit::errors::BybitError::SubscriptionFailed {
+            topics: rejected, ..
+        }) = result
+        else {
+            panic!("expected SubscriptionFailed, got {:?}", result);
+        };
+        assert_eq!(rejected, topics[20..]);
+        assert_eq!(server.result().await, vec![10, 10, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_raw_frames() {
+        use bybit::api::{Public, WebsocketAPI};
+        let server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-6").await;
+            let push = r#"{"topic":"brandNew.BTCUSDT","ts":1,"data":{"x":1}}"#;
+            socket.send(Message::Text(push.into())).await.unwrap();
+            // Echo the raw op back so the test can see it arrive.
+            while let Some(Ok(frame)) = socket.next().await {
+                if frame.is_text() {
+                    socket.send(frame).await.unwrap();
+                }
+            }
+        })
+        .await;
+        let ws = server.stream();
+        let (tx, mut frames) = tokio::sync::mpsc::unbounded_channel();
+        let subscriber = ws.clone();
+        tokio::spawn(async move {
+            let request = Subscription::new(SubscriptionOp::Subscribe, vec!["brandNew.BTCUSDT"]);
+            subscriber
+                .ws_subscribe_raw(
+                    WebsocketAPI::Public(Public::Linear),
+                    request,
+                    move |frame| {
+                        tx.send(frame).unwrap();
+                        Ok(())
+                    },
+                )
+                .await
+        });
+
+        let push = frames.recv().await.unwrap();
+        assert_eq!(push["topic"], "brandNew.BTCUSDT");
+        ws.ws_send_raw(serde_json::json!({"op": "brandNewOp", "args": [1]}))
+            .await
+            .unwrap();
+        let echoed = frames.recv().await.unwrap();
+        assert_eq!(echoed["op"], "brandNewOp");
+    }
+
+    #[tokio::test]
+    async fn test_ws_message_size_limit() {
+        use bybit::api::{Public, WebsocketAPI};
+        use bybit::client::WebSocketConfig;
+        use bybit::errors::BybitError;
+        use tokio_tungstenite::tungstenite::{error::CapacityError, Error};
+        let server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-7").await;
+            let snapshot = format!(
+                r#"{{"topic":"orderbook.500.BTCUSDT","ts":1,"data":"{}"}}"#,
+                "0".repeat(200_000)
+            );
+            socket.send(Message::Text(snapshot)).await.ok();
+            socket.close(None).await.ok();
+        })
+        .await;
+        let subscribe = |ws: Stream| async move {
+            let (tx, mut sizes) = tokio::sync::mpsc::unbounded_channel();
+            let request =
+                Subscription::new(SubscriptionOp::Subscribe, vec!["orderbook.500.BTCUSDT"]);
+            let result = ws
+                .ws_subscribe_raw(
+                    WebsocketAPI::Public(Public::Linear),
+                    request,
+                    move |frame| {
+                        tx.send(frame.to_string().len()).unwrap();
+                        Ok(())
+                    },
+                )
+                .await;
+            (result, sizes.try_recv().ok())
+        };
+
+        let limited = WebSocketConfig {
+            max_message_size: Some(64 << 10),
+            ..WebSocketConfig::default()
+        };
+        let (result, received) = subscribe(server.stream().with_ws_config(limited)).await;
+        assert_eq!(received, None);
+        assert!(matches!(
+            result,
+            Err(BybitError::Tungstenite(ref e))
+                if matches!(**e, Error::Capacity(CapacityError::MessageTooLong { .. }))
+        ));
+
+        // The default limits accept the same snapshot.
+        let (result, received) = subscribe(server.stream()).await;
+        assert!(received.unwrap() > 200_000);
+        assert!(matches!(result, Err(BybitError::ConnectionClosed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_close() {
+        let mut server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-3").await;
+            // Keep reading so the library answers the client's Close frame.
+            drain(&mut socket).await
+        })
+        .await;
+        let ws = server.stream();
+
+        let subscriber = ws.clone();
+        let handle = tokio::spawn(async move {
+            let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+            subscriber
+                .ws_subscribe(request, Category::Linear, None, None, |_| Ok(()))
+                .await
+        });
+        while ws.conn_id().is_none() {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        ws.close().await.unwrap();
+        assert!(handle.await.unwrap().is_ok());
+        assert!(server.result().await);
+    }
+
+    #[tokio::test]
+    async fn test_stream_handles() {
+        let mut server = MockServer::start(|id, mut socket| async move {
+            ack_next(&mut socket, &format!("conn-{id}")).await;
+            drain(&mut socket).await
+        })
+        .await;
+        let ws = server.stream();
+
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let tickers = ws.ws_subscribe_spawn(request, Category::Linear, None, None, |_| Ok(()));
+        let trades = ws.spawn(|stream| async move {
+            let request = Subscription::new(SubscriptionOp::Subscribe, vec!["publicTrade.BTCUSDT"]);
+            stream
+                .ws_subscribe(request, Category::Linear, None, None, |_| Ok(()))
+                .await
+        });
+        while tickers.stream().conn_id().is_none() || trades.stream().conn_id().is_none() {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert_ne!(tickers.stream().conn_id(), trades.stream().conn_id());
+
+        tickers.stop().await.unwrap();
+        assert!(!trades.is_finished());
+        trades.stop().await.unwrap();
+        for _ in 0..2 {
+            assert!(server.result().await);
+        }
+
+        // A subscription that is never acknowledged is aborted.
+        let silent = MockServer::start(|_, socket| async move {
+            let _socket = socket;
+            std::future::pending::<()>().await
+        })
+        .await;
+        let ws = silent.stream();
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let pending = ws.ws_subscribe_spawn(request, Category::Linear, None, None, |_| Ok(()));
+        pending.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_manager() {
+        use bybit::config::ReconnectConfig;
+        use bybit::stream_manager::StreamManager;
+        use std::time::Duration;
+        let (frames, mut subscribed) = mpsc::unbounded_channel();
+        let server = MockServer::start(move |id, mut socket| {
+            let frames = frames.clone();
+            async move {
+                while let Some(request) = ack_next(&mut socket, &format!("conn-{id}")).await {
+                    if request["op"] != "subscribe" {
+                        continue;
+                    }
+                    for topic in request["args"].as_array().unwrap() {
+                        frames
+                            .send((id, topic.as_str().unwrap().to_string()))
+                            .unwrap();
+                        let push = serde_json::json!({"topic": topic, "ts": 1, "data": {}});
+                        socket.send(Message::Text(push.to_string())).await.unwrap();
+                    }
+                    // The first connection drops after its first push.
+                    if id == 0 {
+                        return;
+                    }
+                }
+            }
+        })
+        .await;
+        let ws = server.stream();
+        let reconnect =
+            ReconnectConfig::new(3, Duration::from_millis(50), Duration::from_millis(50));
+        let manager = StreamManager::new(ws)
+            .with_reconnect(reconnect)
+            .with_max_args_length(20);
+
+        let mut first = manager
+            .subscribe(Category::Linear, "tickers.BTCUSDT")
+            .await
+            .unwrap();
+        let mut second = manager
+            .subscribe(Category::Linear, "tickers.BTCUSDT")
+            .await
+            .unwrap();
+        // Both topics do not fit in 20 characters, so a second connection is opened.
+        let mut other = manager
+            .subscribe(Category::Linear, "tickers.ETHUSDT")
+            .await
+            .unwrap();
+        assert_eq!(manager.connection_count().await, 2);
+
+        let event = other.recv().await.unwrap();
+        assert_eq!(event.topic(), Some("tickers.ETHUSDT"));
+        for _ in 0..2 {
+            let event = first.recv().await.unwrap();
+            assert_eq!(event.topic(), Some("tickers.BTCUSDT"));
+        }
+        assert_eq!(
+            second.recv().await.unwrap().topic(),
+            Some("tickers.BTCUSDT")
+        );
+
+        // The dropped connection resubscribed to its topic on a new connection.
+        let mut seen = Vec::new();
+        while seen.len() < 3 {
+            seen.push(subscribed.recv().await.unwrap());
+        }
+        let btc: Vec<_> = seen
+            .iter()
+            .filter(|(_, topic)| topic == "tickers.BTCUSDT")
+            .map(|(id, _)| *id)
+            .collect();
+        assert_eq!(btc.len(), 2);
+        assert_ne!(btc[0], btc[1]);
+
+        manager.shutdown().await.unwrap();
+        assert!(first.recv().await.is_none());
+        assert!(other.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_server_ping_and_close() {
+        use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
+        let mut server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-4").await;
+            let ticker = r#"{"topic":"tickers.BTCUSDT","ts":1,"type":"snapshot","cs":1,"data":{}}"#;
+            socket
+                .send(Message::Binary(ticker.as_bytes().to_vec()))
+                .await
+                .unwrap();
+            socket.send(Message::Ping(b"hi".to_vec())).await.unwrap();
+            let pong = loop {
+                match socket.next().await {
+                    Some(Ok(Message::Pong(payload))) => break payload,
+                    Some(Ok(_)) => continue,
+                    _ => panic!("no pong"),
+                }
+            };
+            let frame = CloseFrame {
+                code: CloseCode::Away,
+                reason: "maintenance".into(),
+            };
+            socket.close(Some(frame)).await.unwrap();
+            pong
+        })
+        .await;
+        let ws = server.stream();
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let frames = Arc::new(AtomicUsize::new(0));
+        let counter = frames.clone();
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, None, move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+        // The binary ticker frame reached the handler.
+        assert_eq!(frames.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::ConnectionClosed(ref reason)) if reason == "maintenance"
+        ));
+        assert_eq!(server.result().await, b"hi".to_vec());
+    }
+
+    #[test]
+    fn test_topic_builders() {
+        use bybit::model::Topic;
+        assert_eq!(
+            Topic::orderbook(50, "btcusdt").unwrap().to_string(),
+            "orderbook.50.BTCUSDT"
+        );
+        assert!(Topic::orderbook(40, "BTCUSDT").is_err());
+        assert_eq!(
+            Topic::kline("D", "ETHUSDT").unwrap().to_string(),
+            "kline.D.ETHUSDT"
+        );
+        assert!(Topic::kline("2", "ETHUSDT").is_err());
+        assert_eq!(
+            Topic::public_trade("BTCUSDT").to_string(),
+            "publicTrade.BTCUSDT"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_symbol_list_normalized() {
+        let ws: Stream = Bybit::new(None, None);
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let result = ws.ws_tickers(vec![], Category::Linear, sender).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let result = ws
+            .ws_trades(vec!["BTCUSDT", " "], Category::Linear, sender)
+            .await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+
+        let mut server = MockServer::start(|_, mut socket| async move {
+            let request = ack_next(&mut socket, "conn-10").await.unwrap();
+            socket.close(None).await.ok();
+            request["args"].clone()
+        })
+        .await;
+        let ws = server.stream();
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let subs = vec!["BTCUSDT", " btcusdt", "ETHUSDT", "BTCUSDT"];
+        let _ = ws.ws_tickers(subs, Category::Linear, sender).await;
+        assert_eq!(
+            server.result().await,
+            serde_json::json!(["tickers.BTCUSDT", "tickers.ETHUSDT"])
+        );
+    }
+
+    #[test]
+    fn test_liquidation_events() {
+        let single = r#"{"topic":"liquidation.BTCUSDT","type":"snapshot","ts":1673251091822,"data":{"price":"25000.5","side":"Buy","size":"0.003","symbol":"BTCUSDT","updatedTime":1673251091822}}"#;
+        match serde_json::from_str(single).unwrap() {
+            WebsocketEvents::LiquidationEvent(event) => assert_eq!(event.data.size, 0.003),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        let all = r#"{"topic":"allLiquidation.ROSEUSDT","type":"snapshot","ts":1739502303204,"data":[{"T":1739502302929,"s":"ROSEUSDT","S":"Sell","v":"20000","p":"0.04499"}]}"#;
+        match serde_json::from_str(all).unwrap() {
+            WebsocketEvents::AllLiquidationEvent(event) => {
+                assert_eq!(event.data[0].side, "Sell");
+                assert_eq!(event.data[0].price, 0.04499);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(
+            bybit::model::Topic::all_liquidation("roseusdt").to_string(),
+            "allLiquidation.ROSEUSDT"
+        );
+    }
+
+    #[test]
+    fn test_closed_klines() {
+        let msg = r#"{"topic":"kline.5.BTCUSDT","data":[{"start":1672324800000,"end":1672325099999,"interval":"5","open":"16649.5","close":"16677","high":"16677","low":"16608","volume":"2.081","turnover":"34666.4005","confirm":true,"timestamp":1672324988882},{"start":1672325100000,"end":1672325399999,"interval":"5","open":"16677","close":"16680","high":"16681","low":"16676","volume":"0.1","turnover":"1667.8","confirm":false,"timestamp":1672325101000}],"ts":1672324988882,"type":"snapshot"}"#;
+        let WebsocketEvents::KlineEvent(kline) = serde_json::from_str(msg).unwrap() else {
+            panic!("expected a kline event");
+        };
+        let closed: Vec<_> = kline.closed().collect();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].start, 1672324800000);
+    }
+
+    #[test]
+    fn test_insurance_event() {
+        let msg = r#"{"topic":"insurance.USDT","type":"delta","ts":1747722930000,"data":[{"coin":"USDT","symbols":"GRIFFAINUSDT,HYPEUSDT","balance":"25193.6646517","updateTime":"1747722930000"}]}"#;
+        let WebsocketEvents::InsuranceEvent(insurance) = serde_json::from_str(msg).unwrap() else {
+            panic!("expected an insurance event");
+        };
+        assert_eq!(insurance.data[0].balance, 25193.6646517);
+        assert_eq!(insurance.data[0].update_time, 1747722930000);
+        assert_eq!(Topic::insurance("Inverse").to_string(), "insurance.inverse");
+        assert_eq!(Topic::insurance("usdc").to_string(), "insurance.USDC");
+
+        let rpi =
+            r#"{"topic":"orderbook.rpi.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT"}}"#;
+        assert!(matches!(
+            serde_json::from_str(rpi).unwrap(),
+            WebsocketEvents::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn test_option_greeks() {
+        let msg = r#"{"id":"tickers.BTC-6JAN23-17500-C-2480334983-1672917511074","topic":"tickers.BTC-6JAN23-17500-C","ts":1672917511074,"data":{"symbol":"BTC-6JAN23-17500-C","bidPrice":"0","bidSize":"0","bidIv":"0","askPrice":"10","askSize":"5.1","askIv":"0.514","lastPrice":"10","highPrice24h":"25","lowPrice24h":"5","markPrice":"7.86976724","indexPrice":"16823.73","markPriceIv":"0.4896","underlyingPrice":"16815.1","openInterest":"6.35","turnover24h":"2225.73","volume24h":"0.13","totalVolume":"4","totalTurnover":"4","delta":"0.01999678","gamma":"0.00003539","vega":"1.27636808","theta":"-4.54435269","predictedDeliveryPrice":"0","change24h":"-0.33333334"},"type":"snapshot"}"#;
+        let WebsocketEvents::TickerEvent(WsTicker {
+            data: Tickers::Option(ticker),
+            ts,
+            ..
+        }) = serde_json::from_str(msg).unwrap()
+        else {
+            panic!("expected an option ticker");
+        };
+        let greeks = ticker.greeks(ts).unwrap();
+        assert_eq!(greeks.symbol, "BTC-6JAN23-17500-C");
+        assert_eq!(greeks.delta, 0.01999678);
+        assert_eq!(greeks.theta, -4.54435269);
+        assert_eq!(greeks.mark_price_iv, 0.4896);
+        assert_eq!(greeks.ts, 1672917511074);
+    }
+
+    #[test]
+    fn test_event_routing() {
+        let samples = [
+            (
+                "OrderBookEvent",
+                r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{"s":"BTCUSDT","b":[["16493.50","0.006"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724},"cts":1672304484976}"#,
+            ),
+            (
+                "TradeEvent",
+                r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,"data":[{"T":1672304486865,"s":"BTCUSDT","S":"Buy","v":"0.001","p":"16578.50","L":"PlusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}]}"#,
+            ),
+            (
+                "TickerEvent",
+                r#"{"topic":"tickers.BTCUSDT","type":"snapshot","data":{"symbol":"BTCUSDT","tickDirection":"PlusTick","price24hPcnt":"0.017103","lastPrice":"17216.00","prevPrice24h":"16926.50","highPrice24h":"17281.50","lowPrice24h":"16915.00","prevPrice1h":"17238.00","markPrice":"17217.33","indexPrice":"17227.36","openInterest":"68744.761","openInterestValue":"1183601235.91","turnover24h":"1570383121.943499","volume24h":"91705.276","nextFundingTime":"1673280000000","fundingRate":"-0.000212","bid1Price":"17215.50","bid1Size":"84.489","ask1Price":"17216.00","ask1Size":"83.020"},"cs":24987956059,"ts":1673272861686}"#,
+            ),
+            (
+                "FastExecEvent",
+                r#"{"topic":"execution.fast","creationTime":1716800399338,"data":[{"category":"linear","symbol":"ICPUSDT","execId":"3510f361-0add-5c7b-a2e7-9679810944fc","execPrice":"12.015","execQty":"3000","orderId":"443d63fa-b4c3-4297-b7b1-23bca88b04dc","isMaker":false,"orderLinkId":"test-00001","side":"Sell","execTime":"1716800399334","seq":34771365464}]}"#,
+            ),
+            (
+                "ExecutionEvent",
+                r#"{"id":"592324803b2785-26fa-4214-9963-bdd4727f07be","topic":"execution","creationTime":1672364174455,"data":[{"category":"linear","symbol":"XRPUSDT","execFee":"0.005061","execId":"7e2ae69c-4edf-5800-a352-893d52b446aa","execPrice":"0.3374","execQty":"25","execType":"Trade","execValue":"8.435","isMaker":false,"feeRate":"0.0006","tradeIv":"","markIv":"","blockTradeId":"","markPrice":"0.3391","indexPrice":"","underlyingPrice":"","leavesQty":"0","orderId":"f6e324ff-99c2-4e89-9739-3086e47f9381","orderLinkId":"","orderPrice":"0.3207","orderQty":"25","orderType":"Market","stopOrderType":"UNKNOWN","side":"Sell","execTime":"1672364174443","isLeverage":"0","closedSize":"","seq":4688002127}]}"#,
+            ),
+            (
+                "PositionEvent",
+                r#"{"id":"1003076014fb7eedb-c7e6-45d6-a8c1-270f0169171a","topic":"position","creationTime":1697682317044,"data":[{"positionIdx":2,"tradeMode":0,"riskId":1,"riskLimitValue":"2000000","symbol":"BTCUSDT","side":"","size":"0","entryPrice":"0","leverage":"10","positionValue":"0","positionBalance":"0","markPrice":"28184.5","positionIM":"0","positionMM":"0","takeProfit":"0","stopLoss":"0","trailingStop":"0","unrealisedPnl":"0","cumRealisedPnl":"-25.06579337","createdTime":"1694402496913","updatedTime":"1697682317038","tpslMode":"Full","liqPrice":"0","bustPrice":"","category":"linear","positionStatus":"Normal","adlRankIndicator":0,"autoAddMargin":0,"leverageSysUpdatedTime":"","mmrSysUpdatedTime":"","seq":8327597863,"isReduceOnly":false}]}"#,
+            ),
+            (
+                "OrderEvent",
+                r#"{"id":"5923240c6880ab-c59f-420b-9adb-3639adc9dd90","topic":"order","creationTime":1672364262474,"data":[{"symbol":"ETH-30DEC22-1400-C","orderId":"5cf98598-39a7-459e-97bf-76ca765ee020","side":"Sell","orderType":"Market","cancelType":"UNKNOWN","price":"72.5","qty":"1","orderIv":"","timeInForce":"IOC","orderStatus":"Filled","orderLinkId":"","lastPriceOnCreated":"","reduceOnly":false,"leavesQty":"","leavesValue":"","cumExecQty":"1","cumExecValue":"75","avgPrice":"75","blockTradeId":"","positionIdx":0,"cumExecFee":"0.358635","createdTime":"1672364262444","updatedTime":"1672364262457","rejectReason":"EC_NoError","stopOrderType":"","tpslMode":"","triggerPrice":"","takeProfit":"","stopLoss":"","tpTriggerBy":"","slTriggerBy":"","tpLimitPrice":"","slLimitPrice":"","triggerDirection":0,"triggerBy":"","closeOnTrigger":false,"category":"option","placeType":"price","smpType":"None","smpGroup":0,"smpOrderId":"","feeCurrency":""}]}"#,
+            ),
+            (
+                "Wallet",
+                r#"{"id":"592324d2bce751-ad38-48eb-8f42-4671d1fb4d4e","topic":"wallet","creationTime":1700034722104,"data":[{"accountIMRate":"0","accountMMRate":"0","totalEquity":"10262.91335023","totalWalletBalance":"9684.46297164","totalMarginBalance":"9684.46297164","totalAvailableBalance":"9556.6056555","totalPerpUPL":"0","totalInitialMargin":"0","totalMaintenanceMargin":"0","coin":[{"coin":"BTC","equity":"0.00102964","usdValue":"36.70759517","walletBalance":"0.00102964","availableToWithdraw":"0.00102964","availableToBorrow":"","borrowAmount":"0","accruedInterest":"0","totalOrderIM":"","totalPositionIM":"","totalPositionMM":"","unrealisedPnl":"0","cumRealisedPnl":"-0.00000973","bonus":"0","collateralSwitch":true,"marginCollateral":true,"locked":"0","spotHedgingQty":"0.01592413"}],"accountLTV":"0","accountType":"UNIFIED"}]}"#,
+            ),
+            (
+                "GreeksEvent",
+                r#"{"id":"592324fa945a30-2603-49a5-b865-21668c29f2a6","topic":"greeks","creationTime":1672364262482,"data":[{"baseCoin":"ETH","totalDelta":"0.06999986","totalGamma":"-0.00000001","totalVega":"-0.00000024","totalTheta":"0.00001314"}]}"#,
+            ),
+            (
+                "TradeStream",
+                r#"{"reqId":"test-005","retCode":0,"retMsg":"OK","op":"order.create","data":{"orderId":"1321003749386327552","orderLinkId":"spot-test-postonly"},"header":{"X-Bapi-Limit":"20","X-Bapi-Limit-Status":"19","X-Bapi-Limit-Reset-Timestamp":"1711001595207","Traceid":"df8fe9ee8a1b4d5eb5ed1c91edb5e1d2","Timenow":"1711001595209"},"connId":"cnmrqidbce92iq6qc5i0-c"}"#,
+            ),
+        ];
+        for (expected, sample) in samples {
+            let event: WebsocketEvents = serde_json::from_str(sample).unwrap();
+            assert!(
+                event.time().is_some(),
+                "{} sample has no timestamp",
+                expected
+            );
+            let variant = match event {
+                WebsocketEvents::OrderBookEvent(_) => "OrderBookEvent",
+                WebsocketEvents::TradeEvent(_) => "TradeEvent",
+                WebsocketEvents::TickerEvent(_) => "TickerEvent",
+                WebsocketEvents::FastExecEvent(_) => "FastExecEvent",
+                WebsocketEvents::ExecutionEvent(_) => "ExecutionEvent",
+                WebsocketEvents::PositionEvent(_) => "PositionEvent",
+                WebsocketEvents::OrderEvent(_) => "OrderEvent",
+                WebsocketEvents::Wallet(_) => "Wallet",
+                WebsocketEvents::GreeksEvent(_) => "GreeksEvent",
+                WebsocketEvents::TradeStream(_) => "TradeStream",
+                other => panic!("{} sample decoded as {:?}", expected, other),
+            };
+            assert_eq!(variant, expected);
+        }
+
+        // A trade payload on a ticker topic is not mistaken for a trade.
+        let mislabelled = r#"{"topic":"tickers.BTCUSDT","type":"snapshot","ts":1,"data":[{"T":1,"s":"BTCUSDT","S":"Buy","v":"1","p":"1","L":"PlusTick","i":"x","BT":false}]}"#;
+        assert!(matches!(
+            serde_json::from_str(mislabelled).unwrap(),
+            WebsocketEvents::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn test_lt_klines() {
+        let msg = r#"{"topic":"kline_lt.5.EOS3LUSDT","data":[{"start":1672325100000,"end":1672325399999,"interval":"5","open":"0.416039541212402799","close":"0.41477848043290448","high":"0.416039541212402799","low":"0.409734237314911206","confirm":false,"timestamp":1672325322393}],"ts":1672325322393,"type":"snapshot"}"#;
+        let WebsocketEvents::LtKlineEvent(kline) = serde_json::from_str(msg).unwrap() else {
+            panic!("expected a leveraged token kline event");
+        };
+        assert_eq!(kline.data[0].interval, "5");
+        assert_eq!(kline.closed().count(), 0);
+        assert_eq!(
+            Topic::lt_kline("5", "eos3lusdt").unwrap().to_string(),
+            "kline_lt.5.EOS3LUSDT"
+        );
+        assert!(Topic::lt_kline("7", "EOS3LUSDT").is_err());
+    }
+
+    #[test]
+    fn test_subscription_chunking() {
+        let topics: Vec<String> = (0..23).map(|i| format!("tickers.COIN{}USDT", i)).collect();
+        let request = Subscription::new(
+            SubscriptionOp::Subscribe,
+            topics.iter().map(String::as_str).collect(),
+        );
+        let frames = Stream::build_subscriptions(request);
+        assert_eq!(frames.len(), 3);
+        let last: serde_json::Value = serde_json::from_str(&frames[2]).unwrap();
+        assert_eq!(last["args"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_auth_ack() {
+        use bybit::errors::BybitError;
+        let ok = r#"{"success":true,"ret_msg":"","op":"auth","conn_id":"c1"}"#;
+        assert!(matches!(Stream::parse_auth_ack(ok), Some(Ok(()))));
+        let expired = r#"{"success":false,"ret_msg":"Params Error: request expired","op":"auth","conn_id":"c1"}"#;
+        assert!(matches!(
+            Stream::parse_auth_ack(expired),
+            Some(Err(BybitError::AuthFailed { .. }))
+        ));
+        let trade = r#"{"retCode":10004,"retMsg":"Invalid sign","op":"auth","connId":"c2"}"#;
+        assert!(matches!(
+            Stream::parse_auth_ack(trade),
+            Some(Err(BybitError::AuthFailed { .. }))
+        ));
+        let ack = r#"{"success":true,"ret_msg":"","op":"subscribe","conn_id":"c1"}"#;
+        assert!(Stream::parse_auth_ack(ack).is_none());
+    }
+
+    #[test]
+    fn test_event_metadata() {
+        let book = r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":1672304484978,"data":{"s":"BTCUSDT","b":[],"a":[],"u":18521289,"seq":7961638725},"cts":1672304484976}"#;
+        let event: WebsocketEvents = serde_json::from_str(book).unwrap();
+        assert_eq!(event.topic(), Some("orderbook.50.BTCUSDT"));
+        assert_eq!(event.timestamp(), Some(1672304484978));
+        assert_eq!(
+            event.time().unwrap().to_rfc3339(),
+            "2022-12-29T09:01:24.978+00:00"
+        );
+
+        let unknown = r#"{"topic":"spread.orderbook.BTCUSDT","ts":1700000000000,"data":{}}"#;
+        let event: WebsocketEvents = serde_json::from_str(unknown).unwrap();
+        assert!(matches!(event, WebsocketEvents::Unknown(_)));
+        assert_eq!(event.topic(), Some("spread.orderbook.BTCUSDT"));
+        assert_eq!(event.timestamp(), Some(1700000000000));
+    }
+
+    #[tokio::test]
+    async fn test_ping_latency() {
+        let server = MockServer::start(|_, mut socket| async move {
+            let Some(Ok(Message::Text(frame))) = socket.next().await else {
+                return;
+            };
+            let request: serde_json::Value = serde_json::from_str(&frame).unwrap();
+            // A pong for some other ping is skipped.
+            let stale = r#"{"req_id":"stale","op":"pong","args":["1"],"conn_id":"conn-9"}"#;
+            socket.send(Message::Text(stale.to_string())).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            let pong = serde_json::json!({
+                "req_id": request["req_id"], "op": "pong",
+                "args": ["1675418560633"], "conn_id": "conn-9",
+            });
+            socket.send(Message::Text(pong.to_string())).await.unwrap();
+        })
+        .await;
+        let ws = server.stream();
+        let pong = ws.ws_ping(false).await.unwrap();
+        assert_ne!(pong.data.req_id.as_deref(), Some("stale"));
+        assert_eq!(pong.data.server_time(), Some(1675418560633));
+        assert!(pong.latency >= std::time::Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout() {
+        use std::time::Duration;
+        let mut server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-11").await;
+            // Half-open: frames are read but never answered.
+            let mut pings = 0;
+            while let Some(Ok(Message::Text(frame))) = socket.next().await {
+                if frame.contains(r#""op":"ping""#) {
+                    pings += 1;
+                }
+            }
+            pings
+        })
+        .await;
+        let ws = server.stream();
+        let ws = ws.with_read_timeout(Duration::from_millis(100));
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let started = Instant::now();
+        let result = ws
+            .ws_subscribe(request, Category::Linear, None, None, |_| Ok(()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::ConnectionClosed(_))
+        ));
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(200) && elapsed < Duration::from_secs(5));
+        assert!(server.result().await >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_collect_events() {
+        let mut server = MockServer::start(|_, mut socket| async move {
+            ack_next(&mut socket, "conn-12").await;
+            let unknown = r#"{"topic":"spread.orderbook.BTCUSDT","ts":1,"data":{}}"#;
+            socket
+                .send(Message::Text(unknown.to_string()))
+                .await
+                .unwrap();
+            for ts in 1..=3 {
+                let trade = format!(
+                    r#"{{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":{},"data":[{{"T":1,"s":"BTCUSDT","S":"Buy","v":"0.001","p":"16578.50","L":"PlusTick","i":"x","BT":false}}]}}"#,
+                    ts
+                );
+                socket.send(Message::Text(trade)).await.unwrap();
+            }
+            drain(&mut socket).await
+        })
+        .await;
+        let ws = server.stream();
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["publicTrade.BTCUSDT"]);
+        let events = ws.ws_collect(request, Category::Linear, 2).await.unwrap();
+        let timestamps: Vec<_> = events.iter().map(|event| event.timestamp()).collect();
+        assert_eq!(timestamps, vec![Some(1), Some(2)]);
+        assert!(server.result().await);
+
+        // The stream ends before enough events arrive.
+        let ws = mock_endpoint(
+            r#"{"success":true,"ret_msg":"","conn_id":"conn-13","req_id":"1","op":"subscribe"}"#,
+        )
+        .await;
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["publicTrade.BTCUSDT"]);
+        assert!(ws.ws_collect(request, Category::Linear, 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_keepalive() {
+        let mut server = MockServer::start(|_, mut socket| async move {
+            let mut pings = 0;
+            while let Some(request) = ack_next(&mut socket, "conn-8").await {
+                if request["op"] == "ping" {
+                    pings += 1;
+                }
+            }
+            pings
+        })
+        .await;
+        let ws = server.stream();
+        assert!(ws.keepalive().await.is_err());
+
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let handle = ws.ws_subscribe_spawn(request, Category::Linear, None, None, |_| Ok(()));
+        while handle.stream().conn_id().is_none() {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        handle.stream().keepalive().await.unwrap();
+        handle.stream().keepalive().await.unwrap();
+        handle.stop().await.unwrap();
+        // Both pings went over the subscription's own connection.
+        assert_eq!(server.result().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_zero_ping_interval() {
+        let mut server = MockServer::start(|_, mut socket| async move {
+            let mut pings = 0;
+            while let Some(request) = ack_next(&mut socket, "conn-9").await {
+                if request["op"] == "ping" {
+                    pings += 1;
+                }
+            }
+            pings
+        })
+        .await;
+        // A zero interval is raised to one second rather than panicking the event loop.
+        let request = Subscription::new(SubscriptionOp::Subscribe, vec!["tickers.BTCUSDT"]);
+        let handle = server.stream().ws_subscribe_spawn(
+            request,
+            Category::Linear,
+            Some(0),
+            None,
+            |_| Ok(()),
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        handle.stop().await.unwrap();
+        assert!(server.result().await >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_without_subscription() {
+        let ws: Stream = Bybit::new(None, None);
+        assert!(ws.ws_unsubscribe(vec!["tickers.BTCUSDT"]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_spot_position_stream_rejected() {
+        let ws: Stream = Bybit::new(None, None);
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let result = ws.ws_position(Some(Category::Spot), sender).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_partially_filled_order() {
+        let sample = r#"{"id":"5923240c6880ab-c59f-420b-9adb-3639adc9dd91","topic":"order","creationTime":1672364262474,"data":[{"symbol":"BTCUSDT","orderId":"1c9d2e8b-4a0f-4f7c-9c43-05c3a4a1b3d2","side":"Buy","orderType":"Limit","cancelType":"UNKNOWN","price":"30000","qty":"0.4","orderIv":"","timeInForce":"GTC","orderStatus":"PartiallyFilled","orderLinkId":"","lastPriceOnCreated":"30010","reduceOnly":false,"leavesQty":"0.3","leavesValue":"9000","cumExecQty":"0.1","cumExecValue":"3000","avgPrice":"30000","blockTradeId":"","positionIdx":0,"cumExecFee":"1.8","createdTime":"1672364262444","updatedTime":"1672364262457","rejectReason":"EC_NoError","stopOrderType":"","tpslMode":"UNKNOWN","triggerPrice":"","takeProfit":"","stopLoss":"","tpTriggerBy":"","slTriggerBy":"","tpLimitPrice":"","slLimitPrice":"","triggerDirection":0,"triggerBy":"","closeOnTrigger":false,"category":"linear","placeType":"","smpType":"None","smpGroup":0,"smpOrderId":"","feeCurrency":""}]}"#;
+        let WebsocketEvents::OrderEvent(event) = serde_json::from_str(sample).unwrap() else {
+            panic!("not an order event");
+        };
+        let order = &event.data[0];
+        assert_eq!(order.state(), Some(OrderState::PartiallyFilled));
+        assert!(!order.is_fully_filled());
+        assert_eq!(order.cum_exec_qty, Some(0.1));
+        assert_eq!(order.leaves_qty, Some(0.3));
+        assert_eq!(order.cum_exec_value, Some(3000.0));
+        assert_eq!(order.avg_price, Some(30000.0));
+        assert!((order.fill_ratio() - 0.25).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_fast_executions_and_portfolio_greeks() {
+        let ws: Stream = Bybit::new(None, None);
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let result = ws.ws_fast_executions(Some(Category::Option), sender).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+
+        let fast = r#"{"topic":"execution.fast.spot","creationTime":1716800399338,"data":[{"category":"spot","symbol":"BTCUSDT","execId":"2100000000055286283","execPrice":"67000","execQty":"0.001","orderId":"1708906800220279552","isMaker":true,"orderLinkId":"","side":"Buy","execTime":"1716800399334","seq":34771365465}]}"#;
+        let WebsocketEvents::FastExecEvent(execution) = serde_json::from_str(fast).unwrap() else {
+            panic!("expected a fast execution event");
+        };
+        assert!(execution.data[0].is_maker);
+
+        let greeks = r#"{"id":"592324fa945a30-2603-49a5-b865-21668c29f2a6","topic":"greeks","creationTime":1672364262482,"data":[{"baseCoin":"ETH","totalDelta":"0.06999986","totalGamma":"-0.00000001","totalVega":"-0.00000024","totalTheta":"0.00001314"}]}"#;
+        let WebsocketEvents::GreeksEvent(greeks) = serde_json::from_str(greeks).unwrap() else {
+            panic!("expected a greeks event");
+        };
+        assert_eq!(greeks.data[0].base_coin, "ETH");
+        assert_eq!(greeks.data[0].total_delta, 0.06999986);
+        assert_eq!(greeks.data[0].total_gamma, -0.00000001);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_shapes() {
+        const UNIFIED: &str = r#"{"id":"592324d2bce751-ad38-48eb-8f42-4671d1fb4d4e","topic":"wallet","creationTime":1700034722104,"data":[{"accountIMRate":"0","accountMMRate":"0","totalEquity":"10262.91335023","totalWalletBalance":"9684.46297164","totalMarginBalance":"9684.46297164","totalAvailableBalance":"9556.6056555","totalPerpUPL":"0","totalInitialMargin":"0","totalMaintenanceMargin":"0","coin":[{"coin":"BTC","equity":"0.00102964","usdValue":"36.70759517","walletBalance":"0.00102964","availableToWithdraw":"0.00102964","availableToBorrow":"","borrowAmount":"0","accruedInterest":"0","totalOrderIM":"","totalPositionIM":"","totalPositionMM":"","unrealisedPnl":"0","cumRealisedPnl":"-0.00000973","bonus":"0","collateralSwitch":true,"marginCollateral":true,"locked":"0","spotHedgingQty":"0.01592413"}],"accountLTV":"0","accountType":"UNIFIED"}]}"#;
+        // Classic accounts leave the account-wide fields empty and send fewer coin fields.
+        const CLASSIC: &str = r#"{"id":"5923242c464be9-25ca-483d-a743-c60101fc656f","topic":"wallet","creationTime":1672364262482,"data":[{"accountIMRate":"","accountMMRate":"","totalEquity":"","totalWalletBalance":"","totalMarginBalance":"","totalAvailableBalance":"","totalPerpUPL":"","totalInitialMargin":"","totalMaintenanceMargin":"","coin":[{"coin":"USDT","equity":"3662.81038535","usdValue":"","walletBalance":"3662.81038535","availableToWithdraw":"3658.00393046","availableToBorrow":"","borrowAmount":"","accruedInterest":"","totalOrderIM":"0","totalPositionIM":"4.80645489","totalPositionMM":"0.31180009","unrealisedPnl":"-0.0504","cumRealisedPnl":"-62.97050089","bonus":"0"}],"accountLTV":"","accountType":"CONTRACT"}]}"#;
+
+        for sample in [UNIFIED, CLASSIC] {
+            let event: WebsocketEvents = serde_json::from_str(sample).unwrap();
+            assert!(matches!(event, WebsocketEvents::Wallet(_)));
+        }
+
+        let server = MockServer::start(|_, mut socket| async move {
+            // The auth frame, then the subscribe request.
+            ack_next(&mut socket, "conn-3").await;
+            ack_next(&mut socket, "conn-3").await;
+            for sample in [UNIFIED, CLASSIC] {
+                socket.send(Message::Text(sample.into())).await.unwrap();
+            }
+            socket.close(None).await.ok();
+        })
+        .await;
+        let ws: Stream = Bybit::new_with_config(
+            &server.config,
+            Some("XXXXXXXXXX".into()),
+            Some("secret".into()),
+        );
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        // The mock closes the connection after the samples, which ends the subscription.
+        let result = ws.ws_wallet(sender).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::ConnectionClosed(_))
+        ));
+
+        let unified = receiver.recv().await.unwrap();
+        assert!(unified.is_unified());
+        assert_eq!(unified.total_equity, Some(10262.91335023));
+        assert_eq!(unified.coin[0].collateral_switch, Some(true));
+        assert_eq!(unified.coin[0].available_to_borrow, None);
+
+        let classic = receiver.recv().await.unwrap();
+        assert!(!classic.is_unified());
+        assert_eq!(classic.total_equity, None);
+        assert_eq!(classic.account_ltv, None);
+        let usdt = &classic.coin[0];
+        assert_eq!(usdt.wallet_balance, Some(3662.81038535));
+        assert_eq!(usdt.collateral_switch, None);
+        assert_eq!(usdt.locked, None);
+    }
+
+    #[tokio::test]
+    async fn test_session() {
+        use bybit::api::{Public, WebsocketAPI};
+        let server = MockServer::start(|_, mut socket| async move {
+            for op in ["subscribe", "unsubscribe"] {
+                let Some(Ok(Message::Text(request))) = socket.next().await else {
+                    return;
+                };
+                let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+                assert_eq!(request["op"], op);
+                // An event racing the ack is kept for the next poll.
+                let ticker = r#"{"topic":"tickers.BTCUSDT","type":"snapshot","ts":1,"cs":1,"data":{"symbol":"BTCUSDT"}}"#;
+                socket.send(Message::Text(ticker.into())).await.unwrap();
+                let ack = format!(
+                    r#"{{"success":true,"ret_msg":"","conn_id":"conn-9","req_id":{},"op":"{}"}}"#,
+                    request["req_id"], op
+                );
+                socket.send(Message::Text(ack)).await.unwrap();
+            }
+            socket.close(None).await.ok();
+        })
+        .await;
+        let ws = server.stream();
+        let mut session = ws
+            .connect(WebsocketAPI::Public(Public::Linear))
+            .await
+            .unwrap();
+        session
+            .subscribe(vec!["tickers.BTCUSDT", "tickers.BTCUSDT"])
+            .await
+            .unwrap();
+        assert_eq!(session.topics(), ["tickers.BTCUSDT"]);
+        assert_eq!(session.conn_id(), Some("conn-9"));
+        let event = session.poll().await.unwrap().unwrap();
+        assert_eq!(event.topic(), Some("tickers.BTCUSDT"));
+        session.unsubscribe(vec!["tickers.BTCUSDT"]).await.unwrap();
+        assert!(session.topics().is_empty());
+        assert!(session.poll().await.unwrap().is_some());
+        assert!(session.poll().await.unwrap().is_none());
+
+        let result = ws.connect(WebsocketAPI::TradeStream).await;
+        assert!(matches!(
+            result,
+            Err(bybit::errors::BybitError::InvalidParams(_))
+        ));
+    }
+
+    /// A trade stream that accepts the auth frame and answers the next request with
+    /// `ret_code`, echoing its `reqId`.
+    async fn mock_trade_endpoint(ret_code: i32) -> Stream {
+        let server = MockServer::start(move |_, mut socket| async move {
+            socket.next().await;
+            let auth = r#"{"retCode":0,"retMsg":"OK","op":"auth","connId":"c1"}"#;
+            socket.send(Message::Text(auth.to_string())).await.unwrap();
+            let Some(Ok(Message::Text(request))) = socket.next().await else {
+                return;
+            };
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["op"], "order.create");
+            let reply = serde_json::json!({
+                "reqId": request["reqId"],
+                "retCode": ret_code,
+                "retMsg": if ret_code == 0 { "OK" } else { "Insufficient balance" },
+                "op": "order.create",
+                "data": {"orderId": "1", "orderLinkId": "link-1"},
+                "header": {
+                    "X-Bapi-Limit": "10",
+                    "X-Bapi-Limit-Status": "9",
+                    "X-Bapi-Limit-Reset-Timestamp": "1",
+                    "Traceid": "t",
+                    "Timenow": "1"
+                },
+                "connId": "c1"
+            });
+            // An unrelated frame first, which must not be taken as the response.
+            let other = r#"{"reqId":"other","retCode":0,"retMsg":"OK","op":"order.create"}"#;
+            socket.send(Message::Text(other.to_string())).await.unwrap();
+            socket.send(Message::Text(reply.to_string())).await.unwrap();
+        })
+        .await;
+        Bybit::new_with_config(&server.config, Some("key".into()), Some("secret".into()))
+    }
+
+    #[tokio::test]
+    async fn test_ws_place_order() {
+        use bybit::model::{OrderRequest, Side};
+        let order = OrderRequest::market(Category::Linear, "BTCUSDT", Side::Buy, 0.001);
+
+        let ws = mock_trade_endpoint(0).await;
+        let ack = ws.ws_place_order(order.clone()).await.unwrap();
+        assert_eq!(ack.data.order_link_id, "link-1");
+
+        let ws = mock_trade_endpoint(110007).await;
+        let err = ws.ws_place_order(order).await.unwrap_err();
+        assert_eq!(err.ret_code(), Some(110007));
+    }
 }